@@ -0,0 +1,408 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::{fs_utils, paths};
+use crate::error::LauncherError;
+
+/// Keychain service name for account secrets
+const KEYRING_SERVICE: &str = "ALauncher";
+
+pub(crate) const MINECRAFT_PROFILE_URL: &str = "https://api.minecraftservices.com/minecraft/profile";
+
+/// How long a token validity check result is reused
+const TOKEN_CHECK_TTL: Duration = Duration::from_secs(60);
+
+/// Token validity cache keyed by a hash of the token (never the token itself)
+static TOKEN_STATUS_CACHE: OnceLock<Mutex<HashMap<String, (Instant, TokenStatus)>>> = OnceLock::new();
+
+/// Account as seen by the frontend (tokens included)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountInfo {
+    pub id: String,
+    pub username: String,
+    pub uuid: String,
+    /// "microsoft" or "offline"
+    #[serde(default)]
+    pub account_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountList {
+    pub active_account_id: Option<String>,
+    pub accounts: Vec<AccountInfo>,
+}
+
+/// Tokens kept in the OS keychain, keyed by account id
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AccountSecrets {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+}
+
+/// On-disk `accounts.json`. Tokens never go here; older launcher versions stored
+/// them inline, such entries are migrated to the keychain on load.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AccountsFile {
+    #[serde(default)]
+    active_account_id: Option<String>,
+    #[serde(default)]
+    accounts: Vec<AccountInfo>,
+}
+
+fn accounts_file_path() -> Result<PathBuf, String> {
+    paths::app_data_dir()
+        .map(|dir| dir.join("accounts.json"))
+        .ok_or_else(|| "Failed to determine app data directory".to_string())
+}
+
+fn read_accounts_file() -> Result<AccountsFile, String> {
+    let path = accounts_file_path()?;
+    if !path.exists() {
+        return Ok(AccountsFile::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read accounts file: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse accounts file: {}", e))
+}
+
+fn write_accounts_file(file: &AccountsFile) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(file)
+        .map_err(|e| format!("Failed to serialize accounts: {}", e))?;
+    fs_utils::write_atomic(&accounts_file_path()?, &json)
+}
+
+fn keyring_entry(account_id: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, account_id)
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))
+}
+
+fn load_secrets(account_id: &str) -> Result<AccountSecrets, String> {
+    match keyring_entry(account_id)?.get_password() {
+        Ok(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse stored credentials: {}", e)),
+        Err(keyring::Error::NoEntry) => Ok(AccountSecrets::default()),
+        Err(e) => Err(format!("Failed to read keychain: {}", e)),
+    }
+}
+
+fn store_secrets(account_id: &str, secrets: &AccountSecrets) -> Result<(), String> {
+    let entry = keyring_entry(account_id)?;
+
+    if secrets.access_token.is_none() && secrets.refresh_token.is_none() {
+        return match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to clear keychain entry: {}", e)),
+        };
+    }
+
+    let json = serde_json::to_string(secrets)
+        .map_err(|e| format!("Failed to serialize credentials: {}", e))?;
+    entry.set_password(&json)
+        .map_err(|e| format!("Failed to write keychain: {}", e))
+}
+
+/// Account entry for `accounts.json` with the tokens moved to the keychain via `store`
+///
+/// Fails if the keychain is unavailable (e.g. no Secret Service on Linux): tokens are
+/// never written to disk in plaintext.
+fn secure_account(
+    account: &AccountInfo,
+    store: impl FnOnce(&AccountSecrets) -> Result<(), String>,
+) -> Result<AccountInfo, String> {
+    let (stored, secrets) = strip_secrets(account);
+    store(&secrets)
+        .map_err(|e| format!("Failed to store credentials of account {} in the keychain: {}", account.id, e))?;
+    Ok(stored)
+}
+
+fn strip_secrets(account: &AccountInfo) -> (AccountInfo, AccountSecrets) {
+    let secrets = AccountSecrets {
+        access_token: account.access_token.clone(),
+        refresh_token: account.refresh_token.clone(),
+    };
+    let stored = AccountInfo {
+        access_token: None,
+        refresh_token: None,
+        ..account.clone()
+    };
+    (stored, secrets)
+}
+
+/// Load stored accounts, filling tokens from the keychain
+#[tauri::command]
+pub async fn load_accounts() -> Result<AccountList, LauncherError> {
+    Ok(load_account_list()?)
+}
+
+fn load_account_list() -> Result<AccountList, String> {
+    let file = read_accounts_file()?;
+
+    // Миграция: старые версии хранили токены прямо в JSON
+    let has_plaintext_tokens = file.accounts.iter()
+        .any(|a| a.access_token.is_some() || a.refresh_token.is_some());
+    if has_plaintext_tokens {
+        eprintln!("[Accounts] Migrating plaintext tokens to the keychain...");
+        let stored_accounts: Result<Vec<AccountInfo>, String> = file.accounts.iter()
+            .map(|account| secure_account(account, |secrets| store_secrets(&account.id, secrets)))
+            .collect();
+        // Без связки ключей файл не трогаем, миграция повторится при следующей загрузке
+        match stored_accounts {
+            Ok(stored_accounts) => {
+                write_accounts_file(&AccountsFile {
+                    active_account_id: file.active_account_id.clone(),
+                    accounts: stored_accounts,
+                })?;
+                eprintln!("[Accounts] ✅ Migration complete");
+            }
+            Err(e) => eprintln!("[Accounts] ⚠️ Migration postponed: {}", e),
+        }
+    }
+
+    let mut accounts = Vec::with_capacity(file.accounts.len());
+    for account in file.accounts {
+        let secrets = match load_secrets(&account.id) {
+            Ok(secrets) => secrets,
+            Err(e) => {
+                eprintln!("[Accounts] ⚠️ No credentials for account {}: {}", account.id, e);
+                AccountSecrets::default()
+            }
+        };
+        accounts.push(AccountInfo {
+            access_token: secrets.access_token.or(account.access_token),
+            refresh_token: secrets.refresh_token.or(account.refresh_token),
+            ..account
+        });
+    }
+
+    let active_account_id = file.active_account_id
+        .filter(|id| accounts.iter().any(|a| &a.id == id));
+
+    Ok(AccountList {
+        active_account_id,
+        accounts,
+    })
+}
+
+/// Save the account list: metadata to `accounts.json`, tokens to the keychain
+///
+/// Fails without touching `accounts.json` when the keychain is unavailable.
+#[tauri::command]
+pub async fn save_accounts(
+    accounts: Vec<AccountInfo>,
+    active_account_id: Option<String>,
+) -> Result<(), LauncherError> {
+    let ids: HashSet<&str> = accounts.iter().map(|a| a.id.as_str()).collect();
+    if ids.len() != accounts.len() {
+        return Err(LauncherError::invalid_params("Duplicate account ids"));
+    }
+    if let Some(active) = &active_account_id {
+        if !ids.contains(active.as_str()) {
+            return Err(LauncherError::invalid_params(format!("Active account {} is not in the list", active)));
+        }
+    }
+
+    let previous = read_accounts_file().unwrap_or_default();
+
+    let stored_accounts = accounts.iter()
+        .map(|account| secure_account(account, |secrets| store_secrets(&account.id, secrets)))
+        .collect::<Result<_, _>>()?;
+
+    write_accounts_file(&AccountsFile {
+        active_account_id,
+        accounts: stored_accounts,
+    })?;
+
+    // Удаляем токены аккаунтов, которых больше нет в списке
+    for removed in previous.accounts.iter().filter(|a| !ids.contains(a.id.as_str())) {
+        if let Err(e) = store_secrets(&removed.id, &AccountSecrets::default()) {
+            eprintln!("[Accounts] ⚠️ Failed to remove credentials for {}: {}", removed.id, e);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TokenStatus {
+    Valid,
+    Expired,
+    /// Offline account, there is no token to check
+    Offline,
+    /// The check could not be performed (no network, service down)
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountStatus {
+    pub account: AccountInfo,
+    pub active: bool,
+    pub token_status: TokenStatus,
+}
+
+/// List stored accounts with a token validity check against the Minecraft profile endpoint
+///
+/// Checks run concurrently and are cached briefly; without network the status is `Unknown`.
+#[tauri::command]
+pub async fn list_accounts_with_status() -> Result<Vec<AccountStatus>, LauncherError> {
+    let list = load_account_list()?;
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let checks = list.accounts.iter().map(|account| check_token(&client, account));
+    let statuses = futures_util::future::join_all(checks).await;
+
+    Ok(list.accounts.into_iter()
+        .zip(statuses)
+        .map(|(account, token_status)| AccountStatus {
+            active: list.active_account_id.as_deref() == Some(account.id.as_str()),
+            account,
+            token_status,
+        })
+        .collect())
+}
+
+/// Make the given account the active one
+#[tauri::command]
+pub async fn set_active_account(id: String) -> Result<(), LauncherError> {
+    let mut file = read_accounts_file()?;
+    if !file.accounts.iter().any(|a| a.id == id) {
+        return Err(LauncherError::not_found(format!("Account not found: {}", id)));
+    }
+
+    file.active_account_id = Some(id);
+    Ok(write_accounts_file(&file)?)
+}
+
+async fn check_token(client: &reqwest::Client, account: &AccountInfo) -> TokenStatus {
+    if account.account_type == "offline" {
+        return TokenStatus::Offline;
+    }
+
+    let token = match account.access_token.as_deref() {
+        Some(token) if !token.is_empty() => token,
+        _ => return TokenStatus::Expired,
+    };
+
+    let cache_key = hex::encode(Sha256::digest(token.as_bytes()));
+    let cache = TOKEN_STATUS_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Ok(cache) = cache.lock() {
+        if let Some((checked_at, status)) = cache.get(&cache_key) {
+            if checked_at.elapsed() < TOKEN_CHECK_TTL {
+                return *status;
+            }
+        }
+    }
+
+    let status = match client.get(MINECRAFT_PROFILE_URL).bearer_auth(token).send().await {
+        Ok(response) if response.status().is_success() => TokenStatus::Valid,
+        Ok(response) if response.status() == reqwest::StatusCode::UNAUTHORIZED
+            || response.status() == reqwest::StatusCode::FORBIDDEN => TokenStatus::Expired,
+        Ok(response) => {
+            eprintln!("[Accounts] Unexpected profile response for {}: {}", account.id, response.status());
+            TokenStatus::Unknown
+        }
+        Err(e) => {
+            eprintln!("[Accounts] Token check failed for {}: {}", account.id, e);
+            TokenStatus::Unknown
+        }
+    };
+
+    // Неизвестный статус не кэшируем, чтобы повторить проверку, когда сеть вернется
+    if status != TokenStatus::Unknown {
+        if let Ok(mut cache) = cache.lock() {
+            cache.insert(cache_key, (Instant::now(), status));
+        }
+    }
+
+    status
+}
+
+/// Username and UUID for playing without a Microsoft account
+#[derive(Debug, Clone, Serialize)]
+pub struct OfflineProfile {
+    pub username: String,
+    pub uuid: String,
+}
+
+/// Offline profile with the same UUID a vanilla server in offline mode assigns to `username`
+#[tauri::command]
+pub async fn generate_offline_profile(username: String) -> Result<OfflineProfile, LauncherError> {
+    let username = username.trim().to_string();
+    if username.is_empty() {
+        return Err(LauncherError::invalid_params("Username must not be empty"));
+    }
+
+    Ok(OfflineProfile {
+        uuid: offline_uuid(&username),
+        username,
+    })
+}
+
+/// `UUID.nameUUIDFromBytes(("OfflinePlayer:" + name).getBytes(UTF_8))`: MD5-based version 3 UUID
+pub fn offline_uuid(username: &str) -> String {
+    let digest = md5::Md5::digest(format!("OfflinePlayer:{}", username).as_bytes());
+    uuid::Builder::from_md5_bytes(digest.into())
+        .into_uuid()
+        .hyphenated()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account() -> AccountInfo {
+        AccountInfo {
+            id: "acc-1".into(),
+            username: "Steve".into(),
+            uuid: "069a79f4-44e9-4726-a5be-fca90e38aaf5".into(),
+            account_type: "microsoft".into(),
+            access_token: Some("access".into()),
+            refresh_token: Some("refresh".into()),
+            expires_at: Some(1),
+        }
+    }
+
+    #[test]
+    fn tokens_go_to_keychain_when_available() {
+        let mut keychain = None;
+        let stored = secure_account(&account(), |secrets| {
+            keychain = Some((secrets.access_token.clone(), secrets.refresh_token.clone()));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(stored.access_token, None);
+        assert_eq!(stored.refresh_token, None);
+        assert_eq!(stored.username, "Steve");
+        assert_eq!(keychain, Some((Some("access".into()), Some("refresh".into()))));
+    }
+
+    #[test]
+    fn unavailable_keychain_is_an_error() {
+        let error = secure_account(&account(), |_| Err("Platform secure storage failure".into())).unwrap_err();
+        assert!(error.contains("acc-1") && error.contains("keychain"), "{}", error);
+        assert!(!error.contains("access") && !error.contains("refresh"), "{}", error);
+    }
+
+    #[test]
+    fn offline_uuid_matches_vanilla_server() {
+        // UUID.nameUUIDFromBytes("OfflinePlayer:Notch".getBytes(UTF_8))
+        assert_eq!(offline_uuid("Notch"), "b50ad385-829d-3141-a216-7e7d7539ba7f");
+    }
+}
@@ -0,0 +1,308 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use glob::{MatchOptions, Pattern};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use crate::error::LauncherError;
+
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Extract only the zip entries matching `include_globs` (all when empty) and none of `exclude_globs`
+///
+/// Globs are matched against the entry path inside the archive, e.g. `overrides/**`.
+/// Returns the extracted file paths.
+#[tauri::command]
+pub async fn extract_zip_filtered(
+    archive: String,
+    dest_dir: String,
+    include_globs: Vec<String>,
+    exclude_globs: Vec<String>,
+) -> Result<Vec<String>, LauncherError> {
+    let include = compile_globs(&include_globs)?;
+    let exclude = compile_globs(&exclude_globs)?;
+
+    tokio::task::spawn_blocking(move || {
+        extract_zip_matching(Path::new(&archive), Path::new(&dest_dir), |name| {
+            glob_filter_matches(&include, &exclude, name)
+        })
+    })
+    .await
+    .map_err(|e| format!("Extraction task failed: {}", e))?
+    .map_err(LauncherError::from)
+}
+
+/// Extract a whole zip (natives jars, modpacks) into `dest_dir`
+///
+/// Entries that would land outside `dest_dir` are refused. Returns the extracted file paths.
+#[tauri::command]
+pub async fn extract_archive(archive: String, dest_dir: String) -> Result<Vec<String>, LauncherError> {
+    if !Path::new(&archive).is_file() {
+        return Err(LauncherError::not_found(format!("Archive not found: {}", archive)));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        extract_zip_matching(Path::new(&archive), Path::new(&dest_dir), |_| true)
+    })
+    .await
+    .map_err(|e| format!("Extraction task failed: {}", e))?
+    .map_err(LauncherError::from)
+}
+
+fn compile_globs(globs: &[String]) -> Result<Vec<Pattern>, String> {
+    globs.iter()
+        .map(|g| Pattern::new(g).map_err(|e| format!("Invalid glob '{}': {}", g, e)))
+        .collect()
+}
+
+/// Exclude wins over include; no include globs means everything
+fn glob_filter_matches(include: &[Pattern], exclude: &[Pattern], name: &str) -> bool {
+    (include.is_empty() || include.iter().any(|p| p.matches_with(name, GLOB_OPTIONS)))
+        && !exclude.iter().any(|p| p.matches_with(name, GLOB_OPTIONS))
+}
+
+/// Extract entries accepted by `filter`, refusing entries that would escape `dest_dir` (Zip Slip)
+pub fn extract_zip_matching<F>(archive: &Path, dest_dir: &Path, filter: F) -> Result<Vec<String>, String>
+where
+    F: Fn(&str) -> bool,
+{
+    let file = fs::File::open(archive)
+        .map_err(|e| format!("Failed to open archive {:?}: {}", archive, e))?;
+    let mut zip = ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read ZIP archive: {}", e))?;
+
+    fs::create_dir_all(dest_dir)
+        .map_err(|e| format!("Failed to create destination directory: {}", e))?;
+
+    let mut extracted = Vec::new();
+
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)
+            .map_err(|e| format!("Failed to get file {}: {}", i, e))?;
+
+        let name = entry.name().to_string();
+        if !filter(&name) {
+            continue;
+        }
+
+        let relative = match entry.enclosed_name() {
+            Some(path) => path,
+            None => {
+                eprintln!("[Archive] ⚠️ Refusing unsafe entry path: {}", name);
+                continue;
+            }
+        };
+        let out_path = dest_dir.join(relative);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path)
+                .map_err(|e| format!("Failed to create directory {:?}: {}", out_path, e))?;
+            continue;
+        }
+
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+        }
+
+        let mut output = fs::File::create(&out_path)
+            .map_err(|e| format!("Failed to create file {:?}: {}", out_path, e))?;
+        std::io::copy(&mut entry, &mut output)
+            .map_err(|e| format!("Failed to write file {:?}: {}", out_path, e))?;
+
+        // Сохраняем права из архива (исполняемые скрипты, бинарники), без setuid/setgid
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&out_path, fs::Permissions::from_mode(mode & 0o777))
+                .map_err(|e| format!("Failed to set permissions on {:?}: {}", out_path, e))?;
+        }
+
+        extracted.push(out_path.to_string_lossy().to_string());
+    }
+
+    Ok(extracted)
+}
+
+/// Read a single entry from a zip/jar without extracting it; `Ok(None)` if the entry is absent
+pub fn read_zip_entry(archive: &Path, entry_name: &str) -> Result<Option<Vec<u8>>, String> {
+    let file = fs::File::open(archive)
+        .map_err(|e| format!("Failed to open archive {:?}: {}", archive, e))?;
+    let mut zip = ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read ZIP archive: {}", e))?;
+
+    let mut entry = match zip.by_name(entry_name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {} from archive: {}", entry_name, e)),
+    };
+
+    let mut content = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut content)
+        .map_err(|e| format!("Failed to read {} from archive: {}", entry_name, e))?;
+
+    Ok(Some(content))
+}
+
+/// Extract a `.tar.gz` archive; entries with absolute paths or `..` are skipped by `tar`
+pub fn extract_tar_gz(archive: &Path, dest_dir: &Path) -> Result<(), String> {
+    let file = fs::File::open(archive)
+        .map_err(|e| format!("Failed to open archive {:?}: {}", archive, e))?;
+
+    fs::create_dir_all(dest_dir)
+        .map_err(|e| format!("Failed to create destination directory: {}", e))?;
+
+    tar::Archive::new(flate2::read::GzDecoder::new(file))
+        .unpack(dest_dir)
+        .map_err(|e| format!("Failed to extract tar.gz archive: {}", e))
+}
+
+/// Write `files` (source path, name inside the archive) into a new zip at `dest`
+///
+/// `compress` = false stores entries as-is, which is the right choice for PNG/JAR.
+pub fn write_zip(dest: &Path, files: &[(PathBuf, String)], compress: bool) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+
+    let options = SimpleFileOptions::default().compression_method(if compress {
+        CompressionMethod::Deflated
+    } else {
+        CompressionMethod::Stored
+    });
+
+    let result = (|| -> Result<(), String> {
+        let file = fs::File::create(dest)
+            .map_err(|e| format!("Failed to create archive {:?}: {}", dest, e))?;
+        let mut zip = ZipWriter::new(file);
+
+        for (source, name) in files {
+            zip.start_file(name.as_str(), options)
+                .map_err(|e| format!("Failed to add {} to archive: {}", name, e))?;
+            let mut input = fs::File::open(source)
+                .map_err(|e| format!("Failed to open {:?}: {}", source, e))?;
+            std::io::copy(&mut input, &mut zip)
+                .map_err(|e| format!("Failed to write {} to archive: {}", name, e))?;
+        }
+
+        zip.finish()
+            .map_err(|e| format!("Failed to finalize archive: {}", e))?;
+        Ok(())
+    })();
+
+    // Не оставляем недописанный архив
+    if result.is_err() {
+        let _ = fs::remove_file(dest);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn globs(globs: &[&str]) -> Vec<Pattern> {
+        compile_globs(&globs.iter().map(|g| g.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    fn write_test_zip(path: &Path, names: &[&str]) {
+        let mut zip = ZipWriter::new(fs::File::create(path).unwrap());
+        for name in names {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(name.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn exclude_wins_over_overlapping_include() {
+        let include = globs(&["overrides/**"]);
+        let exclude = globs(&["overrides/config/**", "**/*.log"]);
+
+        assert!(glob_filter_matches(&include, &exclude, "overrides/mods/a.jar"));
+        assert!(!glob_filter_matches(&include, &exclude, "overrides/config/b.toml"));
+        assert!(!glob_filter_matches(&include, &exclude, "overrides/logs/latest.log"));
+        assert!(!glob_filter_matches(&include, &exclude, "manifest.json"));
+    }
+
+    #[test]
+    fn empty_include_means_everything() {
+        let exclude = globs(&["*.txt"]);
+
+        assert!(glob_filter_matches(&[], &[], "any/path"));
+        assert!(glob_filter_matches(&[], &exclude, "dir/readme.txt"), "* must not cross /");
+        assert!(!glob_filter_matches(&[], &exclude, "readme.txt"));
+    }
+
+    #[test]
+    fn invalid_glob_is_rejected() {
+        assert!(compile_globs(&["[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn extracts_filtered_entries_and_refuses_zip_slip() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("pack.zip");
+        write_test_zip(&archive, &[
+            "manifest.json",
+            "overrides/mods/a.jar",
+            "overrides/config/b.toml",
+            "overrides/../../evil.txt",
+        ]);
+
+        let dest = dir.path().join("out");
+        let include = globs(&["overrides/**"]);
+        let exclude = globs(&["overrides/config/**"]);
+        let extracted = extract_zip_matching(&archive, &dest, |name| glob_filter_matches(&include, &exclude, name))
+            .unwrap();
+
+        assert_eq!(extracted, vec![dest.join("overrides/mods/a.jar").to_string_lossy().to_string()]);
+        assert!(!dest.join("manifest.json").exists());
+        assert!(!dest.join("overrides/config/b.toml").exists());
+        assert!(!dir.path().join("evil.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn extract_archive_returns_paths_and_keeps_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("natives.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&archive).unwrap());
+        zip.start_file("lib/liblwjgl.so", SimpleFileOptions::default().unix_permissions(0o644)).unwrap();
+        zip.write_all(b"native").unwrap();
+        zip.start_file("bin/run.sh", SimpleFileOptions::default().unix_permissions(0o755)).unwrap();
+        zip.write_all(b"#!/bin/sh\n").unwrap();
+        zip.start_file("../escape.txt", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"evil").unwrap();
+        zip.finish().unwrap();
+
+        let dest = dir.path().join("natives");
+        let mut extracted = extract_archive(archive.to_string_lossy().to_string(), dest.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        extracted.sort();
+
+        assert_eq!(extracted, vec![
+            dest.join("bin/run.sh").to_string_lossy().to_string(),
+            dest.join("lib/liblwjgl.so").to_string_lossy().to_string(),
+        ]);
+        assert_eq!(fs::read(dest.join("lib/liblwjgl.so")).unwrap(), b"native");
+        assert!(!dir.path().join("escape.txt").exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &str| fs::metadata(dest.join(path)).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode("bin/run.sh"), 0o755);
+            assert_eq!(mode("lib/liblwjgl.so"), 0o644);
+        }
+
+        let missing = extract_archive(dir.path().join("missing.zip").to_string_lossy().to_string(), String::new()).await;
+        assert!(matches!(missing, Err(LauncherError::NotFound { .. })));
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+/// Values for the `${...}` placeholders used in Mojang-style argument templates
+#[derive(Clone)]
+pub struct TemplateVars {
+    values: HashMap<&'static str, String>,
+}
+
+impl TemplateVars {
+    pub fn new() -> Self {
+        let mut values = HashMap::new();
+        values.insert("launcher_name", "ALauncher".to_string());
+        values.insert("launcher_version", env!("CARGO_PKG_VERSION").to_string());
+        TemplateVars { values }
+    }
+
+    pub fn set(&mut self, name: &'static str, value: impl Into<String>) -> &mut Self {
+        self.values.insert(name, value.into());
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// Copy without `names`; their placeholders are then left unexpanded
+    pub fn without(&self, names: &[&str]) -> Self {
+        let mut vars = self.clone();
+        vars.values.retain(|name, _| !names.contains(name));
+        vars
+    }
+}
+
+/// Expand every known `${name}` in `template`; unknown placeholders are left as-is with a warning
+pub fn substitute(template: &str, vars: &TemplateVars) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match vars.get(name) {
+                    Some(value) => result.push_str(value),
+                    None => {
+                        eprintln!("[Launcher Backend] ⚠️ Unknown argument placeholder: ${{{}}}", name);
+                        result.push_str(&rest[start..start + 2 + end + 1]);
+                    }
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                // Незакрытый плейсхолдер - оставляем хвост как есть
+                result.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+pub fn substitute_all(args: &[String], vars: &TemplateVars) -> Vec<String> {
+    args.iter().map(|arg| substitute(arg, vars)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitutes_known_placeholders() {
+        let mut vars = TemplateVars::new();
+        vars.set("game_directory", "/games/pack");
+
+        assert_eq!(substitute("--gameDir=${game_directory}", &vars), "--gameDir=/games/pack");
+        assert_eq!(substitute("${launcher_name}-${unknown}", &vars), "ALauncher-${unknown}");
+        assert_eq!(substitute("tail ${game_directory", &vars), "tail ${game_directory");
+    }
+
+    #[test]
+    fn removed_vars_are_not_expanded() {
+        let mut vars = TemplateVars::new();
+        vars.set("auth_access_token", "secret").set("auth_player_name", "Steve");
+        let public = vars.without(&["auth_access_token"]);
+
+        assert_eq!(substitute("${auth_player_name}:${auth_access_token}", &public), "Steve:${auth_access_token}");
+        assert_eq!(vars.get("auth_access_token"), Some("secret"));
+    }
+}
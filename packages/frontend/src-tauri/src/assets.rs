@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use crate::download::{self, BatchProgress, RetryPolicy};
+use crate::error::LauncherError;
+use crate::hashing;
+
+const RESOURCES_URL: &str = "https://resources.download.minecraft.net";
+
+/// Parallel asset downloads; there are thousands of small files per version
+const ASSET_CONCURRENCY: usize = 8;
+
+/// `assets/indexes/<id>.json`
+#[derive(Debug, Deserialize)]
+struct AssetIndexFile {
+    objects: HashMap<String, AssetObject>,
+    /// Pre-1.7: assets are also needed by name in `assets/virtual/<id>`
+    #[serde(default, rename = "virtual")]
+    is_virtual: bool,
+    /// Pre-1.6: assets are also needed by name in `<game_dir>/resources`
+    #[serde(default)]
+    map_to_resources: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AssetObject {
+    hash: String,
+    size: u64,
+}
+
+impl AssetObject {
+    /// `<2-char-prefix>/<hash>`, both under `objects/` and on the resources server
+    fn relative_path(&self) -> String {
+        format!("{}/{}", &self.hash[..2], self.hash)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetDownloadSummary {
+    pub total: usize,
+    pub downloaded: usize,
+    /// Already present with the right hash
+    pub skipped: usize,
+    pub bytes: u64,
+}
+
+/// Download every object of an asset index into `<assets_dir>/objects`
+///
+/// Objects already present with the right SHA-1 are skipped, the rest are downloaded
+/// in parallel and verified, with network errors retried per `retry`. Aggregate progress
+/// is emitted as `download-progress` under `download_id`. Legacy indexes are also copied
+/// by name: `virtual` ones into `<assets_dir>/virtual/<index id>`, `map_to_resources`
+/// ones into `<game_dir>/resources`.
+#[tauri::command]
+pub async fn download_assets(
+    asset_index_path: String,
+    assets_dir: String,
+    download_id: String,
+    game_dir: Option<String>,
+    retry: Option<RetryPolicy>,
+    app: tauri::AppHandle,
+) -> Result<AssetDownloadSummary, LauncherError> {
+    let content = fs::read_to_string(&asset_index_path)
+        .map_err(|e| LauncherError::io(&format!("Failed to read asset index {}", asset_index_path), e))?;
+    let index: AssetIndexFile = serde_json::from_str(&content)
+        .map_err(|e| LauncherError::invalid_params(format!("Failed to parse asset index {}: {}", asset_index_path, e)))?;
+
+    if index.objects.values().any(|object| object.hash.len() != 40) {
+        return Err(LauncherError::invalid_params(format!("Asset index {} has malformed hashes", asset_index_path)));
+    }
+    if index.map_to_resources && game_dir.is_none() {
+        return Err(LauncherError::invalid_params("This asset index maps to resources, game_dir is required"));
+    }
+
+    let assets_dir = PathBuf::from(&assets_dir);
+    let objects_dir = assets_dir.join("objects");
+
+    let objects = index.objects.clone();
+    let check_dir = objects_dir.clone();
+    let (total, missing) = tokio::task::spawn_blocking(move || missing_objects(&objects, &check_dir))
+        .await
+        .map_err(|e| format!("Asset check task failed: {}", e))?;
+
+    let bytes: u64 = missing.iter().map(|object| object.size).sum();
+    eprintln!(
+        "[Assets] {} objects in {}, {} to download ({} bytes)",
+        total, asset_index_path, missing.len(), bytes
+    );
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let progress = BatchProgress::new(&app, download_id, bytes);
+    let policy = retry.unwrap_or_default();
+
+    let results = stream::iter(&missing)
+        .map(|object| {
+            let (client, app, progress) = (&client, &app, &progress);
+            let url = format!("{}/{}", RESOURCES_URL, object.relative_path());
+            let dest = objects_dir.join(object.relative_path());
+            async move { download::download_verified(client, &url, &dest, Some(&object.hash), policy, app, progress).await }
+        })
+        .buffer_unordered(ASSET_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+    progress.emit();
+
+    let errors: Vec<String> = results.into_iter().filter_map(Result::err).collect();
+    if let Some(first) = errors.first() {
+        eprintln!("[Assets] ❌ {} objects failed to download", errors.len());
+        return Err(LauncherError::network(format!(
+            "{} of {} assets failed to download: {}",
+            errors.len(),
+            missing.len(),
+            first
+        )));
+    }
+
+    if index.is_virtual || index.map_to_resources {
+        let index_id = Path::new(&asset_index_path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "legacy".to_string());
+        let target = if index.map_to_resources {
+            Path::new(game_dir.as_deref().unwrap_or_default()).join("resources")
+        } else {
+            assets_dir.join("virtual").join(index_id)
+        };
+
+        let objects = index.objects;
+        tokio::task::spawn_blocking(move || copy_named_assets(&objects, &objects_dir, &target))
+            .await
+            .map_err(|e| format!("Asset copy task failed: {}", e))??;
+    }
+
+    eprintln!("[Assets] ✅ {} objects ready", total);
+    Ok(AssetDownloadSummary {
+        total,
+        downloaded: missing.len(),
+        skipped: total - missing.len(),
+        bytes,
+    })
+}
+
+/// Number of distinct objects and those not yet in `objects_dir` with the right SHA-1
+fn missing_objects(objects: &HashMap<String, AssetObject>, objects_dir: &Path) -> (usize, Vec<AssetObject>) {
+    // Один объект может встречаться под несколькими именами
+    let mut unique: HashMap<&str, &AssetObject> = HashMap::new();
+    for object in objects.values() {
+        unique.entry(object.hash.as_str()).or_insert(object);
+    }
+
+    let missing = unique.values()
+        .filter(|object| {
+            let path = objects_dir.join(object.relative_path());
+            !hashing::file_matches_sha1(&path, &object.hash, Some(object.size))
+        })
+        .map(|object| (*object).clone())
+        .collect();
+    (unique.len(), missing)
+}
+
+/// Copy objects to their original names for legacy clients that read assets by path
+fn copy_named_assets(
+    objects: &HashMap<String, AssetObject>,
+    objects_dir: &Path,
+    target: &Path,
+) -> Result<(), String> {
+    for (name, object) in objects {
+        let dest = target.join(name);
+        if !dest.starts_with(target) || name.split('/').any(|part| part == "..") {
+            eprintln!("[Assets] ⚠️ Skipping suspicious asset name: {}", name);
+            continue;
+        }
+        if fs::metadata(&dest).is_ok_and(|metadata| metadata.len() == object.size) {
+            continue;
+        }
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::copy(objects_dir.join(object.relative_path()), &dest)
+            .map_err(|e| format!("Failed to copy asset {}: {}", name, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SHA-1 of "abc"
+    const ABC_SHA1: &str = "a9993e364706816aba3e25717850c26c9cd0d89d";
+
+    fn index(json: &str) -> AssetIndexFile {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn objects_are_stored_by_hash_prefix() {
+        let object = AssetObject { hash: ABC_SHA1.to_string(), size: 3 };
+        assert_eq!(object.relative_path(), format!("a9/{}", ABC_SHA1));
+    }
+
+    #[test]
+    fn present_and_duplicate_objects_are_not_downloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = index(&format!(r#"{{"objects": {{
+            "minecraft/sounds/a.ogg": {{"hash": "{abc}", "size": 3}},
+            "minecraft/sounds/copy-of-a.ogg": {{"hash": "{abc}", "size": 3}},
+            "minecraft/lang/en_us.json": {{"hash": "{other}", "size": 10}}
+        }}}}"#, abc = ABC_SHA1, other = "0".repeat(40)));
+
+        let (total, missing) = missing_objects(&index.objects, dir.path());
+        assert_eq!((total, missing.len()), (2, 2));
+
+        let present = dir.path().join(format!("a9/{}", ABC_SHA1));
+        fs::create_dir_all(present.parent().unwrap()).unwrap();
+        fs::write(&present, "abc").unwrap();
+        let (total, missing) = missing_objects(&index.objects, dir.path());
+        assert_eq!(total, 2);
+        assert_eq!(missing.iter().map(|object| object.hash.as_str()).collect::<Vec<_>>(), ["0".repeat(40)]);
+
+        // Файл с неверным содержимым скачивается заново
+        fs::write(&present, "abd").unwrap();
+        assert_eq!(missing_objects(&index.objects, dir.path()).1.len(), 2);
+    }
+
+    #[test]
+    fn legacy_assets_are_copied_by_name_inside_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let objects_dir = dir.path().join("objects");
+        let object = objects_dir.join(format!("a9/{}", ABC_SHA1));
+        fs::create_dir_all(object.parent().unwrap()).unwrap();
+        fs::write(&object, "abc").unwrap();
+
+        let index = index(&format!(r#"{{"virtual": true, "objects": {{
+            "sound/step/grass1.ogg": {{"hash": "{abc}", "size": 3}},
+            "../escape.ogg": {{"hash": "{abc}", "size": 3}}
+        }}}}"#, abc = ABC_SHA1));
+        assert!(index.is_virtual && !index.map_to_resources);
+
+        let target = dir.path().join("virtual").join("legacy");
+        copy_named_assets(&index.objects, &objects_dir, &target).unwrap();
+        assert_eq!(fs::read_to_string(target.join("sound/step/grass1.ogg")).unwrap(), "abc");
+        assert!(!dir.path().join("virtual").join("escape.ogg").exists());
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use crate::error::LauncherError;
+
+/// Cancellation flag shared between a running command and `cancel_operation`
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// `Err(Cancelled)` once cancelled, for use with `?` between units of work
+    pub fn check(&self) -> Result<(), LauncherError> {
+        if self.is_cancelled() {
+            Err(LauncherError::cancelled())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Long-running commands in progress, by the operation id the frontend passed in
+#[derive(Default)]
+pub struct CancellationRegistry {
+    operations: Arc<Mutex<HashMap<String, CancelToken>>>,
+}
+
+impl CancellationRegistry {
+    /// Register an operation; it stays cancellable until the returned guard is dropped
+    ///
+    /// Without an id the operation simply can't be cancelled.
+    pub fn register(&self, operation_id: Option<String>) -> Result<Operation, LauncherError> {
+        let token = CancelToken::default();
+        if let Some(id) = &operation_id {
+            let mut operations = self.operations.lock()
+                .map_err(|_| "Cancellation registry is poisoned".to_string())?;
+            if operations.contains_key(id) {
+                return Err(LauncherError::invalid_params(format!("Operation {} is already running", id)));
+            }
+            operations.insert(id.clone(), token.clone());
+        }
+
+        Ok(Operation {
+            id: operation_id,
+            token,
+            operations: self.operations.clone(),
+        })
+    }
+
+    /// Cancel a running operation; false when no such operation is running
+    pub fn cancel(&self, operation_id: &str) -> bool {
+        let operations = match self.operations.lock() {
+            Ok(operations) => operations,
+            Err(_) => return false,
+        };
+        match operations.get(operation_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Registered operation; unregisters itself on drop
+pub struct Operation {
+    id: Option<String>,
+    token: CancelToken,
+    operations: Arc<Mutex<HashMap<String, CancelToken>>>,
+}
+
+impl Operation {
+    pub fn token(&self) -> CancelToken {
+        self.token.clone()
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        if let Some(id) = &self.id {
+            if let Ok(mut operations) = self.operations.lock() {
+                operations.remove(id);
+            }
+        }
+    }
+}
+
+/// Cancel a command started with this `operation_id`
+///
+/// Returns false when it already finished or never started.
+#[tauri::command]
+pub async fn cancel_operation(
+    operation_id: String,
+    registry: tauri::State<'_, CancellationRegistry>,
+) -> Result<bool, LauncherError> {
+    let cancelled = registry.cancel(&operation_id);
+    if cancelled {
+        eprintln!("[Launcher Backend] Cancelling operation {}", operation_id);
+    }
+    Ok(cancelled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_reaches_registered_token() {
+        let registry = CancellationRegistry::default();
+        let operation = registry.register(Some("verify".into())).unwrap();
+        let token = operation.token();
+
+        assert!(!token.is_cancelled());
+        assert!(registry.cancel("verify"));
+        assert!(token.is_cancelled());
+        assert!(matches!(token.check(), Err(LauncherError::Cancelled { .. })));
+    }
+
+    #[test]
+    fn finished_operation_is_unregistered() {
+        let registry = CancellationRegistry::default();
+        drop(registry.register(Some("verify".into())).unwrap());
+
+        assert!(!registry.cancel("verify"));
+        // Тот же id можно использовать снова
+        assert!(registry.register(Some("verify".into())).is_ok());
+    }
+
+    #[test]
+    fn duplicate_operation_id_is_rejected() {
+        let registry = CancellationRegistry::default();
+        let _running = registry.register(Some("verify".into())).unwrap();
+
+        assert!(matches!(
+            registry.register(Some("verify".into())),
+            Err(LauncherError::InvalidParams { .. })
+        ));
+    }
+
+    #[test]
+    fn anonymous_operation_is_not_cancellable() {
+        let registry = CancellationRegistry::default();
+        let operation = registry.register(None).unwrap();
+
+        assert!(!registry.cancel(""));
+        assert!(!operation.token().is_cancelled());
+    }
+}
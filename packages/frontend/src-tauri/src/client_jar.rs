@@ -0,0 +1,42 @@
+use std::path::Path;
+use regex::Regex;
+use serde_json::Value;
+use crate::archive;
+use crate::error::LauncherError;
+
+/// Read the Minecraft version id embedded in a client jar
+///
+/// Modern clients (1.14+) ship `version.json`; older ones only have the version in the
+/// window title string inside `Minecraft.class`. Jars without either are reported as such.
+#[tauri::command]
+pub async fn detect_jar_version(jar_path: String) -> Result<String, LauncherError> {
+    let jar = Path::new(&jar_path);
+    if !jar.is_file() {
+        return Err(LauncherError::not_found(format!("Jar not found: {}", jar_path)));
+    }
+
+    if let Some(content) = archive::read_zip_entry(jar, "version.json")? {
+        let json: Value = serde_json::from_slice(&content)
+            .map_err(|e| format!("Failed to parse version.json: {}", e))?;
+        if let Some(id) = json.get("id").or_else(|| json.get("name")).and_then(Value::as_str) {
+            return Ok(id.to_string());
+        }
+    }
+
+    // Старые клиенты: ищем "Minecraft 1.x.y" в классе клиента
+    for class_name in ["net/minecraft/client/Minecraft.class", "net/minecraft/client/main/Main.class"] {
+        if let Some(content) = archive::read_zip_entry(jar, class_name)? {
+            if let Some(version) = find_version_marker(&content) {
+                return Ok(version);
+            }
+        }
+    }
+
+    Err(LauncherError::not_found(format!("No version info embedded in jar: {}", jar_path)))
+}
+
+fn find_version_marker(class_bytes: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(class_bytes);
+    let re = Regex::new(r"Minecraft (?:Minecraft )?(1\.\d+(?:\.\d+)?)").ok()?;
+    re.captures(&text).map(|c| c[1].to_string())
+}
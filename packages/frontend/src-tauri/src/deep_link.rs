@@ -0,0 +1,218 @@
+use std::ffi::OsString;
+use std::sync::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::error::LauncherError;
+
+/// URL scheme registered for "play" links: `alauncher://launch/<profile_id>`
+pub const URL_SCHEME: &str = "alauncher";
+
+/// Profile the launcher was asked to start from outside (desktop shortcut, URL)
+///
+/// Launch request formats:
+/// - `alauncher --launch-profile <profile_id>` (desktop shortcuts)
+/// - `alauncher://launch/<profile_id>` (links on websites)
+///
+/// A request passed on startup is kept here until the frontend picks it up with
+/// `take_launch_profile`, so nothing is lost while the webview is still loading.
+/// Requests arriving while the launcher is already running are forwarded to the
+/// running instance and emitted as a `deep-link-launch` event.
+pub struct PendingLaunch {
+    profile_id: Mutex<Option<String>>,
+}
+
+impl PendingLaunch {
+    pub fn new(profile_id: Option<String>) -> Self {
+        PendingLaunch {
+            profile_id: Mutex::new(profile_id),
+        }
+    }
+}
+
+/// `alauncher://launch/<profile_id>`
+pub fn launch_url(profile_id: &str) -> String {
+    format!("{}://launch/{}", URL_SCHEME, profile_id)
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DeepLinkLaunch {
+    profile_id: String,
+}
+
+/// Extract `<profile_id>` from `--launch-profile <profile_id>` (or `--launch-profile=<profile_id>`)
+/// or from an `alauncher://launch/<profile_id>` URL among the arguments
+pub fn parse_launch_profile_arg(args: &[String]) -> Option<String> {
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if is_deep_link_url(arg) {
+            if let Some(profile_id) = parse_deep_link_url(arg) {
+                return Some(profile_id);
+            }
+            continue;
+        }
+
+        let value = if arg == "--launch-profile" {
+            iter.next().cloned()
+        } else {
+            arg.strip_prefix("--launch-profile=").map(str::to_string)
+        };
+
+        if let Some(profile_id) = value {
+            if is_valid_profile_id(&profile_id) {
+                return Some(profile_id);
+            }
+            eprintln!("[Deep Link] ⚠️ Ignoring invalid profile id: {}", profile_id);
+        }
+    }
+
+    None
+}
+
+/// Arguments without the launch request (`--launch-profile`, `alauncher://` URLs)
+///
+/// Used when the launcher restarts itself, so the restarted launcher doesn't start
+/// the same profile again.
+pub fn without_launch_args(args: &[OsString]) -> Vec<OsString> {
+    let mut result = Vec::with_capacity(args.len());
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.to_str() {
+            Some("--launch-profile") => {
+                iter.next();
+            }
+            Some(arg) if arg.starts_with("--launch-profile=") || is_deep_link_url(arg) => {}
+            _ => result.push(arg.clone()),
+        }
+    }
+
+    result
+}
+
+fn is_deep_link_url(arg: &str) -> bool {
+    arg.get(..URL_SCHEME.len() + 3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{}://", URL_SCHEME)))
+}
+
+/// Parse `alauncher://launch/<profile_id>`; malformed URLs are ignored with a warning
+pub fn parse_deep_link_url(url: &str) -> Option<String> {
+    let rest = if is_deep_link_url(url) {
+        &url[URL_SCHEME.len() + 3..]
+    } else {
+        eprintln!("[Deep Link] ⚠️ Ignoring URL with unexpected scheme: {}", url);
+        return None;
+    };
+
+    // Отбрасываем query/fragment и завершающий слэш
+    let rest = rest.split(['?', '#']).next().unwrap_or("").trim_end_matches('/');
+
+    match rest.split_once('/') {
+        Some((action, profile_id)) if action.eq_ignore_ascii_case("launch") && is_valid_profile_id(profile_id) => {
+            Some(profile_id.to_string())
+        }
+        _ => {
+            eprintln!("[Deep Link] ⚠️ Ignoring malformed deep link: {}", url);
+            None
+        }
+    }
+}
+
+/// Handle a launch request forwarded to the already running launcher
+pub fn handle_launch_request(app: &AppHandle, args: &[String]) {
+    focus_main_window(app);
+
+    if let Some(profile_id) = parse_launch_profile_arg(args) {
+        emit_launch(app, profile_id);
+    }
+}
+
+/// Handle URLs delivered by the OS through the deep-link plugin
+pub fn handle_urls(app: &AppHandle, urls: &[String]) {
+    focus_main_window(app);
+
+    for url in urls {
+        if let Some(profile_id) = parse_deep_link_url(url) {
+            emit_launch(app, profile_id);
+        }
+    }
+}
+
+fn emit_launch(app: &AppHandle, profile_id: String) {
+    eprintln!("[Deep Link] Launch requested for profile: {}", profile_id);
+    if let Err(e) = app.emit("deep-link-launch", DeepLinkLaunch { profile_id }) {
+        eprintln!("[Deep Link] Failed to emit deep-link-launch event: {}", e);
+    }
+}
+
+fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Profile ids end up in shortcut files and command lines, so keep them boring
+pub fn is_valid_profile_id(profile_id: &str) -> bool {
+    !profile_id.is_empty()
+        && profile_id.len() <= 128
+        && profile_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+/// Return the pending launch request (if any) and clear it
+#[tauri::command]
+pub async fn take_launch_profile(
+    pending: tauri::State<'_, PendingLaunch>,
+) -> Result<Option<String>, LauncherError> {
+    let mut profile_id = pending.profile_id.lock()
+        .map_err(|e| format!("Failed to lock pending launch: {}", e))?;
+    Ok(profile_id.take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_launch_profile_argument() {
+        assert_eq!(parse_launch_profile_arg(&args(&["alauncher", "--launch-profile", "pack-1"])), Some("pack-1".into()));
+        assert_eq!(parse_launch_profile_arg(&args(&["alauncher", "--launch-profile=pack_2"])), Some("pack_2".into()));
+        assert_eq!(parse_launch_profile_arg(&args(&["alauncher", "--launch-profile", "../x y"])), None);
+        assert_eq!(parse_launch_profile_arg(&args(&["alauncher"])), None);
+    }
+
+    #[test]
+    fn parses_deep_link_from_arguments() {
+        // Так ссылку получает процесс, запущенный по ней
+        assert_eq!(parse_launch_profile_arg(&args(&["alauncher.exe", "alauncher://launch/pack-1"])), Some("pack-1".into()));
+        assert_eq!(parse_deep_link_url("ALAUNCHER://Launch/pack-1/?from=site#top"), Some("pack-1".into()));
+        assert_eq!(parse_deep_link_url(&launch_url("pack.1")), Some("pack.1".into()));
+    }
+
+    #[test]
+    fn strips_launch_request_from_arguments() {
+        let os_args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            without_launch_args(&os_args(&["--launch-profile", "pack-1", "--verbose", "--launch-profile=pack-2"])),
+            os_args(&["--verbose"])
+        );
+        assert_eq!(without_launch_args(&os_args(&["ALAUNCHER://launch/pack-1", "--x"])), os_args(&["--x"]));
+        assert_eq!(without_launch_args(&os_args(&["--launch-profile"])), os_args(&[]));
+    }
+
+    #[test]
+    fn ignores_malformed_deep_links() {
+        assert_eq!(parse_deep_link_url("alauncher://open/pack-1"), None);
+        assert_eq!(parse_deep_link_url("alauncher://launch/"), None);
+        assert_eq!(parse_deep_link_url("alauncher://launch/a/b"), None);
+        assert_eq!(parse_deep_link_url("https://launch/pack-1"), None);
+    }
+}
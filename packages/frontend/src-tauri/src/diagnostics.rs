@@ -0,0 +1,62 @@
+use std::sync::Mutex;
+use serde::Serialize;
+use crate::{java_locator, logger, paths};
+use crate::error::LauncherError;
+
+/// Snapshot of how the launcher started, for support requests
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupDiagnostics {
+    pub app_version: String,
+    pub platform: String,
+    pub arch: String,
+    pub data_dir: Option<String>,
+    pub log_dir: Option<String>,
+    pub logger_initialized: bool,
+    pub log_file: Option<String>,
+    /// Filled in when the command is called, detection is too slow for startup
+    pub java_installations: usize,
+    /// Non-fatal problems hit during setup
+    pub warnings: Vec<String>,
+}
+
+impl StartupDiagnostics {
+    pub fn collect() -> Self {
+        let log_file = logger::current_log_path();
+        StartupDiagnostics {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            platform: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            data_dir: paths::app_data_dir().map(|p| p.to_string_lossy().to_string()),
+            log_dir: paths::logs_dir().map(|p| p.to_string_lossy().to_string()),
+            logger_initialized: log_file.is_some(),
+            log_file: log_file.map(|p| p.to_string_lossy().to_string()),
+            java_installations: 0,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Record a non-fatal setup problem (also logged)
+    pub fn warn(&mut self, warning: impl Into<String>) {
+        let warning = warning.into();
+        eprintln!("[Startup] ⚠️ {}", warning);
+        logger::log_error(&warning);
+        self.warnings.push(warning);
+    }
+}
+
+/// Managed state holding the diagnostics gathered in `run()` setup
+pub struct DiagnosticsState(pub Mutex<StartupDiagnostics>);
+
+#[tauri::command]
+pub async fn get_startup_diagnostics(state: tauri::State<'_, DiagnosticsState>) -> Result<StartupDiagnostics, LauncherError> {
+    let mut diagnostics = state.0.lock()
+        .map_err(|e| format!("Failed to read startup diagnostics: {}", e))?
+        .clone();
+
+    diagnostics.java_installations = tokio::task::spawn_blocking(java_locator::find_java_installations)
+        .await
+        .map(|installations| installations.len())
+        .unwrap_or_default();
+
+    Ok(diagnostics)
+}
@@ -0,0 +1,838 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use bytes::Bytes;
+use reqwest::{header, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use tauri::Emitter;
+use crate::archive;
+use crate::error::LauncherError;
+
+/// Emit progress at most once per this many downloaded bytes
+const PROGRESS_STEP_BYTES: u64 = 256 * 1024;
+
+/// Emit `download-progress` at most this often
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Upper bound for a single backoff delay
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// A checksum mismatch repeated this many times means the source is bad, not the connection
+const CHECKSUM_ATTEMPTS: u32 = 2;
+
+/// How many times received data may restore the retries of one file
+const MAX_RETRY_RESETS: u32 = 5;
+
+/// Retry policy for network errors and 5xx responses; 4xx responses are never retried
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each following one
+    pub base_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay_ms: 500,
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(self.base_delay_ms.saturating_mul(factor)).min(MAX_RETRY_DELAY)
+    }
+}
+
+/// Retry count of one file; `max_retries` applies to each failure in a row, not to the whole file
+///
+/// A long download that gets interrupted now and then but keeps making progress is not
+/// given up on; one that fails `max_retries` times without receiving a byte is. Progress
+/// restores the retries at most [`MAX_RETRY_RESETS`] times, so a connection that drops
+/// after every few bytes still fails eventually.
+#[derive(Debug, Default)]
+struct RetryCounter {
+    attempt: u32,
+    resets: u32,
+}
+
+impl RetryCounter {
+    /// Attempt number for the next retry, `None` when the retries are used up
+    fn next(&mut self, policy: &RetryPolicy) -> Option<u32> {
+        if self.attempt >= policy.max_retries {
+            return None;
+        }
+        self.attempt += 1;
+        Some(self.attempt)
+    }
+
+    /// Data arrived: the next failure starts counting from the first retry again
+    fn progressed(&mut self) {
+        if self.attempt > 0 && self.resets < MAX_RETRY_RESETS {
+            self.attempt = 0;
+            self.resets += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DownloadRetry {
+    url: String,
+    attempt: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DownloadProgress {
+    download_id: String,
+    downloaded: u64,
+    total: Option<u64>,
+    bytes_per_sec: u64,
+}
+
+/// HTTP body reader that reconnects on network errors, resuming with a `Range` request
+///
+/// When the server ignores `Range`, the already received prefix is skipped, so the
+/// consumer always sees one continuous byte stream.
+pub struct ResumableDownload {
+    client: reqwest::Client,
+    url: String,
+    access_token: Option<String>,
+    policy: RetryPolicy,
+    app: tauri::AppHandle,
+    response: Option<reqwest::Response>,
+    /// Bytes already handed to the consumer
+    offset: u64,
+    /// Bytes of the current response to drop (server restarted from zero)
+    skip: u64,
+    total: Option<u64>,
+    retries: RetryCounter,
+}
+
+impl ResumableDownload {
+    pub async fn start(
+        url: &str,
+        access_token: Option<String>,
+        policy: RetryPolicy,
+        app: &tauri::AppHandle,
+    ) -> Result<Self, String> {
+        Self::start_with_client(reqwest::Client::new(), url, access_token, policy, app).await
+    }
+
+    /// Same as `start`, reusing `client` (and its connection pool) across many small files
+    pub async fn start_with_client(
+        client: reqwest::Client,
+        url: &str,
+        access_token: Option<String>,
+        policy: RetryPolicy,
+        app: &tauri::AppHandle,
+    ) -> Result<Self, String> {
+        let mut download = ResumableDownload {
+            client,
+            url: url.to_string(),
+            access_token,
+            policy,
+            app: app.clone(),
+            response: None,
+            offset: 0,
+            skip: 0,
+            total: None,
+            retries: RetryCounter::default(),
+        };
+        download.connect().await?;
+        Ok(download)
+    }
+
+    pub fn total(&self) -> Option<u64> {
+        self.total
+    }
+
+    /// Next chunk of the body, `None` at the end
+    pub async fn next_chunk(&mut self) -> Result<Option<Bytes>, String> {
+        loop {
+            let response = match self.response.as_mut() {
+                Some(response) => response,
+                None => {
+                    self.connect().await?;
+                    continue;
+                }
+            };
+
+            match response.chunk().await {
+                Ok(Some(mut chunk)) => {
+                    if self.skip > 0 {
+                        let n = (self.skip as usize).min(chunk.len());
+                        self.skip -= n as u64;
+                        chunk = chunk.slice(n..);
+                        if chunk.is_empty() {
+                            continue;
+                        }
+                    }
+                    self.offset += chunk.len() as u64;
+                    self.retries.progressed();
+                    return Ok(Some(chunk));
+                }
+                Ok(None) => {
+                    // Соединение закрылось раньше, чем пришло заявленное число байт
+                    if self.total.is_some_and(|total| self.offset < total) {
+                        self.response = None;
+                        let error = format!("Connection closed after {} bytes", self.offset);
+                        self.backoff(error).await?;
+                        continue;
+                    }
+                    return Ok(None);
+                }
+                Err(e) => {
+                    self.response = None;
+                    self.backoff(format!("Download interrupted: {}", e)).await?;
+                }
+            }
+        }
+    }
+
+    async fn connect(&mut self) -> Result<(), String> {
+        loop {
+            let mut request = self.client.get(&self.url);
+            if let Some(token) = &self.access_token {
+                request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+            }
+            if self.offset > 0 {
+                request = request.header(header::RANGE, format!("bytes={}-", self.offset));
+            }
+
+            let response = match request.send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    self.backoff(format!("Failed to initiate download: {}", e)).await?;
+                    continue;
+                }
+            };
+
+            let status = response.status();
+            if status.is_client_error() {
+                return Err(format!("Download failed with status: {}", status));
+            }
+            if !status.is_success() {
+                self.backoff(format!("Download failed with status: {}", status)).await?;
+                continue;
+            }
+
+            if self.offset == 0 {
+                self.total = response.content_length();
+            } else if status != StatusCode::PARTIAL_CONTENT {
+                eprintln!("[Download] Server ignored Range for {}, skipping {} bytes", self.url, self.offset);
+                self.skip = self.offset;
+            }
+
+            self.response = Some(response);
+            return Ok(());
+        }
+    }
+
+    async fn backoff(&mut self, error: String) -> Result<(), String> {
+        let attempt = match self.retries.next(&self.policy) {
+            Some(attempt) => attempt,
+            None => return Err(error),
+        };
+
+        eprintln!(
+            "[Download] ⚠️ {} - retry {}/{} for {}",
+            error, attempt, self.policy.max_retries, self.url
+        );
+        let _ = self.app.emit("download-retry", DownloadRetry {
+            url: self.url.clone(),
+            attempt,
+        });
+        tokio::time::sleep(self.policy.delay(attempt)).await;
+        Ok(())
+    }
+}
+
+/// Download `url` to `dest_path`, going through `<dest_path>.part`
+///
+/// The file appears under its final name only once it is complete. With `on_progress`,
+/// `download-progress` events are emitted under `download_id` (the URL by default).
+#[tauri::command]
+pub async fn download_file(
+    url: String,
+    dest_path: String,
+    on_progress: bool,
+    access_token: Option<String>,
+    retry: Option<RetryPolicy>,
+    download_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<(), LauncherError> {
+    let dest = PathBuf::from(&dest_path);
+    let file_name = dest.file_name()
+        .ok_or_else(|| LauncherError::invalid_params(format!("Invalid destination path: {}", dest_path)))?;
+    let part_path = dest.with_file_name(format!("{}.part", file_name.to_string_lossy()));
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| LauncherError::io("Failed to create destination directory", e))?;
+    }
+
+    // Редиректы reqwest проходит сам, не-2xx ответы приходят ошибкой
+    let mut body = ResumableDownload::start(&url, access_token, retry.unwrap_or_default(), &app)
+        .await
+        .map_err(LauncherError::network)?;
+
+    let download_id = download_id.unwrap_or_else(|| url.clone());
+    let total = body.total();
+    let emit_progress = |downloaded: u64, started: Instant| {
+        if !on_progress {
+            return;
+        }
+        let elapsed = started.elapsed().as_secs_f64();
+        let bytes_per_sec = if elapsed > 0.0 { (downloaded as f64 / elapsed) as u64 } else { 0 };
+        let _ = app.emit("download-progress", DownloadProgress {
+            download_id: download_id.clone(),
+            downloaded,
+            total,
+            bytes_per_sec,
+        });
+    };
+
+    let result = async {
+        let mut file = File::create(&part_path)
+            .map_err(|e| LauncherError::io("Failed to create destination file", e))?;
+
+        let started = Instant::now();
+        let mut last_emit = started;
+        let mut downloaded = 0u64;
+        while let Some(chunk) = body.next_chunk().await.map_err(LauncherError::network)? {
+            file.write_all(&chunk)
+                .map_err(|e| LauncherError::io("Failed to write downloaded content", e))?;
+            downloaded += chunk.len() as u64;
+
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                emit_progress(downloaded, started);
+                last_emit = Instant::now();
+            }
+        }
+        file.sync_all()
+            .map_err(|e| LauncherError::io("Failed to flush downloaded file", e))?;
+        emit_progress(downloaded, started);
+
+        fs::rename(&part_path, &dest)
+            .map_err(|e| LauncherError::io("Failed to move downloaded file into place", e))
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = fs::remove_file(&part_path);
+    }
+    result
+}
+
+/// `download-progress` for a batch of files, reported as one download
+pub struct BatchProgress {
+    app: tauri::AppHandle,
+    download_id: String,
+    total: u64,
+    downloaded: AtomicU64,
+    started: Instant,
+    last_emit: Mutex<Instant>,
+}
+
+impl BatchProgress {
+    pub fn new(app: &tauri::AppHandle, download_id: String, total: u64) -> Self {
+        let now = Instant::now();
+        BatchProgress {
+            app: app.clone(),
+            download_id,
+            total,
+            downloaded: AtomicU64::new(0),
+            started: now,
+            last_emit: Mutex::new(now),
+        }
+    }
+
+    pub fn add(&self, bytes: u64) {
+        self.downloaded.fetch_add(bytes, Ordering::Relaxed);
+
+        let due = match self.last_emit.lock() {
+            Ok(mut last_emit) if last_emit.elapsed() >= PROGRESS_INTERVAL => {
+                *last_emit = Instant::now();
+                true
+            }
+            _ => false,
+        };
+        if due {
+            self.emit();
+        }
+    }
+
+    /// Take back bytes of a file that is downloaded again
+    pub fn rewind(&self, bytes: u64) {
+        self.downloaded.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    pub fn emit(&self) {
+        let downloaded = self.downloaded.load(Ordering::Relaxed);
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let bytes_per_sec = if elapsed > 0.0 { (downloaded as f64 / elapsed) as u64 } else { 0 };
+        let _ = self.app.emit("download-progress", DownloadProgress {
+            download_id: self.download_id.clone(),
+            downloaded,
+            total: Some(self.total),
+            bytes_per_sec,
+        });
+    }
+}
+
+/// Download one file of a batch to `dest` via `<dest>.part`, checking SHA-1 when known
+///
+/// Network errors are retried per `policy`; a checksum mismatch downloads the file again,
+/// up to [`CHECKSUM_ATTEMPTS`] times. Every received chunk is reported to `progress`.
+pub async fn download_verified(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    expected_sha1: Option<&str>,
+    policy: RetryPolicy,
+    app: &tauri::AppHandle,
+    progress: &BatchProgress,
+) -> Result<(), String> {
+    let part_path = dest.with_extension(match dest.extension() {
+        Some(ext) => format!("{}.part", ext.to_string_lossy()),
+        None => "part".to_string(),
+    });
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {}: {}", parent.display(), e))?;
+    }
+
+    let mut attempt = 1;
+    loop {
+        let mut received = 0;
+        let result = download_verified_once(client, url, &part_path, expected_sha1, policy, app, |bytes| {
+            received += bytes;
+            progress.add(bytes);
+        })
+        .await;
+
+        if result.is_err() {
+            let _ = fs::remove_file(&part_path);
+        }
+        match result {
+            Ok(()) => break,
+            Err(AttemptError::ChecksumMismatch(error)) if attempt < CHECKSUM_ATTEMPTS => {
+                eprintln!("[Download] ⚠️ {} for {}, downloading again", error, url);
+                progress.rewind(received);
+                attempt += 1;
+            }
+            Err(AttemptError::ChecksumMismatch(error)) => return Err(format!("{} for {}", error, url)),
+            Err(AttemptError::Failed(error)) => return Err(error),
+        }
+    }
+
+    fs::rename(&part_path, dest).map_err(|e| {
+        let _ = fs::remove_file(&part_path);
+        format!("Failed to move {} into place: {}", dest.display(), e)
+    })
+}
+
+/// One download of `url` into `part_path`, verified against `expected_sha1`
+async fn download_verified_once(
+    client: &reqwest::Client,
+    url: &str,
+    part_path: &Path,
+    expected_sha1: Option<&str>,
+    policy: RetryPolicy,
+    app: &tauri::AppHandle,
+    mut on_chunk: impl FnMut(u64),
+) -> Result<(), AttemptError> {
+    let mut body = ResumableDownload::start_with_client(client.clone(), url, None, policy, app).await?;
+    let mut file = File::create(part_path)
+        .map_err(|e| format!("Failed to create {}: {}", part_path.display(), e))?;
+    let mut hasher = Sha1::new();
+
+    while let Some(chunk) = body.next_chunk().await? {
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .map_err(|e| format!("Failed to write {}: {}", part_path.display(), e))?;
+        on_chunk(chunk.len() as u64);
+    }
+    drop(file);
+
+    verify_sha1(hasher, expected_sha1)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveKind {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveKind {
+    fn parse(archive_type: &str) -> Result<Self, String> {
+        match archive_type.to_ascii_lowercase().as_str() {
+            "zip" => Ok(ArchiveKind::Zip),
+            "tar.gz" | "tgz" => Ok(ArchiveKind::TarGz),
+            _ => Err(format!("Unsupported archive type: {}. Supported: zip, tar.gz.", archive_type)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DownloadExtractProgress {
+    url: String,
+    /// "downloading", "extracting" or "done"
+    stage: &'static str,
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+/// Staging directory (and temp archive for zip) removed on drop unless committed,
+/// so a failed or cancelled install leaves nothing half-extracted behind
+struct PartialInstall {
+    dir: PathBuf,
+    archive: PathBuf,
+    committed: bool,
+}
+
+impl PartialInstall {
+    fn new(dest: &Path) -> Result<Self, String> {
+        let name = dest.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or_else(|| format!("Invalid destination directory: {}", dest.display()))?;
+        let parent = dest.parent().unwrap_or_else(|| Path::new("."));
+
+        let partial = PartialInstall {
+            dir: parent.join(format!(".{}.partial", name)),
+            archive: parent.join(format!(".{}.partial.zip", name)),
+            committed: false,
+        };
+
+        // Остатки прошлой прерванной установки
+        partial.cleanup();
+        fs::create_dir_all(&partial.dir)
+            .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+        Ok(partial)
+    }
+
+    fn commit(mut self, dest: &Path) -> Result<(), String> {
+        let _ = fs::remove_file(&self.archive);
+        // Пустую директорию назначения можно заменить, на Windows rename поверх нее не работает
+        if dest.is_dir() {
+            fs::remove_dir(dest)
+                .map_err(|e| format!("Failed to replace destination directory: {}", e))?;
+        }
+        fs::rename(&self.dir, dest)
+            .map_err(|e| format!("Failed to move extracted files into place: {}", e))?;
+        self.committed = true;
+        Ok(())
+    }
+
+    fn cleanup(&self) {
+        let _ = fs::remove_file(&self.archive);
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+impl Drop for PartialInstall {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.cleanup();
+        }
+    }
+}
+
+/// Blocking `Read` over chunks sent from the async download loop
+struct ChannelReader {
+    rx: tokio::sync::mpsc::Receiver<Bytes>,
+    chunk: Bytes,
+    pos: usize,
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos >= self.chunk.len() {
+            match self.rx.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Download an archive and extract it into `dest_dir` in one pass
+///
+/// tar.gz is unpacked while the body streams in; zip needs random access, so it is
+/// buffered to a temp file first. Files are extracted into a staging directory and
+/// moved into place only after `expected_sha1` (over the downloaded bytes) matches.
+/// Progress is emitted as `download-extract-progress`. Returns `dest_dir`.
+#[tauri::command]
+pub async fn download_and_extract(
+    url: String,
+    dest_dir: String,
+    archive_type: String,
+    expected_sha1: Option<String>,
+    retry: Option<RetryPolicy>,
+    app: tauri::AppHandle,
+) -> Result<String, LauncherError> {
+    let kind = ArchiveKind::parse(&archive_type)?;
+    let dest = PathBuf::from(&dest_dir);
+    let policy = retry.unwrap_or_default();
+
+    let dest_not_empty = fs::read_dir(&dest).is_ok_and(|mut entries| entries.next().is_some());
+    if dest_not_empty {
+        return Err(LauncherError::invalid_params(format!("Destination directory is not empty: {}", dest_dir)));
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| LauncherError::io("Failed to create destination directory", e))?;
+    }
+
+    eprintln!("[Download] Downloading and extracting {} into {}", url, dest_dir);
+
+    let mut attempt = 1;
+    let (partial, downloaded) = loop {
+        match download_and_extract_once(&url, &dest, kind, expected_sha1.as_deref(), policy, &app).await {
+            Ok(done) => break done,
+            Err(AttemptError::ChecksumMismatch(error)) if attempt < CHECKSUM_ATTEMPTS => {
+                eprintln!("[Download] ⚠️ {}, downloading again", error);
+                attempt += 1;
+            }
+            Err(AttemptError::ChecksumMismatch(error) | AttemptError::Failed(error)) => return Err(error.into()),
+        }
+    };
+
+    partial.commit(&dest)?;
+
+    let _ = app.emit("download-extract-progress", DownloadExtractProgress {
+        url: url.clone(),
+        stage: "done",
+        downloaded,
+        total: Some(downloaded),
+    });
+    eprintln!("[Download] ✅ Extracted {} bytes from {} into {}", downloaded, url, dest_dir);
+
+    Ok(dest_dir)
+}
+
+enum AttemptError {
+    ChecksumMismatch(String),
+    Failed(String),
+}
+
+impl From<String> for AttemptError {
+    fn from(error: String) -> Self {
+        AttemptError::Failed(error)
+    }
+}
+
+/// One download + extraction into a fresh staging directory; returns it uncommitted
+async fn download_and_extract_once(
+    url: &str,
+    dest: &Path,
+    kind: ArchiveKind,
+    expected_sha1: Option<&str>,
+    policy: RetryPolicy,
+    app: &tauri::AppHandle,
+) -> Result<(PartialInstall, u64), AttemptError> {
+    let mut body = ResumableDownload::start(url, None, policy, app).await?;
+    let partial = PartialInstall::new(dest)?;
+
+    let mut progress = DownloadExtractProgress {
+        url: url.to_string(),
+        stage: "downloading",
+        downloaded: 0,
+        total: body.total(),
+    };
+    let mut last_emitted = 0;
+    let mut hasher = Sha1::new();
+
+    let partial = match kind {
+        ArchiveKind::Zip => {
+            let mut file = File::create(&partial.archive)
+                .map_err(|e| format!("Failed to create temporary archive: {}", e))?;
+
+            while let Some(chunk) = body.next_chunk().await? {
+                hasher.update(&chunk);
+                file.write_all(&chunk)
+                    .map_err(|e| format!("Failed to write temporary archive: {}", e))?;
+
+                progress.downloaded += chunk.len() as u64;
+                if progress.downloaded - last_emitted >= PROGRESS_STEP_BYTES {
+                    last_emitted = progress.downloaded;
+                    let _ = app.emit("download-extract-progress", &progress);
+                }
+            }
+            drop(file);
+
+            // Проверяем хэш до распаковки: битый архив распаковывать незачем
+            verify_sha1(hasher, expected_sha1)?;
+
+            progress.stage = "extracting";
+            let _ = app.emit("download-extract-progress", &progress);
+
+            // Staging-директория принадлежит распаковщику, пока он работает: если команду
+            // прервут, Drop не удалит ее из-под пишущего потока
+            tokio::task::spawn_blocking(move || {
+                archive::extract_zip_matching(&partial.archive, &partial.dir, |_| true)?;
+                Ok::<_, String>(partial)
+            })
+            .await
+            .map_err(|e| format!("Extraction task failed: {}", e))??
+        }
+        ArchiveKind::TarGz => {
+            let (tx, rx) = tokio::sync::mpsc::channel::<Bytes>(16);
+
+            // Как и для zip, staging-директорию распаковщик возвращает, только закончив запись
+            let extractor = tokio::task::spawn_blocking(move || {
+                let reader = ChannelReader { rx, chunk: Bytes::new(), pos: 0 };
+                let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(reader));
+                tar.unpack(&partial.dir)
+                    .map_err(|e| format!("Failed to extract tar.gz archive: {}", e))?;
+
+                // Дочитываем хвост, чтобы хэш считался по всему телу ответа
+                io::copy(&mut tar.into_inner().into_inner(), &mut io::sink())
+                    .map_err(|e| format!("Failed to read archive: {}", e))?;
+                Ok::<_, String>(partial)
+            });
+
+            let mut download_result = Ok(());
+            loop {
+                let chunk = match body.next_chunk().await {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => break,
+                    Err(e) => {
+                        download_result = Err(e);
+                        break;
+                    }
+                };
+                hasher.update(&chunk);
+
+                progress.downloaded += chunk.len() as u64;
+                if progress.downloaded - last_emitted >= PROGRESS_STEP_BYTES {
+                    last_emitted = progress.downloaded;
+                    let _ = app.emit("download-extract-progress", &progress);
+                }
+
+                // Распаковщик завершился раньше времени - его ошибку вернет join ниже
+                if tx.send(chunk).await.is_err() {
+                    break;
+                }
+            }
+            drop(tx);
+
+            let extract_result = extractor.await
+                .map_err(|e| format!("Extraction task failed: {}", e))?;
+            download_result?;
+            let partial = extract_result?;
+
+            verify_sha1(hasher, expected_sha1)?;
+            partial
+        }
+    };
+
+    Ok((partial, progress.downloaded))
+}
+
+fn verify_sha1(hasher: Sha1, expected: Option<&str>) -> Result<(), AttemptError> {
+    let expected = match expected {
+        Some(expected) => expected,
+        None => return Ok(()),
+    };
+
+    let actual = hex::encode(hasher.finalize());
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        return Err(AttemptError::ChecksumMismatch(format!(
+            "Checksum mismatch: expected {}, got {}",
+            expected, actual
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_are_counted_per_failure_streak() {
+        let policy = RetryPolicy { max_retries: 2, base_delay_ms: 0 };
+        let mut retries = RetryCounter::default();
+
+        assert_eq!(retries.next(&policy), Some(1));
+        assert_eq!(retries.next(&policy), Some(2));
+        assert_eq!(retries.next(&policy), None);
+
+        // После полученных данных снова доступны все повторы
+        retries.progressed();
+        assert_eq!(retries.next(&policy), Some(1));
+    }
+
+    #[test]
+    fn connection_dropping_after_every_chunk_runs_out_of_retries() {
+        let policy = RetryPolicy { max_retries: 2, base_delay_ms: 0 };
+        let mut retries = RetryCounter::default();
+
+        let mut total = 0;
+        while retries.next(&policy).is_some() {
+            total += 1;
+            assert!(total <= 100, "retried forever");
+            retries.progressed();
+        }
+        assert_eq!(total, policy.max_retries + MAX_RETRY_RESETS);
+
+        // Данные без ошибок между ними повторы не расходуют
+        let mut retries = RetryCounter::default();
+        for _ in 0..10 {
+            retries.progressed();
+        }
+        assert_eq!(retries.resets, 0);
+    }
+
+    #[test]
+    fn no_retries_when_disabled() {
+        let policy = RetryPolicy { max_retries: 0, base_delay_ms: 500 };
+        assert_eq!(RetryCounter::default().next(&policy), None);
+    }
+
+    #[test]
+    fn staging_directory_is_removed_unless_committed() {
+        let root = tempfile::tempdir().unwrap();
+        let dest = root.path().join("client");
+
+        let partial = PartialInstall::new(&dest).unwrap();
+        let staging = partial.dir.clone();
+        fs::write(staging.join("a.txt"), "a").unwrap();
+        drop(partial);
+        assert!(!staging.exists());
+        assert!(!dest.exists());
+
+        let partial = PartialInstall::new(&dest).unwrap();
+        fs::write(partial.dir.join("a.txt"), "a").unwrap();
+        partial.commit(&dest).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "a");
+        assert!(!staging.exists());
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_limit() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(1), Duration::from_millis(500));
+        assert_eq!(policy.delay(2), Duration::from_millis(1000));
+        assert_eq!(policy.delay(3), Duration::from_millis(2000));
+        assert_eq!(policy.delay(64), MAX_RETRY_DELAY);
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt;
+use std::io;
+use serde::Serialize;
+
+/// Error returned by Tauri commands
+///
+/// Serialized as `{ "kind": "...", "message": "..." }` so the frontend can branch
+/// on the kind and still show the message as before.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum LauncherError {
+    Io { message: String },
+    NotFound { message: String },
+    PermissionDenied { message: String },
+    ProcessNotFound { message: String },
+    InvalidParams { message: String },
+    /// The configured or detected Java binary does not exist
+    JavaNotFound { message: String },
+    JavaIncompatible { message: String },
+    Network { message: String },
+    /// Microsoft sign-in was declined, expired or rejected by Xbox Live
+    AuthFailed { message: String },
+    /// Signed in, but the account does not own Minecraft
+    NoMinecraft { message: String },
+    /// Stored refresh token is no longer accepted, a full login is needed
+    ReauthRequired { message: String },
+    /// Stopped by `cancel_operation`
+    Cancelled { message: String },
+    /// A download could not be verified on this system (e.g. no `gpg`), nothing was installed
+    VerificationUnavailable { message: String },
+    Other { message: String },
+}
+
+impl LauncherError {
+    /// I/O error with context, keeping not-found and permission-denied apart
+    pub fn io(context: &str, err: io::Error) -> Self {
+        let message = format!("{}: {}", context, err);
+        match err.kind() {
+            io::ErrorKind::NotFound => LauncherError::NotFound { message },
+            io::ErrorKind::PermissionDenied => LauncherError::PermissionDenied { message },
+            _ => LauncherError::Io { message },
+        }
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        LauncherError::InvalidParams { message: message.into() }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        LauncherError::NotFound { message: message.into() }
+    }
+
+    pub fn java_not_found(message: impl Into<String>) -> Self {
+        LauncherError::JavaNotFound { message: message.into() }
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        LauncherError::Network { message: message.into() }
+    }
+
+    pub fn auth_failed(message: impl Into<String>) -> Self {
+        LauncherError::AuthFailed { message: message.into() }
+    }
+
+    pub fn process_not_found(process_id: &str) -> Self {
+        LauncherError::ProcessNotFound { message: format!("Process not found: {}", process_id) }
+    }
+
+    pub fn cancelled() -> Self {
+        LauncherError::Cancelled { message: "Operation cancelled".to_string() }
+    }
+
+    pub fn verification_unavailable(message: impl Into<String>) -> Self {
+        LauncherError::VerificationUnavailable { message: message.into() }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            LauncherError::Io { message }
+            | LauncherError::NotFound { message }
+            | LauncherError::PermissionDenied { message }
+            | LauncherError::ProcessNotFound { message }
+            | LauncherError::InvalidParams { message }
+            | LauncherError::JavaNotFound { message }
+            | LauncherError::JavaIncompatible { message }
+            | LauncherError::Network { message }
+            | LauncherError::AuthFailed { message }
+            | LauncherError::NoMinecraft { message }
+            | LauncherError::ReauthRequired { message }
+            | LauncherError::Cancelled { message }
+            | LauncherError::VerificationUnavailable { message }
+            | LauncherError::Other { message } => message,
+        }
+    }
+}
+
+impl fmt::Display for LauncherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for LauncherError {}
+
+impl From<io::Error> for LauncherError {
+    fn from(err: io::Error) -> Self {
+        LauncherError::io("I/O error", err)
+    }
+}
+
+/// Внутренние функции пока возвращают String; такие ошибки не классифицированы
+impl From<String> for LauncherError {
+    fn from(message: String) -> Self {
+        LauncherError::Other { message }
+    }
+}
+
+impl From<tauri::Error> for LauncherError {
+    fn from(err: tauri::Error) -> Self {
+        LauncherError::Other { message: err.to_string() }
+    }
+}
@@ -0,0 +1,304 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tauri::Emitter;
+use crate::error::LauncherError;
+
+const COPY_BUFFER_BYTES: usize = 1024 * 1024;
+
+/// Emit `copy-progress` at most this often
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Serialize)]
+struct CopyProgress {
+    operation_id: String,
+    copied: u64,
+    total: u64,
+}
+
+/// Copy a file or a whole directory to `dest`, which must not exist yet
+///
+/// Files are streamed in chunks and `copy-progress` events report bytes copied
+/// against the total under `operation_id`. Returns the number of bytes copied.
+#[tauri::command]
+pub async fn copy_path(
+    src: String,
+    dest: String,
+    operation_id: String,
+    app: tauri::AppHandle,
+) -> Result<u64, LauncherError> {
+    let context = format!("Failed to copy {} to {}", src, dest);
+    let (src, dest) = (PathBuf::from(src), PathBuf::from(dest));
+    check_transfer(&src, &dest)?;
+
+    tokio::task::spawn_blocking(move || {
+        let total = total_size(&src);
+        let mut progress = Progress {
+            app: Some(app),
+            operation_id,
+            copied: 0,
+            total,
+            last_emit: Instant::now(),
+        };
+
+        let result = copy_recursive(&src, &dest, &mut progress);
+        if result.is_err() {
+            remove_path(&dest);
+        }
+        progress.emit();
+        result.map(|_| progress.copied)
+    })
+    .await
+    .map_err(|e| format!("Copy task failed: {}", e))?
+    .map_err(|e| LauncherError::io(&context, e))
+}
+
+/// Move a file or directory to `dest`, which must not exist yet
+///
+/// A rename is tried first; across filesystems the data is copied and the source
+/// removed only after the copy succeeded.
+#[tauri::command]
+pub async fn move_path(src: String, dest: String) -> Result<(), LauncherError> {
+    let context = format!("Failed to move {} to {}", src, dest);
+    let (src, dest) = (PathBuf::from(src), PathBuf::from(dest));
+    check_transfer(&src, &dest)?;
+
+    tokio::task::spawn_blocking(move || {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        match fs::rename(&src, &dest) {
+            Ok(()) => return Ok(()),
+            Err(e) if is_cross_device(&e) => {
+                eprintln!("[Files] {} is on another filesystem, copying instead of renaming", dest.display());
+            }
+            Err(e) => return Err(e),
+        }
+
+        let mut progress = Progress::silent();
+        if let Err(e) = copy_recursive(&src, &dest, &mut progress) {
+            remove_path(&dest);
+            return Err(e);
+        }
+        if src.is_dir() && !fs::symlink_metadata(&src)?.file_type().is_symlink() {
+            fs::remove_dir_all(&src)
+        } else {
+            fs::remove_file(&src)
+        }
+    })
+    .await
+    .map_err(|e| format!("Move task failed: {}", e))?
+    .map_err(|e| LauncherError::io(&context, e))
+}
+
+/// Source exists, destination is free and not inside the source
+fn check_transfer(src: &Path, dest: &Path) -> Result<(), LauncherError> {
+    if fs::symlink_metadata(src).is_err() {
+        return Err(LauncherError::not_found(format!("Source not found: {}", src.display())));
+    }
+    if fs::symlink_metadata(dest).is_ok() {
+        return Err(LauncherError::invalid_params(format!("Destination already exists: {}", dest.display())));
+    }
+
+    // Копия каталога внутрь самого себя никогда не закончится
+    let src_abs = fs::canonicalize(src).unwrap_or_else(|_| src.to_path_buf());
+    if src.is_dir() && resolve_missing(dest).starts_with(&src_abs) {
+        return Err(LauncherError::invalid_params(format!(
+            "Cannot copy {} into itself",
+            src.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Canonical form of a path that doesn't exist yet: the deepest existing ancestor is
+/// canonicalized and the missing components are applied on top of it
+fn resolve_missing(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        let ancestor = if existing.as_os_str().is_empty() { Path::new(".") } else { existing };
+        if let Ok(mut resolved) = fs::canonicalize(ancestor) {
+            for component in missing.into_iter().rev() {
+                match component {
+                    Component::ParentDir => { resolved.pop(); }
+                    Component::CurDir => {}
+                    other => resolved.push(other),
+                }
+            }
+            return resolved;
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(component)) => {
+                missing.push(component);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// `rename` failed because `dest` is on another filesystem
+fn is_cross_device(err: &io::Error) -> bool {
+    #[cfg(unix)]
+    const CROSS_DEVICE: i32 = libc::EXDEV;
+    #[cfg(windows)]
+    const CROSS_DEVICE: i32 = 17; // ERROR_NOT_SAME_DEVICE
+
+    err.raw_os_error() == Some(CROSS_DEVICE)
+}
+
+struct Progress {
+    app: Option<tauri::AppHandle>,
+    operation_id: String,
+    copied: u64,
+    total: u64,
+    last_emit: Instant,
+}
+
+impl Progress {
+    fn silent() -> Self {
+        Progress {
+            app: None,
+            operation_id: String::new(),
+            copied: 0,
+            total: 0,
+            last_emit: Instant::now(),
+        }
+    }
+
+    fn add(&mut self, bytes: u64) {
+        self.copied += bytes;
+        if self.last_emit.elapsed() >= PROGRESS_INTERVAL {
+            self.emit();
+            self.last_emit = Instant::now();
+        }
+    }
+
+    fn emit(&self) {
+        if let Some(app) = &self.app {
+            let _ = app.emit("copy-progress", CopyProgress {
+                operation_id: self.operation_id.clone(),
+                copied: self.copied,
+                total: self.total,
+            });
+        }
+    }
+}
+
+fn total_size(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+fn copy_recursive(src: &Path, dest: &Path, progress: &mut Progress) -> io::Result<()> {
+    // Симлинки не раскрываем: копируем саму ссылку
+    for entry in walkdir::WalkDir::new(src).follow_links(false) {
+        let entry = entry.map_err(io::Error::other)?;
+        let relative = entry.path().strip_prefix(src).map_err(io::Error::other)?;
+        let target = if relative.as_os_str().is_empty() { dest.to_path_buf() } else { dest.join(relative) };
+        let file_type = entry.file_type();
+
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(entry.path(), &target)?;
+        } else {
+            copy_file(entry.path(), &target, progress)?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_file(src: &Path, dest: &Path, progress: &mut Progress) -> io::Result<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut input = File::open(src)?;
+    let mut output = File::create(dest)?;
+    let mut buffer = vec![0u8; COPY_BUFFER_BYTES];
+    loop {
+        let n = input.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        output.write_all(&buffer[..n])?;
+        progress.add(n as u64);
+    }
+    output.sync_all()?;
+
+    fs::set_permissions(dest, fs::metadata(src)?.permissions())
+}
+
+#[cfg(unix)]
+fn copy_symlink(src: &Path, dest: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(src)?, dest)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(src: &Path, _dest: &Path) -> io::Result<()> {
+    // Создание симлинков на Windows требует прав администратора
+    eprintln!("[Files] ⚠️ Skipping symlink {}", src.display());
+    Ok(())
+}
+
+/// Remove a partially written copy
+fn remove_path(path: &Path) {
+    let _ = match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(_) => Ok(()),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_copy_into_missing_subdirectory_of_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("instance");
+        fs::create_dir(&src).unwrap();
+
+        let nested = src.join("backups").join("today").join("instance");
+        assert!(check_transfer(&src, &nested).is_err());
+
+        // Тот же каталог через `..` в ещё не созданной части пути
+        let sneaky = dir.path().join("other").join("..").join("instance").join("copy");
+        assert!(check_transfer(&src, &sneaky).is_err());
+
+        assert!(check_transfer(&src, &dir.path().join("backups").join("instance")).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_copy_into_source_through_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("instance");
+        fs::create_dir(&src).unwrap();
+        std::os::unix::fs::symlink(&src, dir.path().join("link")).unwrap();
+
+        let dest = dir.path().join("link").join("missing").join("copy");
+        assert!(check_transfer(&src, &dest).is_err());
+    }
+
+    #[test]
+    fn detects_cross_device_errors_by_os_code() {
+        #[cfg(unix)]
+        let code = libc::EXDEV;
+        #[cfg(windows)]
+        let code = 17;
+        assert!(is_cross_device(&io::Error::from_raw_os_error(code)));
+        assert!(!is_cross_device(&io::Error::from(io::ErrorKind::PermissionDenied)));
+    }
+}
@@ -0,0 +1,129 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Write a file via a temp file + rename so readers never see a half-written file
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+
+    replace_atomic(path, |file| file.write_all(content))
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Fill a sibling temp file with `write`, fsync it and rename it over `path`
+fn replace_atomic(path: &Path, write: impl FnOnce(&mut fs::File) -> io::Result<()>) -> io::Result<()> {
+    let file_name = path.file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid file path: {:?}", path)))?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    let result = (|| {
+        let mut file = fs::File::create(&tmp_path)?;
+        write(&mut file)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Keep only the last `keep_bytes` of a file, starting at a line boundary; returns the bytes freed
+///
+/// The tail is copied to a temp file that then replaces the original, so a crash or a
+/// concurrent append never leaves a half-rewritten file. Lines appended before the copy
+/// finishes are kept. A process that still has the old file open (the game writing
+/// `latest.log`) goes on writing into the replaced file, so truncate after it exits.
+/// When the tail is a single line longer than `keep_bytes`, it is kept cut mid-line
+/// rather than dropped.
+pub fn truncate_to_tail(path: &Path, keep_bytes: u64) -> io::Result<u64> {
+    let mut file = fs::File::open(path)?;
+    let original_len = file.metadata()?.len();
+    if original_len <= keep_bytes {
+        return Ok(0);
+    }
+
+    let mut start = original_len - keep_bytes;
+    if keep_bytes > 0 {
+        // Первая граница строки не раньше start (перевод строки прямо перед start тоже подходит)
+        file.seek(SeekFrom::Start(start - 1))?;
+        let mut partial_line = Vec::new();
+        BufReader::new(&mut file).read_until(b'\n', &mut partial_line)?;
+        let line_start = start - 1 + partial_line.len() as u64;
+        if partial_line.ends_with(b"\n") && line_start < original_len {
+            start = line_start;
+        }
+    }
+
+    file.seek(SeekFrom::Start(start))?;
+    replace_atomic(path, |tmp| io::copy(&mut file, tmp).map(|_| ()))?;
+
+    Ok(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn truncated(content: &str, keep_bytes: u64) -> (u64, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("latest.log");
+        fs::write(&path, content).unwrap();
+
+        let freed = truncate_to_tail(&path, keep_bytes).unwrap();
+        (freed, fs::read_to_string(&path).unwrap())
+    }
+
+    #[test]
+    fn keeps_tail_from_a_line_boundary() {
+        assert_eq!(truncated("first line\nsecond\nthird\n", 10), (18, "third\n".to_string()));
+        // Граница строки ровно на отрезке
+        assert_eq!(truncated("first line\nsecond\n", 7), (11, "second\n".to_string()));
+    }
+
+    #[test]
+    fn small_file_is_left_alone() {
+        assert_eq!(truncated("short\n", 100), (0, "short\n".to_string()));
+    }
+
+    #[test]
+    fn single_long_line_is_cut_instead_of_dropped() {
+        assert_eq!(truncated("header\n0123456789abcdef\n", 8), (16, "9abcdef\n".to_string()));
+        assert_eq!(truncated("0123456789", 4), (6, "6789".to_string()));
+    }
+
+    #[test]
+    fn keep_zero_empties_the_file() {
+        assert_eq!(truncated("a\nb\n", 0), (4, String::new()));
+    }
+
+    #[test]
+    fn file_is_replaced_through_a_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("latest.log");
+        fs::write(&path, "old line\nnew line\n").unwrap();
+
+        // Старое содержимое остается у уже открытого дескриптора, файл подменяется целиком
+        let mut reader = fs::File::open(&path).unwrap();
+        assert_eq!(truncate_to_tail(&path, 9).unwrap(), 9);
+        let mut before = String::new();
+        reader.read_to_string(&mut before).unwrap();
+        assert_eq!(before, "old line\nnew line\n");
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new line\n");
+        let names: Vec<_> = fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(names, ["latest.log"]);
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(truncate_to_tail(&dir.path().join("missing.log"), 10).is_err());
+        assert!(!dir.path().join(".missing.log.tmp").exists());
+    }
+}
@@ -1,36 +1,95 @@
 use std::process::{Command, Child};
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime};
 use std::path::{Path, PathBuf};
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
+use crate::arg_template::{self, TemplateVars};
+use crate::log_buffer::{LogBuffer, DEFAULT_OUTPUT_BUFFER_BYTES};
+use crate::process_tuning::ProcessPriority;
+use crate::{accounts, fs_utils, gpu, java_locator, launch_builder, launch_hooks, log4j, paths, process_tuning, system_info};
+use crate::version_json::{self, VersionJson};
 use std::fs;
+use crate::error::LauncherError;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LaunchParams {
     pub profile_id: String,
+    /// Версия клиента для --version (например, "1.12.2")
+    pub version: String,
+    /// Индекс ассетов для --assetIndex; пустой - флаг не передается (старые клиенты)
+    pub asset_index: String,
     pub username: String,
     pub uuid: String,
     pub access_token: String,
+    /// Запуск без аккаунта: токен не передается, --userType legacy.
+    /// Заглушка вместо токена ("", "0", "demo", ...) тоже считается офлайном
+    #[serde(default)]
+    pub offline: bool,
     pub game_dir: String,
     pub assets_dir: String,
     pub resolution: Resolution,
     pub full_screen: bool,
     pub java_path: String,
     pub java_version: String,
-    pub ram: String,
+    /// -Xmx в мегабайтах
+    pub max_ram: String,
+    /// -Xms в мегабайтах, по умолчанию DEFAULT_MIN_RAM_MB (но не больше max_ram)
+    #[serde(default)]
+    pub min_ram: Option<String>,
     pub jvm_args: Vec<String>,
     pub client_args: Vec<String>,
+    /// Игровые аргументы из version json (см. build_launch_params); заменяют стандартный набор
+    #[serde(default)]
+    pub game_args: Option<Vec<String>>,
     pub main_class: String,
     pub class_path: Vec<String>,
     pub server_address: Option<String>,
     pub server_port: Option<i32>,
+    #[serde(default)]
+    pub minimize_on_launch: bool,
+    #[serde(default)]
+    pub cpu_affinity: Option<Vec<usize>>,
+    /// Команда-обертка перед java, например ["gamemoderun"] или ["prime-run"]
+    #[serde(default)]
+    pub wrapper_command: Option<Vec<String>>,
+    /// Не добавлять -XstartOnFirstThread на macOS (например, для клиентов без LWJGL 3)
+    #[serde(default)]
+    pub disable_start_on_first_thread: bool,
+    /// Дополнительные переменные окружения (DRI_PRIME, JAVA_TOOL_OPTIONS, ...).
+    /// Добавляются к окружению лаунчера, одноименные переменные перезаписываются
+    #[serde(default)]
+    pub env_vars: HashMap<String, String>,
+    /// Команда перед запуском java; ненулевой код выхода или HOOK_TIMEOUT отменяют запуск
+    #[serde(default)]
+    pub pre_launch: Option<Vec<String>>,
+    /// Команда после завершения игры (не выполняется, если игру остановил kill_game_process)
+    #[serde(default)]
+    pub post_exit: Option<Vec<String>>,
+    /// Приоритет процесса игры, Normal - без изменений (см. apply_process_priority)
+    #[serde(default)]
+    pub process_priority: ProcessPriority,
+    #[serde(default)]
+    pub structured_logs: bool,
+    /// Порт JDWP для подключения отладчика из IDE
+    #[serde(default)]
+    pub debug_port: Option<u16>,
+    /// Каталог нативных библиотек для -Djava.library.path; пустой - <game_dir>/natives,
+    /// которые лаунчер сам извлекает из natives-jar в libraries.
+    /// Свой каталог заполняется заранее: скачать natives-jar, распаковать каждый через
+    /// extract_archive в этот каталог, затем вызвать launch_game_client
+    #[serde(default)]
+    pub natives_dir: String,
+    /// Сколько последних байт stdout/stderr хранить, по умолчанию DEFAULT_OUTPUT_BUFFER_BYTES
+    #[serde(default)]
+    pub output_buffer_bytes: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Resolution {
     pub width: u32,
     pub height: u32,
@@ -44,6 +103,70 @@ pub struct LaunchResult {
     pub process_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "errorCode")]
+    pub error_code: Option<LaunchErrorCode>,
+    /// Подсказка пользователю, запуск при этом успешен (например, игра на встроенном GPU)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+impl LaunchResult {
+    fn failure(code: LaunchErrorCode, message: String) -> Self {
+        eprintln!("[ERROR] {:?}: {}", code, message);
+        LaunchResult {
+            success: false,
+            process_id: None,
+            error: Some(message),
+            error_code: Some(code),
+            warning: None,
+        }
+    }
+}
+
+/// Стабильные коды ошибок запуска, по которым UI показывает подсказку
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LaunchErrorCode {
+    JavaNotFound,
+    JavaNotExecutable,
+    GameDirMissing,
+    JavaIncompatible,
+    MainClassMissing,
+    ClassPathEntryMissing,
+    PermissionDenied,
+    OutOfMemory,
+    PreLaunchHookFailed,
+    /// Параметры запуска вне допустимых значений (ядра CPU, порт отладчика)
+    InvalidLaunchOptions,
+    Unknown,
+}
+
+/// Этап запуска, на котором произошла ошибка
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchStage {
+    GameDir,
+    AssetsDir,
+    Spawn,
+}
+
+/// Сопоставить ошибку ввода-вывода и этап запуска с кодом ошибки
+pub fn classify_launch_error(stage: LaunchStage, error: &std::io::Error) -> LaunchErrorCode {
+    use std::io::ErrorKind;
+
+    match (stage, error.kind()) {
+        (_, ErrorKind::OutOfMemory) => LaunchErrorCode::OutOfMemory,
+        (LaunchStage::Spawn, ErrorKind::NotFound) => LaunchErrorCode::JavaNotFound,
+        (LaunchStage::Spawn, ErrorKind::PermissionDenied) => LaunchErrorCode::JavaNotExecutable,
+        // ENOEXEC: файл есть, но это не исполняемый бинарник (битая загрузка, чужая архитектура)
+        (LaunchStage::Spawn, _) if error.raw_os_error() == Some(8) && cfg!(unix) => {
+            LaunchErrorCode::JavaNotExecutable
+        }
+        (LaunchStage::GameDir | LaunchStage::AssetsDir, ErrorKind::PermissionDenied) => {
+            LaunchErrorCode::PermissionDenied
+        }
+        (LaunchStage::GameDir | LaunchStage::AssetsDir, _) => LaunchErrorCode::GameDirMissing,
+        _ => LaunchErrorCode::Unknown,
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -52,30 +175,474 @@ pub struct ProcessStatus {
     pub exit_code: Option<i32>,
     pub stdout: Option<String>,
     pub stderr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hs_err_log: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "errorCode")]
+    pub error_code: Option<LaunchErrorCode>,
+}
+
+/// Запущенная игра из реестра, для восстановления UI после перезагрузки webview
+#[derive(Debug, Serialize)]
+pub struct ProcessSummary {
+    pub process_id: String,
+    pub profile_id: String,
+    pub running: bool,
+    pub uptime_secs: u64,
+    /// PID в системе, чтобы сопоставить с внешними инструментами
+    pub pid: u32,
 }
 
 // Структура для отслеживания процессов
 struct GameProcess {
-    child: Child,
-    #[allow(dead_code)]
+    child: GameChild,
+    profile_id: String,
     start_time: Instant,
-    stdout: Arc<Mutex<Vec<u8>>>,
-    stderr: Arc<Mutex<Vec<u8>>>,
+    started_at: SystemTime,
+    game_dir: String,
+    max_ram: String,
+    post_exit: Option<Vec<String>>,
+    stdout: Arc<Mutex<LogBuffer>>,
+    stderr: Arc<Mutex<LogBuffer>>,
+    /// Поток, читающий stderr; у восстановленных процессов вывода нет
+    stderr_reader: Option<thread::JoinHandle<()>>,
+    /// Игру сейчас убивают (см. ProcessRegistry::kill_games), ее завершение - не падение
+    killing: bool,
+}
+
+/// Процесс игры: запущенный этим лаунчером или восстановленный по PID после его перезапуска
+enum GameChild {
+    Spawned(Child),
+    /// start_time - время старта по данным ОС, защищает от переиспользования PID
+    Attached { pid: u32, start_time: u64 },
 }
 
-// Глобальное хранилище процессов (безопасное)
-static PROCESSES: OnceLock<Mutex<HashMap<String, Box<GameProcess>>>> = OnceLock::new();
-static NEXT_ID: Mutex<u64> = Mutex::new(1);
+/// Итог завершения игры; у восстановленных процессов код выхода неизвестен
+#[derive(Debug, Clone, Copy)]
+struct GameExit {
+    code: Option<i32>,
+    success: bool,
+}
+
+/// Допустимое расхождение времени старта процесса и записи в RUNNING_GAMES_FILE
+const PID_START_TOLERANCE_SECS: u64 = 10;
+
+impl GameChild {
+    fn id(&self) -> u32 {
+        match self {
+            GameChild::Spawned(child) => child.id(),
+            GameChild::Attached { pid, .. } => *pid,
+        }
+    }
+
+    fn try_wait(&mut self) -> std::io::Result<Option<GameExit>> {
+        match self {
+            GameChild::Spawned(child) => Ok(child.try_wait()?.map(|status| GameExit {
+                code: status.code(),
+                success: status.success(),
+            })),
+            // Код выхода чужого процесса не получить, поэтому завершение считаем штатным
+            GameChild::Attached { pid, start_time } => {
+                let running = is_same_process(*pid, *start_time);
+                Ok((!running).then_some(GameExit { code: None, success: true }))
+            }
+        }
+    }
+
+    fn kill_target(&self) -> KillTarget {
+        match self {
+            GameChild::Spawned(child) => KillTarget { pid: child.id(), start_time: None },
+            GameChild::Attached { pid, start_time } => KillTarget { pid: *pid, start_time: Some(*start_time) },
+        }
+    }
+
+    /// Дерево процессов убить не удалось: убить хотя бы сам процесс игры, если он наш
+    fn kill_alone(&mut self, tree_error: std::io::Error) -> std::io::Result<()> {
+        match self {
+            GameChild::Spawned(child) => {
+                eprintln!(
+                    "[Launcher Backend] ⚠️ Failed to kill process tree of PID {}: {}, killing the process only",
+                    child.id(), tree_error
+                );
+                child.kill()
+            }
+            GameChild::Attached { .. } => Err(tree_error),
+        }
+    }
+
+    /// Дождаться убитого процесса, чтобы на Unix не остался зомби; восстановленный - не наш ребенок
+    fn reap(&mut self) {
+        if let GameChild::Spawned(child) = self {
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Что нужно, чтобы убить игру без блокировки реестра
+#[derive(Debug, Clone, Copy)]
+struct KillTarget {
+    pid: u32,
+    /// Только у восстановленных процессов, см. GameChild::Attached
+    start_time: Option<u64>,
+}
+
+impl KillTarget {
+    fn kill_tree(self) -> std::io::Result<()> {
+        match self.start_time {
+            // PID мог достаться другому процессу после последней проверки реапера
+            Some(start_time) if !is_same_process(self.pid, start_time) => {
+                eprintln!("[Launcher Backend] Game PID {} has already exited, nothing to kill", self.pid);
+                Ok(())
+            }
+            _ => process_tuning::kill_process_tree_by_pid(self.pid),
+        }
+    }
+}
+
+/// PID по-прежнему принадлежит процессу, стартовавшему в start_time
+fn is_same_process(pid: u32, start_time: u64) -> bool {
+    system_info::process_start_time(pid)
+        .is_some_and(|started| started.abs_diff(start_time) <= PID_START_TOLERANCE_SECS)
+}
+
+/// Файл в каталоге данных со списком запущенных игр, см. ProcessRegistry::restore
+const RUNNING_GAMES_FILE: &str = "running_games.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedProcess {
+    process_id: String,
+    pid: u32,
+    profile_id: String,
+    game_dir: String,
+    max_ram: String,
+    /// Секунды с эпохи Unix
+    started_at: u64,
+    #[serde(default)]
+    post_exit: Option<Vec<String>>,
+}
+
+fn running_games_path() -> Option<PathBuf> {
+    paths::app_data_dir().map(|dir| dir.join(RUNNING_GAMES_FILE))
+}
+
+/**
+ * Сохранить реестр в path после каждого изменения
+ * Ошибка записи не мешает игре, только восстановлению после перезапуска
+ */
+fn persist_processes(path: &Path, processes: &HashMap<String, GameProcess>) {
+
+    let records: Vec<PersistedProcess> = processes.iter()
+        .map(|(process_id, game_process)| PersistedProcess {
+            process_id: process_id.clone(),
+            pid: game_process.child.id(),
+            profile_id: game_process.profile_id.clone(),
+            game_dir: game_process.game_dir.clone(),
+            max_ram: game_process.max_ram.clone(),
+            started_at: game_process.started_at
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            post_exit: game_process.post_exit.clone(),
+        })
+        .collect();
+
+    let result = serde_json::to_vec_pretty(&records)
+        .map_err(|e| e.to_string())
+        .and_then(|content| fs_utils::write_atomic(path, &content));
+    if let Err(e) = result {
+        eprintln!("[Launcher Backend] ⚠️ Failed to save running games: {}", e);
+    }
+}
+
+/// Запущенные игры. Регистрируется в run() через `app.manage()`,
+/// команды получают его как `State<'_, ProcessRegistry>`
+pub struct ProcessRegistry {
+    processes: Mutex<HashMap<String, GameProcess>>,
+    next_id: AtomicU64,
+    /// Убивать игры при выходе из лаунчера (см. handle_launcher_exit)
+    kill_on_exit: AtomicBool,
+    /// Куда сохраняется список игр (RUNNING_GAMES_FILE); None - только в памяти
+    state_file: Option<PathBuf>,
+}
+
+impl Default for ProcessRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessRegistry {
+    /// Реестр только в памяти, без RUNNING_GAMES_FILE
+    pub fn new() -> Self {
+        ProcessRegistry {
+            processes: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            kill_on_exit: AtomicBool::new(false),
+            state_file: None,
+        }
+    }
+
+    /**
+     * Реестр с играми, которые пережили перезапуск лаунчера
+     * Процесс восстанавливается, если PID жив и время его старта совпадает с записью;
+     * остальные записи удаляются из файла. Вывод и код выхода таких игр недоступны
+     */
+    pub fn restore() -> Self {
+        let registry = ProcessRegistry {
+            state_file: running_games_path(),
+            ..Self::new()
+        };
+        let Some(path) = &registry.state_file else {
+            return registry;
+        };
+        let Ok(content) = fs::read_to_string(path) else {
+            return registry;
+        };
+        let records: Vec<PersistedProcess> = serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("[Launcher Backend] ⚠️ Failed to parse {}: {}", path.display(), e);
+            Vec::new()
+        });
+
+        let mut processes = HashMap::new();
+        let mut last_id = 0;
+        for record in records {
+            let start_time = system_info::process_start_time(record.pid)
+                .filter(|started| started.abs_diff(record.started_at) <= PID_START_TOLERANCE_SECS);
+            let Some(start_time) = start_time else {
+                eprintln!("[Launcher Backend] Game {} (PID {}) is no longer running", record.process_id, record.pid);
+                continue;
+            };
+
+            if let Some(id) = record.process_id.strip_prefix("game_process_").and_then(|id| id.parse::<u64>().ok()) {
+                last_id = last_id.max(id);
+            }
+
+            let started_at = SystemTime::UNIX_EPOCH + Duration::from_secs(record.started_at);
+            let uptime = SystemTime::now().duration_since(started_at).unwrap_or_default();
+            eprintln!("[Launcher Backend] Reattached to game {} (PID {})", record.process_id, record.pid);
+            processes.insert(record.process_id, GameProcess {
+                child: GameChild::Attached { pid: record.pid, start_time },
+                profile_id: record.profile_id,
+                start_time: Instant::now().checked_sub(uptime).unwrap_or_else(Instant::now),
+                started_at,
+                game_dir: record.game_dir,
+                max_ram: record.max_ram,
+                post_exit: record.post_exit,
+                stdout: Arc::new(Mutex::new(LogBuffer::new(DEFAULT_OUTPUT_BUFFER_BYTES))),
+                stderr: Arc::new(Mutex::new(LogBuffer::new(DEFAULT_OUTPUT_BUFFER_BYTES))),
+                stderr_reader: None,
+                killing: false,
+            });
+        }
+
+        registry.persist(&processes);
+        *registry.processes.lock().unwrap_or_else(|e| e.into_inner()) = processes;
+        registry.next_id.store(last_id + 1, Ordering::Relaxed);
+        registry
+    }
+
+    fn next_process_id(&self) -> String {
+        format!("game_process_{}", self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<String, GameProcess>>, String> {
+        self.processes.lock()
+            .map_err(|_| "Process registry is poisoned".to_string())
+    }
+
+    fn persist(&self, processes: &HashMap<String, GameProcess>) {
+        if let Some(path) = &self.state_file {
+            persist_processes(path, processes);
+        }
+    }
+
+    /**
+     * Убить игру вместе с дочерними процессами
+     * Из реестра убирается только убитая игра, иначе работающая игра потеряется
+     */
+    pub fn kill(&self, process_id: &str) -> Result<(), LauncherError> {
+        if !self.lock()?.contains_key(process_id) {
+            return Err(LauncherError::process_not_found(process_id));
+        }
+
+        match self.kill_games(&[process_id.to_string()])?.pop() {
+            Some((_, Err(e))) => Err(LauncherError::io("Failed to kill process", e)),
+            // Пока блокировки не было, игра могла завершиться сама и уйти из реестра
+            Some((_, Ok(()))) | None => Ok(()),
+        }
+    }
+
+    /**
+     * Убить все игры из реестра (вместе с дочерними процессами)
+     * Возвращает id убитых; игры, которые убить не удалось, остаются в реестре
+     */
+    pub fn kill_all(&self) -> Result<Vec<String>, String> {
+        let process_ids: Vec<String> = self.lock()?.keys().cloned().collect();
+
+        let mut killed: Vec<String> = self.kill_games(&process_ids)?
+            .into_iter()
+            .filter_map(|(process_id, result)| result.is_ok().then_some(process_id))
+            .collect();
+        killed.sort();
+        Ok(killed)
+    }
+
+    /**
+     * Убить игры и убрать из реестра те, что удалось убить; возвращает итог по каждой
+     * Убиваем без блокировки реестра: на Windows это запуск taskkill. Пока идет kill, игры
+     * помечены killing, и наблюдатели не принимают их завершение за падение. Убитый Child
+     * дожидаемся через wait(), чтобы на Unix не оставался зомби
+     */
+    fn kill_games(&self, process_ids: &[String]) -> Result<Vec<(String, std::io::Result<()>)>, String> {
+        let targets: Vec<(String, KillTarget)> = {
+            let mut processes = self.lock()?;
+            process_ids.iter()
+                .filter_map(|process_id| {
+                    let game_process = processes.get_mut(process_id)?;
+                    game_process.killing = true;
+                    Some((process_id.clone(), game_process.child.kill_target()))
+                })
+                .collect()
+        };
+
+        let results: Vec<(String, std::io::Result<()>)> = targets.into_iter()
+            .map(|(process_id, target)| (process_id, target.kill_tree()))
+            .collect();
+
+        let mut processes = self.lock()?;
+        let results = results.into_iter()
+            .filter_map(|(process_id, result)| {
+                let game_process = processes.get_mut(&process_id)?;
+                let result = result.or_else(|e| game_process.child.kill_alone(e));
+                match &result {
+                    Ok(()) => {
+                        game_process.child.reap();
+                        processes.remove(&process_id);
+                        eprintln!("[Launcher Backend] Process {} killed", process_id);
+                    }
+                    Err(e) => {
+                        game_process.killing = false;
+                        eprintln!("[Launcher Backend] ❌ Failed to kill process {}: {}", process_id, e);
+                    }
+                }
+                Some((process_id, result))
+            })
+            .collect();
+        self.persist(&processes);
+        Ok(results)
+    }
+
+    /// Убить игры, если включен set_kill_games_on_exit; возвращает id убитых
+    fn kill_on_launcher_exit(&self) -> Vec<String> {
+        if !self.kill_on_exit.load(Ordering::Relaxed) {
+            return Vec::new();
+        }
+
+        match self.kill_all() {
+            Ok(killed) => {
+                if !killed.is_empty() {
+                    eprintln!("[Launcher Backend] Killed {} game(s) on exit: {:?}", killed.len(), killed);
+                }
+                killed
+            }
+            Err(e) => {
+                eprintln!("[Launcher Backend] ❌ Failed to kill games on exit: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Убрать из реестра завершившиеся процессы и вернуть их вместе с итогом завершения
+    fn cleanup_dead_processes(&self) -> Vec<(String, GameProcess, GameExit)> {
+        let mut processes = match self.lock() {
+            Ok(processes) => processes,
+            Err(e) => {
+                eprintln!("[Launcher Backend] {}", e);
+                return Vec::new();
+            }
+        };
+
+        // Процессы, которые еще работают (или статус не удалось получить), остаются
+        let exited: Vec<(String, GameExit)> = processes.iter_mut()
+            .filter(|(_, process)| !process.killing)
+            .filter_map(|(id, process)| match process.child.try_wait() {
+                Ok(Some(exit)) => Some((id.clone(), exit)),
+                _ => None,
+            })
+            .collect();
+        if exited.is_empty() {
+            return Vec::new();
+        }
+
+        let reaped = exited.into_iter()
+            .filter_map(|(id, exit)| {
+                let process = processes.remove(&id)?;
+                eprintln!("[Launcher Backend] Cleaned up dead process: {}", id);
+                Some((id, process, exit))
+            })
+            .collect();
+        self.persist(&processes);
+        reaped
+    }
+
+    /// Все отслеживаемые запуски, сначала самые старые
+    pub fn summaries(&self) -> Result<Vec<ProcessSummary>, LauncherError> {
+        let mut processes = self.lock()?;
+        let mut summaries: Vec<ProcessSummary> = processes.iter_mut()
+            .map(|(process_id, game_process)| ProcessSummary {
+                process_id: process_id.clone(),
+                profile_id: game_process.profile_id.clone(),
+                // Ошибку проверки статуса считаем работающим процессом, как в check_game_process
+                running: !matches!(game_process.child.try_wait(), Ok(Some(_))),
+                uptime_secs: game_process.start_time.elapsed().as_secs(),
+                pid: game_process.child.id(),
+            })
+            .collect();
+
+        summaries.sort_by_key(|summary| std::cmp::Reverse(summary.uptime_secs));
+        Ok(summaries)
+    }
+}
+
+/// Как часто фоновая задача убирает из реестра завершившиеся игры
+const REAP_INTERVAL: Duration = Duration::from_secs(5);
+
+/**
+ * Фоновая задача: периодически вызывает cleanup_dead_processes и отправляет game-exited
+ * Страхует слежение за отдельными процессами: событие отправляет тот, кто первым убрал
+ * процесс из реестра, поэтому о каждом завершении сообщается один раз
+ */
+pub fn spawn_process_reaper(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(REAP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+
+            let app_handle = app_handle.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let registry = app_handle.state::<ProcessRegistry>();
+                for (process_id, game_process, exit) in registry.cleanup_dead_processes() {
+                    report_game_exit(&app_handle, process_id, game_process, exit);
+                }
+            })
+            .await;
+            if let Err(e) = result {
+                eprintln!("[Launcher Backend] Process reaper failed: {}", e);
+            }
+        }
+    });
+}
 
 #[tauri::command]
 pub async fn launch_game_client(
     launch_params: LaunchParams,
     app_handle: tauri::AppHandle,
-) -> Result<LaunchResult, String> {
-    // Инициализируем хранилище процессов при первом использовании
-    PROCESSES.get_or_init(|| Mutex::new(HashMap::new()));
-
-    let process_id = generate_process_id();
+    registry: tauri::State<'_, ProcessRegistry>,
+) -> Result<LaunchResult, LauncherError> {
+    let process_id = registry.next_process_id();
 
     // Клонируем значения для проверки директорий до их перемещения в args
     let game_dir = launch_params.game_dir.clone();
@@ -88,330 +655,1222 @@ pub async fn launch_game_client(
     eprintln!("Game Dir: {}", game_dir);
     eprintln!("Assets Dir: {}", assets_dir);
     eprintln!("Main Class: {}", launch_params.main_class);
-    eprintln!("RAM: {} MB", launch_params.ram);
+    eprintln!("RAM: {} MB (min: {:?})", launch_params.max_ram, launch_params.min_ram);
     eprintln!("Username: {}", launch_params.username);
     eprintln!("Profile ID: {}", launch_params.profile_id);
+    eprintln!("Version: {} (asset index: {})", launch_params.version, launch_params.asset_index);
     eprintln!("Server: {:?}", launch_params.server_address);
     eprintln!("==============================");
 
-    // 1. Проверяем и извлекаем native библиотеки перед запуском
-    eprintln!("[Launcher Backend] Checking and extracting native libraries...");
-    match prepare_natives(&game_dir) {
-        Ok(_) => {
-            eprintln!("[Launcher Backend] ✅ Native libraries prepared successfully");
+    // Classpath собираем заранее: его записи проверяются до запуска
+    let new_class_path = build_class_path(&launch_params);
+    // java -version и проверки файлов блокируют поток, поэтому не в async-рантайме
+    let preflight = {
+        let (params, class_path) = (launch_params.clone(), new_class_path.clone());
+        tokio::task::spawn_blocking(move || preflight_check(&params, &class_path))
+            .await
+            .map_err(|e| format!("Pre-flight task failed: {}", e))?
+    };
+    let Preflight { java, mut warnings } = match preflight {
+        Ok(preflight) => preflight,
+        Err((code, message)) => return Ok(LaunchResult::failure(code, message)),
+    };
+
+    // 1. Проверяем и извлекаем native библиотеки перед запуском (свой natives_dir готовит фронтенд)
+    let natives_dir = natives_dir(&launch_params);
+    if launch_params.natives_dir.is_empty() {
+        eprintln!("[Launcher Backend] Checking and extracting native libraries...");
+        match prepare_natives(&game_dir) {
+            Ok(_) => {
+                eprintln!("[Launcher Backend] ✅ Native libraries prepared successfully");
+            }
+            Err(e) => {
+                eprintln!("[Launcher Backend] ❌ Failed to prepare native libraries: {}", e);
+                return Err(format!("Failed to prepare native libraries: {}", e).into());
+            }
         }
-        Err(e) => {
-            eprintln!("[Launcher Backend] ❌ Failed to prepare native libraries: {}", e);
-            return Err(format!("Failed to prepare native libraries: {}", e));
+    } else if !natives_dir.is_dir() {
+        eprintln!("[Launcher Backend] ⚠️ Natives directory does not exist: {}", natives_dir.display());
+    }
+
+    if let Some(cores) = &launch_params.cpu_affinity {
+        if let Err(e) = process_tuning::validate_cpu_affinity(cores) {
+            return Ok(LaunchResult::failure(LaunchErrorCode::InvalidLaunchOptions, e));
         }
     }
 
+    let debug_agent = match launch_params.debug_port.map(jdwp_agent_arg).transpose() {
+        Ok(agent) => agent,
+        Err(e) => return Ok(LaunchResult::failure(LaunchErrorCode::InvalidLaunchOptions, e)),
+    };
+    let (max_ram_mb, min_ram_mb) = heap_sizes(&launch_params.max_ram, launch_params.min_ram.as_deref())
+        .map_err(LauncherError::invalid_params)?;
+
+    // 32-битная JVM не выделит больше ~1.5 ГБ: игра упадет сразу после запуска
+    if max_ram_mb > MAX_32BIT_HEAP_MB && !java.is_64_bit {
+        warnings.push(format!(
+            "{} is a 32-bit Java and cannot use {} MB of memory. Install a 64-bit Java or lower the RAM allocation.",
+            java.path.display(),
+            max_ram_mb
+        ));
+    }
+
+    // Не блокируем запуск: свободная память меняется, а ОС может вытеснить другие процессы в swap
+    warnings.extend(memory_warning(max_ram_mb, &system_info::memory_info()));
+
     // Подготовка командной строки для Java
-    let mut cmd = Command::new(&launch_params.java_path);
+    let mut cmd = java_command(&launch_params.java_path, launch_params.wrapper_command.as_deref());
 
-    
     // Classpath
-    let cp_separator = if cfg!(target_os = "windows") { ";" } else { ":" };
-    let mut new_class_path = Vec::new();
+    let full_class_path = join_classpath_entries(&new_class_path).map_err(LauncherError::invalid_params)?;
+    // Пустой -cp JVM принимает за текущую директорию, поэтому флаг не передаем
+    if full_class_path.is_empty() {
+        eprintln!("[Launcher Backend] ⚠️ Class path is empty, launching without -cp");
+    } else {
+        cmd.arg("-cp").arg(&full_class_path);
+    }
+
+
+    // JVM аргументы
+    cmd.arg(format!("-Xmx{}m", max_ram_mb))
+       .arg(format!("-Xms{}m", min_ram_mb));
+
+    // Устанавливаем java.library.path
+    eprintln!("[Launcher Backend] Setting java.library.path to: {}", natives_dir.to_string_lossy());
+    cmd.arg(library_path_arg(&natives_dir));
+
+    // Подстановка ${...} в пользовательские аргументы (как в манифестах Mojang)
+    let template_vars = launch_template_vars(&launch_params, &natives_dir, &full_class_path);
+    let jvm_args = arg_template::substitute_all(&launch_params.jvm_args, &jvm_template_vars(&template_vars));
+
+    // LWJGL на macOS создает окно только из главного потока, без флага игра падает при старте
+    if needs_start_on_first_thread(std::env::consts::OS, &jvm_args, launch_params.disable_start_on_first_thread) {
+        cmd.arg(START_ON_FIRST_THREAD);
+    }
+
+    // Агент отладчика идет перед пользовательскими аргументами
+    if let (Some(port), Some(agent)) = (launch_params.debug_port, &debug_agent) {
+        eprintln!("[Launcher Backend] 🐞 JDWP debugger enabled, attach to localhost:{}", port);
+        cmd.arg(agent);
+    }
+
+    // Добавляем пользовательские JVM аргументы
+    for arg in &jvm_args {
+        cmd.arg(arg);
+    }
+
+    // Структурированный вывод Log4j в отдельный файл (только если версия использует Log4j)
+    if launch_params.structured_logs {
+        if let Some(flag) = log4j::prepare_log4j_config(Path::new(&game_dir), &new_class_path, &jvm_args) {
+            eprintln!("[Launcher Backend] Structured Log4j output enabled");
+            cmd.arg(flag);
+        }
+    }
+
+    // Основной класс
+    cmd.arg(&launch_params.main_class);
+
+    // Аргументы клиента
+    let args = build_client_args(&launch_params, &template_vars, launch_params.game_args.as_deref());
+
+    // Добавляем все аргументы в команду
+    for arg in args {
+        cmd.arg(arg);
+    }
+
+    // Проверка и создание assets директории
+    eprintln!("Checking assets directory: {}", assets_dir);
+    let assets_dir_path = std::path::Path::new(&assets_dir);
+    if !assets_dir_path.exists() {
+        eprintln!("Assets directory does not exist, creating...");
+        if let Err(e) = std::fs::create_dir_all(assets_dir_path) {
+            return Ok(LaunchResult::failure(
+                classify_launch_error(LaunchStage::AssetsDir, &e),
+                format!("Failed to create assets directory '{}': {}", assets_dir, e),
+            ));
+        }
+        eprintln!("Assets directory created successfully");
+    }
+    eprintln!("Assets directory validated: {}", assets_dir);
+
+    // Установка рабочего каталога
+    eprintln!("Setting current directory to: {}", game_dir);
+    cmd.current_dir(&game_dir);
+
+    apply_env_vars(&mut cmd, &launch_params.env_vars)?;
+
+    // Логируем полную команду
+    eprintln!("=== Launching Java Process ===");
+    eprintln!("Command: {:?}", cmd);
+    eprintln!("============================");
+
+    // Redirect stdout and stderr to pipes to capture output
+    cmd.stdout(std::process::Stdio::piped());
+    cmd.stderr(std::process::Stdio::piped());
+
+    // Своя группа процессов, чтобы kill_game_process завершал и дочерние процессы игры
+    process_tuning::isolate_process_group(&mut cmd);
+
+    // Скрываем консольное окно на Windows
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    if let Some(hook) = launch_params.pre_launch.clone() {
+        // Хук ждет завершения команды пользователя, не блокируем им поток tokio
+        let hook_dir = game_dir.clone();
+        let result = tokio::task::spawn_blocking(move || launch_hooks::run_hook("pre-launch", &hook, &hook_dir))
+            .await
+            .unwrap_or_else(|e| Err(format!("Pre-launch hook task failed: {}", e)));
+        if let Err(e) = result {
+            eprintln!("[Launcher Backend] ❌ {}", e);
+            return Ok(LaunchResult::failure(LaunchErrorCode::PreLaunchHookFailed, e));
+        }
+    }
+
+    // Запуск процесса
+    match cmd.spawn() {
+        Ok(mut child) => {
+            // Создаем буферы для вывода (хранят только последние output_buffer_bytes)
+            let buffer_bytes = launch_params.output_buffer_bytes.unwrap_or(DEFAULT_OUTPUT_BUFFER_BYTES);
+            let stdout = Arc::new(Mutex::new(LogBuffer::new(buffer_bytes)));
+            let stderr = Arc::new(Mutex::new(LogBuffer::new(buffer_bytes)));
+
+            // Привязка к ядрам не критична: при ошибке игра просто работает без нее
+            if let Some(cores) = &launch_params.cpu_affinity {
+                if let Err(e) = process_tuning::apply_cpu_affinity(&child, cores) {
+                    eprintln!("[Launcher Backend] ⚠️ Failed to set CPU affinity: {}", e);
+                }
+            }
+            if let Err(e) = process_tuning::apply_process_priority(&child, launch_params.process_priority) {
+                eprintln!("[Launcher Backend] ⚠️ Failed to set process priority: {}", e);
+            }
+
+            // Забираем потоки до создания замыканий
+            let stdout_reader = child.stdout.take().expect("Failed to open stdout");
+            let stderr_reader = child.stderr.take().expect("Failed to open stderr");
+
+            // Поток на каждый вывод: копит полный лог и шлет строки во фронтенд
+            spawn_output_reader(stdout_reader, stdout.clone(), "stdout", process_id.clone(), app_handle.clone());
+            let stderr_reader = spawn_output_reader(stderr_reader, stderr.clone(), "stderr", process_id.clone(), app_handle.clone());
+
+            // Сохраняем процесс
+            let game_process = GameProcess {
+                child: GameChild::Spawned(child),
+                profile_id: launch_params.profile_id.clone(),
+                start_time: Instant::now(),
+                started_at: SystemTime::now(),
+                game_dir: game_dir.clone(),
+                max_ram: launch_params.max_ram.clone(),
+                post_exit: launch_params.post_exit.clone(),
+                stdout,
+                stderr,
+                stderr_reader: Some(stderr_reader),
+                killing: false,
+            };
+
+            {
+                let mut processes = registry.lock()?;
+                processes.insert(process_id.clone(), game_process);
+                registry.persist(&processes);
+            }
+            spawn_exit_watcher(process_id.clone(), app_handle.clone());
+
+            // Отправляем уведомление об успешном запуске
+            if let Err(e) = app_handle.emit("game-launched", &process_id) {
+                eprintln!("Failed to emit game-launched event: {}", e);
+            }
 
-    for path_item in &launch_params.class_path {
+            // Сворачиваем лаунчер, если пользователь это включил
+            if launch_params.minimize_on_launch {
+                if let Some(window) = app_handle.get_webview_window("main") {
+                    if let Err(e) = window.minimize() {
+                        eprintln!("Failed to minimize launcher window: {}", e);
+                    }
+                }
+            }
+
+            // Только рекомендация, переменные окружения не выставляем
+            if cfg!(target_os = "linux") {
+                let gpus = tokio::task::spawn_blocking(gpu::list_gpus).await.unwrap_or_default();
+                warnings.extend(gpu::offload_recommendation(&gpus));
+            }
+            for warning in &warnings {
+                eprintln!("[Launcher Backend] ⚠️ {}", warning);
+            }
+            let warning = (!warnings.is_empty()).then(|| warnings.join("\n"));
+
+            Ok(LaunchResult {
+                success: true,
+                process_id: Some(process_id),
+                error: None,
+                error_code: None,
+                warning,
+            })
+        }
+        Err(e) => {
+            let error_msg = format!("Failed to launch game: {}. Check if Java is installed and configured correctly.", e);
+            eprintln!("[ERROR] Java path: {}", launch_params.java_path);
+            eprintln!("[ERROR] Game directory: {}", game_dir);
+            eprintln!("[ERROR] Working directory: {:?}", cmd.get_current_dir());
+            eprintln!("[ERROR] Full error: {:?}", e);
+
+            Ok(LaunchResult::failure(
+                classify_launch_error(LaunchStage::Spawn, &e),
+                error_msg,
+            ))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn check_game_process(
+    process_id: String,
+    registry: tauri::State<'_, ProcessRegistry>,
+) -> Result<ProcessStatus, LauncherError> {
+    let mut processes = registry.lock()?;
+    if let Some(game_process) = processes.get_mut(&process_id) {
+        // Проверяем статус процесса
+        match game_process.child.try_wait() {
+            Ok(Some(exit)) => {
+                let exit_code = exit.code;
+                let stdout = game_process.stdout.lock()
+                    .map(|buf| buf.to_string_lossy())
+                    .unwrap_or_default();
+                let stderr = game_process.stderr.lock()
+                    .map(|buf| buf.to_string_lossy())
+                    .unwrap_or_default();
+
+                // При аварийном завершении ищем дамп падения JVM
+                let hs_err_log = if exit.success {
+                    None
+                } else {
+                    find_hs_err_log(&game_process.game_dir, Some(game_process.started_at))
+                        .map(|p| p.to_string_lossy().to_string())
+                };
+
+                let failure = if exit.success {
+                    None
+                } else {
+                    classify_exit_output(&stderr, &game_process.max_ram)
+                };
+                let (error_code, error) = match failure {
+                    Some((code, message)) => (Some(code), Some(message)),
+                    None => (None, None),
+                };
+
+                Ok(ProcessStatus {
+                    running: false,
+                    exit_code,
+                    stdout: Some(stdout),
+                    stderr: Some(stderr),
+                    hs_err_log,
+                    error,
+                    error_code,
+                })
+            }
+            Ok(None) => {
+                // Процесс все еще работает
+                Ok(ProcessStatus {
+                    running: true,
+                    exit_code: None,
+                    stdout: None,
+                    stderr: None,
+                    hs_err_log: None,
+                    error: None,
+                    error_code: None,
+                })
+            }
+            Err(_) => {
+                // Ошибка проверки статуса
+                Ok(ProcessStatus {
+                    running: true,
+                    exit_code: None,
+                    stdout: None,
+                    stderr: None,
+                    hs_err_log: None,
+                    error: None,
+                    error_code: None,
+                })
+            }
+        }
+    } else {
+        Err(LauncherError::process_not_found(&process_id))
+    }
+}
+
+#[tauri::command]
+pub async fn list_game_processes(
+    registry: tauri::State<'_, ProcessRegistry>,
+) -> Result<Vec<ProcessSummary>, LauncherError> {
+    registry.summaries()
+}
+
+#[tauri::command]
+pub async fn kill_game_process(
+    process_id: String,
+    registry: tauri::State<'_, ProcessRegistry>,
+) -> Result<bool, LauncherError> {
+    registry.kill(&process_id)?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn kill_all_processes(
+    registry: tauri::State<'_, ProcessRegistry>,
+) -> Result<Vec<String>, LauncherError> {
+    Ok(registry.kill_all()?)
+}
+
+/// Choose whether quitting the launcher kills running games (off by default)
+#[tauri::command]
+pub async fn set_kill_games_on_exit(
+    enabled: bool,
+    registry: tauri::State<'_, ProcessRegistry>,
+) -> Result<(), LauncherError> {
+    registry.kill_on_exit.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+/**
+ * Выход из лаунчера (RunEvent::ExitRequested)
+ * По умолчанию игры продолжают работать, как в большинстве лаунчеров, и восстанавливаются
+ * при следующем запуске. С set_kill_games_on_exit(true) они синхронно убиваются здесь
+ */
+pub fn handle_launcher_exit(app_handle: &tauri::AppHandle) {
+    app_handle.state::<ProcessRegistry>().kill_on_launcher_exit();
+}
+
+#[tauri::command]
+pub async fn get_hs_err_report(game_dir: String) -> Result<String, LauncherError> {
+    let path = find_hs_err_log(&game_dir, None)
+        .ok_or_else(|| LauncherError::not_found(format!("No hs_err_pid*.log found in '{}'", game_dir)))?;
+
+    fs::read_to_string(&path)
+        .map_err(|e| LauncherError::io(&format!("Failed to read JVM crash log {:?}", path), e))
+}
+
+#[tauri::command]
+pub async fn path_separator() -> String {
+    classpath_separator().to_string()
+}
+
+#[tauri::command]
+pub async fn join_classpath(entries: Vec<String>) -> Result<String, LauncherError> {
+    join_classpath_entries(&entries).map_err(LauncherError::invalid_params)
+}
+
+// Вспомогательные функции
+
+/// Сообщения JVM, когда не удалось выделить кучу заданного размера
+const HEAP_FAILURE_PATTERNS: [&str; 3] = [
+    "Could not reserve enough space for object heap",
+    "Invalid maximum heap size",
+    "There is insufficient memory for the Java Runtime Environment to continue",
+];
+
+/**
+ * Распознать известные причины падения JVM по stderr
+ * Возвращает код ошибки и понятное пользователю сообщение
+ */
+pub fn classify_exit_output(stderr: &str, max_ram: &str) -> Option<(LaunchErrorCode, String)> {
+    if HEAP_FAILURE_PATTERNS.iter().any(|p| stderr.contains(p)) {
+        let suggested_mb = system_info::memory_info().suggested_max_ram_mb;
+        return Some((LaunchErrorCode::OutOfMemory, heap_failure_message(max_ram, suggested_mb)));
+    }
+
+    None
+}
+
+/**
+ * Сообщение о нехватке памяти для кучи с рекомендуемым размером (system_info::suggest_ram_for)
+ * Рекомендацию показываем, только если она меньше выделенного
+ */
+fn heap_failure_message(max_ram: &str, suggested_mb: u32) -> String {
+    let lower = match max_ram.trim().parse::<u64>() {
+        Ok(max_ram_mb) if (suggested_mb as u64) < max_ram_mb => {
+            format!("Lower the RAM allocation to {} MB", suggested_mb)
+        }
+        _ => "Lower the RAM allocation".to_string(),
+    };
+    format!(
+        "Java could not reserve {} MB of memory for the game. {} \
+         or use a 64-bit Java (32-bit Java is limited to about 1.5 GB).",
+        max_ram, lower
+    )
+}
+
+/// Как часто поток-наблюдатель проверяет, завершилась ли игра
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize)]
+struct GameExited {
+    process_id: String,
+    exit_code: Option<i32>,
+    /// Ненулевой код выхода или завершение по сигналу
+    crashed: bool,
+    duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hs_err_log: Option<String>,
+    /// Отчет игры из <game_dir>/crash-reports, созданный за время этого запуска
+    #[serde(skip_serializing_if = "Option::is_none")]
+    crash_report: Option<String>,
+    /// Первые CRASH_REPORT_EXCERPT_LINES строк отчета
+    #[serde(skip_serializing_if = "Option::is_none")]
+    crash_report_excerpt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "errorCode")]
+    error_code: Option<LaunchErrorCode>,
+}
+
+/// Сколько строк отчета о падении отправлять в game-exited
+const CRASH_REPORT_EXCERPT_LINES: usize = 40;
+
+/**
+ * Запустить слежение за играми, восстановленными ProcessRegistry::restore
+ */
+pub fn watch_restored_processes(app_handle: &tauri::AppHandle) {
+    let registry = app_handle.state::<ProcessRegistry>();
+    let process_ids: Vec<String> = match registry.lock() {
+        Ok(processes) => processes.keys().cloned().collect(),
+        Err(e) => {
+            eprintln!("[Launcher Backend] {}", e);
+            return;
+        }
+    };
+    for process_id in process_ids {
+        spawn_exit_watcher(process_id, app_handle.clone());
+    }
+}
+
+/**
+ * Ждет завершения процесса, убирает его из реестра и отправляет событие game-exited
+ * Child остается в реестре, чтобы check_game_process и kill_game_process продолжали работать,
+ * поэтому статус опрашивается через try_wait, а не блокирующим wait
+ */
+fn spawn_exit_watcher(process_id: String, app_handle: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(EXIT_POLL_INTERVAL);
+
+        let registry = app_handle.state::<ProcessRegistry>();
+        let mut processes = match registry.lock() {
+            Ok(processes) => processes,
+            Err(e) => {
+                eprintln!("[Launcher Backend] {}", e);
+                return;
+            }
+        };
+
+        // Процесс уже убран из реестра через kill_game_process
+        let Some(game_process) = processes.get_mut(&process_id) else {
+            return;
+        };
+        if game_process.killing {
+            continue;
+        }
+        let exit = match game_process.child.try_wait() {
+            Ok(Some(exit)) => exit,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("[Launcher Backend] Failed to check process {}: {}", process_id, e);
+                continue;
+            }
+        };
+        let Some(game_process) = processes.remove(&process_id) else {
+            return;
+        };
+        registry.persist(&processes);
+        drop(processes);
+
+        report_game_exit(&app_handle, process_id, game_process, exit);
+        return;
+    });
+}
+
+/**
+ * Собрать диагностику завершившейся игры, отправить game-exited и выполнить post-exit
+ */
+fn report_game_exit(app_handle: &tauri::AppHandle, process_id: String, mut game_process: GameProcess, exit: GameExit) {
+    let crashed = !exit.success;
+    let duration = game_process.start_time.elapsed();
+    let (hs_err_log, failure) = if crashed {
+        // Ошибка JVM может быть еще в канале, а не в буфере
+        if let Some(reader) = game_process.stderr_reader.take() {
+            wait_for_output_reader(reader, OUTPUT_DRAIN_TIMEOUT);
+        }
+        let stderr = game_process.stderr.lock()
+            .map(|buf| buf.to_string_lossy())
+            .unwrap_or_default();
+        (
+            find_hs_err_log(&game_process.game_dir, Some(game_process.started_at))
+                .map(|p| p.to_string_lossy().to_string()),
+            classify_exit_output(&stderr, &game_process.max_ram),
+        )
+    } else {
+        (None, None)
+    };
+    let (error_code, error) = match failure {
+        Some((code, message)) => (Some(code), Some(message)),
+        None => (None, None),
+    };
+    let crash_report = if crashed {
+        find_crash_report(&game_process.game_dir, game_process.started_at)
+    } else {
+        None
+    };
+    let crash_report_excerpt = crash_report.as_deref().and_then(|path| read_head_lines(path, CRASH_REPORT_EXCERPT_LINES));
+    if let Some(path) = &crash_report {
+        eprintln!("[Launcher Backend] Crash report found: {}", path.display());
+    }
+
+    eprintln!(
+        "[Launcher Backend] Process {} exited with {:?} after {}s",
+        process_id,
+        exit.code,
+        duration.as_secs()
+    );
+
+    let payload = GameExited {
+        process_id,
+        exit_code: exit.code,
+        crashed,
+        duration_ms: duration.as_millis() as u64,
+        hs_err_log,
+        crash_report: crash_report.map(|path| path.to_string_lossy().to_string()),
+        crash_report_excerpt,
+        error,
+        error_code,
+    };
+    if let Err(e) = app_handle.emit("game-exited", payload) {
+        eprintln!("Failed to emit game-exited event: {}", e);
+    }
+
+    if let Some(hook) = &game_process.post_exit {
+        if let Err(e) = launch_hooks::run_hook("post-exit", hook, &game_process.game_dir) {
+            eprintln!("[Launcher Backend] ⚠️ {}", e);
+        }
+    }
+}
+
+/// Сколько ждать, пока поток вывода дочитает канал завершившейся игры
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/**
+ * Дождаться, пока поток вывода дочитает канал до EOF
+ * Если канал держит оставшийся дочерний процесс игры, ждем не дольше timeout
+ */
+fn wait_for_output_reader(reader: thread::JoinHandle<()>, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    while !reader.is_finished() {
+        if Instant::now() >= deadline {
+            eprintln!("[Launcher Backend] ⚠️ Game output is still open after exit, classifying what was read");
+            return;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    let _ = reader.join();
+}
+
+/// Строка без перевода строки длиннее этого все равно отправляется во фронтенд
+const MAX_LOG_LINE_BYTES: usize = 64 * 1024;
+
+#[derive(Clone, Serialize)]
+struct GameLogLine<'a> {
+    process_id: &'a str,
+    stream: &'static str,
+    line: String,
+}
+
+/**
+ * Читает вывод процесса в буфер и отправляет каждую строку событием game-log
+ * Поток завершается на EOF, то есть когда канал закрыли все процессы игры
+ */
+fn spawn_output_reader<R: std::io::Read + Send + 'static>(
+    mut reader: R,
+    output: Arc<Mutex<LogBuffer>>,
+    stream: &'static str,
+    process_id: String,
+    app_handle: tauri::AppHandle,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let emit_line = |bytes: &[u8]| {
+            let line = String::from_utf8_lossy(bytes).trim_end_matches(['\r', '\n']).to_string();
+            let payload = GameLogLine { process_id: &process_id, stream, line };
+            if let Err(e) = app_handle.emit("game-log", payload) {
+                eprintln!("[Launcher Backend] Failed to emit game-log event: {}", e);
+            }
+        };
+
+        let mut buffer = [0; 1024];
+        let mut pending = Vec::new();
+        while let Ok(n) = reader.read(&mut buffer) {
+            if n == 0 { break; }
+            if let Ok(mut output_buf) = output.lock() {
+                output_buf.push(&buffer[..n]);
+            }
+
+            pending.extend_from_slice(&buffer[..n]);
+            drain_log_lines(&mut pending, &emit_line);
+        }
+
+        // Хвост без перевода строки после завершения процесса
+        if !pending.is_empty() {
+            emit_line(&pending);
+        }
+    })
+}
+
+/**
+ * Отдать все завершенные строки из pending, остаток ждет следующего чтения
+ * Остаток длиннее MAX_LOG_LINE_BYTES отдается целиком, чтобы буфер не рос без предела
+ */
+fn drain_log_lines(pending: &mut Vec<u8>, mut emit_line: impl FnMut(&[u8])) {
+    while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+        let line: Vec<u8> = pending.drain(..=pos).collect();
+        emit_line(&line);
+    }
+    if pending.len() >= MAX_LOG_LINE_BYTES {
+        emit_line(pending);
+        pending.clear();
+    }
+}
+
+/// Разделитель classpath: `;` на Windows, `:` на остальных платформах
+pub fn classpath_separator() -> &'static str {
+    if cfg!(target_os = "windows") { ";" } else { ":" }
+}
+
+/**
+ * Итоговые аргументы клиента для версии, как их передал бы launch_game_client
+ * Токен доступа в результате заменен на <redacted>
+ */
+#[tauri::command]
+pub async fn resolve_client_args(version_json: String, params: LaunchParams) -> Result<Vec<String>, LauncherError> {
+    let version: VersionJson = serde_json::from_str(&version_json)
+        .map_err(|e| format!("Failed to parse version json: {}", e))?;
+    preview_client_args(&version, params)
+}
+
+/**
+ * game_args из version json (как в build_launch_params), затем те же шаги, что в launch_game_client
+ */
+fn preview_client_args(version: &VersionJson, mut params: LaunchParams) -> Result<Vec<String>, LauncherError> {
+    let version_vars = launch_builder::version_template_vars(version, &params)?;
+    params.game_args = Some(launch_builder::version_game_args(version, &version_vars));
+
+    let class_path = join_classpath_entries(&build_class_path(&params))?;
+    let template_vars = launch_template_vars(&params, &natives_dir(&params), &class_path);
+    let args = build_client_args(&params, &template_vars, params.game_args.as_deref());
+
+    // Заменяем только аргумент целиком: токен может совпасть с частью пути или имени.
+    // Офлайн-заглушка ("0") - не секрет
+    let token = params.access_token.as_str();
+    if is_offline_launch(&params) {
+        return Ok(args);
+    }
+    Ok(args.into_iter()
+        .map(|arg| if arg == token { "<redacted>".to_string() } else { arg })
+        .collect())
+}
+
+/**
+ * Собрать список путей classpath из профиля
+ * Элемент "libraries" раскрывается во все .jar из <game_dir>/libraries
+ */
+pub(crate) fn build_class_path(params: &LaunchParams) -> Vec<String> {
+    let game_dir = Path::new(&params.game_dir);
+    let mut class_path = Vec::new();
+
+    for path_item in &params.class_path {
         if path_item == "libraries" {
-            let libraries_dir = std::path::Path::new(&game_dir).join("libraries");
+            let libraries_dir = game_dir.join("libraries");
             if libraries_dir.is_dir() {
                 // Recursively find all .jar files
                 let walker = walkdir::WalkDir::new(libraries_dir);
                 for entry in walker.into_iter().filter_map(|e| e.ok()) {
                     if entry.file_type().is_file() && entry.path().extension().and_then(std::ffi::OsStr::to_str) == Some("jar") {
-                        new_class_path.push(entry.path().to_string_lossy().to_string());
+                        class_path.push(entry.path().to_string_lossy().to_string());
                     }
                 }
             }
         } else {
-            new_class_path.push(std::path::Path::new(&game_dir).join(path_item).to_string_lossy().to_string());
+            class_path.push(game_dir.join(path_item).to_string_lossy().to_string());
+        }
+    }
+
+    class_path
+}
+
+/// Клиент требует непустой --accessToken, в офлайне подойдет любой
+const OFFLINE_ACCESS_TOKEN: &str = "0";
+
+/**
+ * Запуск без аккаунта: явный флаг offline, пустой токен или заглушка вместо него
+ */
+fn is_offline_launch(params: &LaunchParams) -> bool {
+    params.offline || is_offline_token(&params.access_token)
+}
+
+/// Фронтенд без входа в аккаунт присылает "demo"
+fn is_offline_token(token: &str) -> bool {
+    matches!(token.trim(), "" | "0" | "null" | "offline" | "demo")
+}
+
+/**
+ * Каталог для -Djava.library.path
+ */
+pub(crate) fn natives_dir(params: &LaunchParams) -> PathBuf {
+    if params.natives_dir.is_empty() {
+        Path::new(&params.game_dir).join("natives")
+    } else {
+        PathBuf::from(&params.natives_dir)
+    }
+}
+
+/// Аргумент JVM с каталогом нативных библиотек
+fn library_path_arg(natives_dir: &Path) -> String {
+    format!("-Djava.library.path={}", natives_dir.to_string_lossy())
+}
+
+/**
+ * Значения ${...} для аргументов запуска
+ */
+pub(crate) fn launch_template_vars(params: &LaunchParams, natives_dir: &Path, class_path: &str) -> TemplateVars {
+    let mut vars = TemplateVars::new();
+    // Офлайн-запуск: без токена, UUID как у ванильного сервера в offline-mode
+    let offline = is_offline_launch(params);
+    let access_token = if offline { OFFLINE_ACCESS_TOKEN.to_string() } else { params.access_token.clone() };
+    let uuid = if params.uuid.trim().is_empty() {
+        accounts::offline_uuid(&params.username)
+    } else {
+        params.uuid.clone()
+    };
+
+    vars.set("game_directory", params.game_dir.clone())
+        .set("assets_root", params.assets_dir.clone())
+        .set("game_assets", params.assets_dir.clone())
+        .set("auth_player_name", params.username.clone())
+        .set("auth_uuid", uuid)
+        .set("auth_access_token", access_token.clone())
+        .set("auth_session", access_token)
+        .set("auth_xuid", "")
+        .set("clientid", "")
+        .set("user_type", if offline { "legacy" } else { "mojang" })
+        .set("user_properties", "{}")
+        .set("version_name", params.version.clone())
+        .set("version_type", "release")
+        .set("assets_index_name", params.asset_index.clone())
+        .set("resolution_width", params.resolution.width.to_string())
+        .set("resolution_height", params.resolution.height.to_string())
+        .set("natives_directory", natives_dir.to_string_lossy().to_string())
+        .set("classpath", class_path);
+    vars
+}
+
+/// Переменные с токеном доступа: в JVM-аргументах (-D...) токен виден в jcmd, hs_err и логах
+/// JVM, поэтому туда они не подставляются
+const SECRET_TEMPLATE_VARS: &[&str] = &["auth_access_token", "auth_session"];
+
+/**
+ * Значения ${...} для JVM-аргументов: launch_template_vars без токена доступа
+ */
+pub(crate) fn jvm_template_vars(vars: &TemplateVars) -> TemplateVars {
+    vars.without(SECRET_TEMPLATE_VARS)
+}
+
+/**
+ * Аргументы клиента: пользовательские, затем из version json (если есть) либо стандартный набор,
+ * затем размер окна и адрес сервера
+ */
+fn build_client_args(params: &LaunchParams, vars: &TemplateVars, version_args: Option<&[String]>) -> Vec<String> {
+    let var = |name: &str| vars.get(name).unwrap_or_default().to_string();
+
+    let mut args = arg_template::substitute_all(&params.client_args, vars);
+
+    match version_args {
+        Some(templates) => args.extend(arg_template::substitute_all(templates, vars)),
+        None => {
+            args.extend(vec![
+                "--username".to_string(),
+                params.username.clone(),
+                "--uuid".to_string(),
+                var("auth_uuid"),
+                "--accessToken".to_string(),
+                var("auth_access_token"),
+                "--version".to_string(),
+                var("version_name"),
+                "--gameDir".to_string(),
+                params.game_dir.clone(),
+                "--assetsDir".to_string(),
+                params.assets_dir.clone(),
+            ]);
+
+            // Старые клиенты не знают --assetIndex
+            let asset_index = var("assets_index_name");
+            if !asset_index.is_empty() {
+                args.push("--assetIndex".to_string());
+                args.push(asset_index);
+            }
+
+            args.extend(vec![
+                "--userType".to_string(),
+                var("user_type"),
+                "--versionType".to_string(),
+                var("version_type"),
+            ]);
+        }
+    }
+
+    // Размер окна (в version json эти аргументы за фичей has_custom_resolution, которую мы не включаем)
+    if params.full_screen {
+        args.push("--fullscreen".to_string());
+    } else {
+        args.extend(vec![
+            "--width".to_string(),
+            params.resolution.width.to_string(),
+            "--height".to_string(),
+            params.resolution.height.to_string(),
+        ]);
+    }
+
+    // Добавляем аргументы сервера если есть
+    if let Some(address) = &params.server_address {
+        args.extend(server_args(&params.version, address, params.server_port));
+    }
+
+    args
+}
+
+/**
+ * Аргументы прямого подключения к серверу
+ * С 1.20 (снапшот 23w14a) клиент игнорирует --server/--port и ждет --quickPlayMultiplayer host:port
+ */
+pub(crate) fn server_args(version: &str, address: &str, port: Option<i32>) -> Vec<String> {
+    if !supports_quick_play(version) {
+        let mut args = vec!["--server".to_string(), address.to_string()];
+        if let Some(port) = port {
+            args.push("--port".to_string());
+            args.push(port.to_string());
+        }
+        return args;
+    }
+
+    let target = match port {
+        // IPv6-адрес с портом записывается в квадратных скобках
+        Some(port) if address.contains(':') && !address.starts_with('[') => format!("[{}]:{}", address, port),
+        Some(port) => format!("{}:{}", address, port),
+        None => address.to_string(),
+    };
+    vec!["--quickPlayMultiplayer".to_string(), target]
+}
+
+/// Снапшот, с которого появился Quick Play: 23w14a
+const QUICK_PLAY_SNAPSHOT: (u32, u32) = (23, 14);
+
+fn supports_quick_play(version: &str) -> bool {
+    if let Some((minor, _)) = version_json::release_number(version) {
+        return minor >= 20;
+    }
+
+    // Снапшоты вида 24w14a: год и неделя
+    let version = version.trim();
+    let snapshot = version.split_once('w').and_then(|(year, rest)| {
+        let week: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        Some((year.parse::<u32>().ok()?, week.parse::<u32>().ok()?))
+    });
+    snapshot.is_some_and(|snapshot| snapshot >= QUICK_PLAY_SNAPSHOT)
+}
+
+/// Результат проверок перед запуском
+struct Preflight {
+    /// Java, которой будет запущена игра (`path` - сам исполняемый файл)
+    java: java_locator::JavaInstallation,
+    warnings: Vec<String>,
+}
+
+/**
+ * Проверки перед запуском, чтобы вместо невнятной ошибки Command::spawn
+ * показать пользователю, что именно не так. Отсутствующая игровая директория создается
+ */
+fn preflight_check(params: &LaunchParams, class_path: &[String]) -> Result<Preflight, (LaunchErrorCode, String)> {
+    let java = java_locator::resolve_java_command(&params.java_path).ok_or_else(|| {
+        (
+            LaunchErrorCode::JavaNotFound,
+            format!("Java executable '{}' was not found. Check the Java path in settings.", params.java_path),
+        )
+    })?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&java).map(|m| m.permissions().mode()).unwrap_or(0);
+        if mode & 0o111 == 0 {
+            return Err((
+                LaunchErrorCode::JavaNotExecutable,
+                format!("Java executable '{}' is not executable", java.display()),
+            ));
         }
     }
 
-    let full_class_path = new_class_path.join(cp_separator);
-    cmd.arg("-cp").arg(full_class_path);
+    let mut warnings = Vec::new();
+    let java_info = java_locator::probe_java_binary(&java);
+    eprintln!("[Launcher Backend] Java version: {} (major: {:?})", java_info.version, java_info.major_version);
+    if let Err(message) = java_locator::java_meets_requirement(&java_info, &params.version) {
+        // Слишком старая Java не загрузит классы игры; слишком новая иногда работает - только предупреждаем
+        let too_old = matches!(
+            (java_info.major_version, java_locator::java_requirement(&params.version)),
+            (Some(major), Some(requirement)) if major < requirement.min
+        );
+        if too_old {
+            return Err((LaunchErrorCode::JavaIncompatible, message));
+        }
+        warnings.push(message);
+    }
+
+    let game_dir = Path::new(&params.game_dir);
+    if !game_dir.exists() {
+        eprintln!("[Launcher Backend] Game directory does not exist, creating: {}", params.game_dir);
+        fs::create_dir_all(game_dir).map_err(|e| {
+            (
+                classify_launch_error(LaunchStage::GameDir, &e),
+                format!("Failed to create game directory '{}': {}", params.game_dir, e),
+            )
+        })?;
+    }
+    if !game_dir.is_dir() {
+        return Err((
+            LaunchErrorCode::GameDirMissing,
+            format!("Path '{}' is not a valid directory", params.game_dir),
+        ));
+    }
+
+    if params.main_class.trim().is_empty() {
+        return Err((
+            LaunchErrorCode::MainClassMissing,
+            "Main class is not set for this profile".to_string(),
+        ));
+    }
 
+    if let Some(missing) = class_path.iter().find(|entry| !Path::new(entry).exists()) {
+        return Err((
+            LaunchErrorCode::ClassPathEntryMissing,
+            format!("Class path entry '{}' does not exist. Try reinstalling the client files.", missing),
+        ));
+    }
 
-    // JVM аргументы
-    cmd.arg("-Xmx".to_string() + &launch_params.ram + "m")
-       .arg("-Xms".to_string() + &launch_params.ram + "m");
+    eprintln!("[Launcher Backend] ✅ Pre-flight checks passed (java: {})", java.display());
+    Ok(Preflight { java: java_info, warnings })
+}
 
-    // Устанавливаем java.library.path
-    let natives_dir = std::path::Path::new(&game_dir).join("natives");
-    eprintln!("[Launcher Backend] Setting java.library.path to: {}", natives_dir.to_string_lossy());
-    cmd.arg(format!("-Djava.library.path={}", natives_dir.to_string_lossy()));
-    
-    // Добавляем пользовательские JVM аргументы
-    for arg in &launch_params.jvm_args {
-        cmd.arg(arg);
-    }
+/// Больше 32-битная JVM обычно выделить не может
+const MAX_32BIT_HEAP_MB: u64 = 1536;
 
-    // Основной класс
-    cmd.arg(&launch_params.main_class);
+/// Начальный размер кучи, если min_ram не задан
+const DEFAULT_MIN_RAM_MB: u64 = 512;
 
-    // Аргументы клиента
-    let mut args = launch_params.client_args.clone();
-
-    // Добавляем стандартные аргументы
-    args.extend(vec![
-        "--username".to_string(),
-        launch_params.username,
-        "--uuid".to_string(),
-        launch_params.uuid,
-        "--accessToken".to_string(),
-        launch_params.access_token,
-        "--version".to_string(),
-        launch_params.profile_id.clone(), // Берем из профиля
-        "--gameDir".to_string(),
-        launch_params.game_dir.clone(),
-        "--assetsDir".to_string(),
-        launch_params.assets_dir,
-        "--assetIndex".to_string(),
-        "1.12".to_string(), // TODO: Должно браться из профиля
-        "--userType".to_string(),
-        "mojang".to_string(),
-        "--versionType".to_string(),
-        "release".to_string(),
-    ]);
+const START_ON_FIRST_THREAD: &str = "-XstartOnFirstThread";
 
-    // Добавляем аргументы сервера если есть
-    if let Some(address) = &launch_params.server_address {
-        args.push("--server".to_string());
-        args.push(address.clone());
+/**
+ * Нужно ли добавить -XstartOnFirstThread: только на macOS, если его еще нет в аргументах
+ * и пользователь не отключил
+ */
+pub(crate) fn needs_start_on_first_thread(os: &str, jvm_args: &[String], disabled: bool) -> bool {
+    os == "macos" && !disabled && !jvm_args.iter().any(|arg| arg == START_ON_FIRST_THREAD)
+}
 
-        if let Some(port) = launch_params.server_port {
-            args.push("--port".to_string());
-            args.push(port.to_string());
+/**
+ * Команда запуска java: напрямую или через обертку (`wrapper[0] wrapper[1..] java ...`)
+ * Пустая обертка игнорируется
+ */
+pub(crate) fn java_command(java_path: &str, wrapper: Option<&[String]>) -> Command {
+    match wrapper.and_then(|wrapper| wrapper.split_first()) {
+        Some((program, wrapper_args)) if !program.trim().is_empty() => {
+            eprintln!("[Launcher Backend] Launching through wrapper: {}", program);
+            let mut cmd = Command::new(program);
+            cmd.args(wrapper_args).arg(java_path);
+            cmd
         }
+        _ => Command::new(java_path),
     }
+}
 
-    // Добавляем все аргументы в команду
-    for arg in args {
-        cmd.arg(arg);
-    }
-    
+/**
+ * Проверить и разобрать размеры кучи (МБ) для -Xmx/-Xms
+ * Кривое значение иначе приводит к невнятной ошибке JVM
+ */
+fn heap_sizes(max_ram: &str, min_ram: Option<&str>) -> Result<(u64, u64), String> {
+    let parse = |name: &str, value: &str| -> Result<u64, String> {
+        match value.trim().parse::<u64>() {
+            Ok(mb) if mb > 0 => Ok(mb),
+            _ => Err(format!("Invalid {} '{}': expected a positive number of megabytes", name, value)),
+        }
+    };
 
+    let max = parse("max_ram", max_ram)?;
+    let min = match min_ram {
+        Some(min_ram) => parse("min_ram", min_ram)?,
+        None => DEFAULT_MIN_RAM_MB.min(max),
+    };
 
-    // Проверка и создание рабочего каталога
-    eprintln!("Checking game directory: {}", game_dir);
-    let game_dir_path = std::path::Path::new(&game_dir);
-    if !game_dir_path.exists() {
-        eprintln!("Game directory does not exist, creating...");
-        // Создаем директорию, если она не существует
-        std::fs::create_dir_all(game_dir_path)
-            .map_err(|e| format!("Failed to create game directory '{}': {}", game_dir, e))?;
-        eprintln!("Game directory created successfully");
+    if min > max {
+        return Err(format!("min_ram ({} MB) is larger than max_ram ({} MB)", min, max));
     }
 
-    // Проверяем, что путь действительно является директорией
-    if !game_dir_path.is_dir() {
-        return Err(format!("Path '{}' is not a valid directory", game_dir));
-    }
-    eprintln!("Game directory validated: {}", game_dir);
+    Ok((max, min))
+}
 
-    // Проверка и создание assets директории
-    eprintln!("Checking assets directory: {}", assets_dir);
-    let assets_dir_path = std::path::Path::new(&assets_dir);
-    if !assets_dir_path.exists() {
-        eprintln!("Assets directory does not exist, creating...");
-        std::fs::create_dir_all(assets_dir_path)
-            .map_err(|e| format!("Failed to create assets directory '{}': {}", assets_dir, e))?;
-        eprintln!("Assets directory created successfully");
+/**
+ * Предупреждение, если куча больше доступной сейчас памяти
+ * Неизвестный объем памяти (total_mb == 0) не проверяется
+ */
+fn memory_warning(max_ram_mb: u64, memory: &system_info::MemoryInfo) -> Option<String> {
+    if memory.total_mb == 0 || max_ram_mb <= memory.available_mb {
+        return None;
     }
-    eprintln!("Assets directory validated: {}", assets_dir);
+    Some(format!(
+        "{} MB of memory is allocated to the game, but only {} MB of {} MB is available. The game may stutter or crash; consider lowering the RAM allocation to {} MB.",
+        max_ram_mb,
+        memory.available_mb,
+        memory.total_mb,
+        memory.suggested_max_ram_mb
+    ))
+}
 
-    // Установка рабочего каталога
-    eprintln!("Setting current directory to: {}", game_dir);
-    cmd.current_dir(&game_dir);
+/**
+ * Добавить пользовательские переменные окружения к команде
+ * JVM-аргументы идут только в argv: _JAVA_OPTIONS не выставляем, иначе флаги дублируются
+ */
+fn apply_env_vars(cmd: &mut Command, env_vars: &HashMap<String, String>) -> Result<(), LauncherError> {
+    for (key, value) in env_vars {
+        if key.is_empty() || key.contains('=') || key.contains('\0') || value.contains('\0') {
+            return Err(LauncherError::invalid_params(format!("Invalid environment variable: {:?}", key)));
+        }
+        eprintln!("[Launcher Backend] Setting environment variable {}", key);
+        cmd.env(key, value);
+    }
+    Ok(())
+}
 
-    // Настройка переменных окружения
-    cmd.env("_JAVA_OPTIONS", &launch_params.jvm_args.join(" "));
+/**
+ * Аргумент JVM для запуска с отладчиком (JDWP), не останавливает игру при старте
+ * Слушает только 127.0.0.1: JDWP без аутентификации, подключившийся выполняет любой код
+ */
+fn jdwp_agent_arg(port: u16) -> Result<String, String> {
+    if port < 1024 {
+        return Err(format!("Debug port {} is not allowed, use a port between 1024 and 65535", port));
+    }
+    Ok(format!("-agentlib:jdwp=transport=dt_socket,server=y,suspend=n,address=127.0.0.1:{}", port))
+}
 
-    // Логируем полную команду
-    eprintln!("=== Launching Java Process ===");
-    eprintln!("Command: {:?}", cmd);
-    eprintln!("============================");
+/**
+ * Собрать classpath из списка путей
+ * Путь, содержащий сам разделитель, JVM молча разрежет на два - такое отклоняем
+ */
+pub fn join_classpath_entries(entries: &[String]) -> Result<String, String> {
+    let separator = classpath_separator();
+
+    if let Some(bad) = entries.iter().find(|e| e.contains(separator)) {
+        return Err(format!(
+            "Classpath entry '{}' contains the path separator '{}'",
+            bad, separator
+        ));
+    }
 
-    // Redirect stdout and stderr to pipes to capture output
-    cmd.stdout(std::process::Stdio::piped());
-    cmd.stderr(std::process::Stdio::piped());
+    Ok(entries.join(separator))
+}
 
-    // Скрываем консольное окно на Windows
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        cmd.creation_flags(CREATE_NO_WINDOW);
+/**
+ * Найти самый свежий hs_err_pid*.log (дамп падения JVM)
+ * JVM пишет его в рабочий каталог, поэтому проверяем game_dir и cwd лаунчера
+ */
+fn find_hs_err_log(game_dir: &str, since: Option<SystemTime>) -> Option<PathBuf> {
+    let mut search_dirs = vec![PathBuf::from(game_dir)];
+    if let Ok(cwd) = std::env::current_dir() {
+        if !search_dirs.contains(&cwd) {
+            search_dirs.push(cwd);
+        }
     }
 
-    // Запуск процесса
-    match cmd.spawn() {
-        Ok(mut child) => {
-            // Создаем буферы для вывода
-            let stdout = Arc::new(Mutex::new(Vec::new()));
-            let stderr = Arc::new(Mutex::new(Vec::new()));
+    newest_hs_err_log(&search_dirs, since)
+}
 
-            // Забираем потоки до создания замыканий
-            let stdout_reader = child.stdout.take().expect("Failed to open stdout");
-            let stderr_reader = child.stderr.take().expect("Failed to open stderr");
+fn newest_hs_err_log(search_dirs: &[PathBuf], since: Option<SystemTime>) -> Option<PathBuf> {
+    let mut newest: Option<(SystemTime, PathBuf)> = None;
 
-            let stdout_clone = stdout.clone();
-            let stderr_clone = stderr.clone();
-
-            // Поток для чтения stdout
-            thread::spawn(move || {
-                use std::io::Read;
-                let mut reader = stdout_reader;
-                let mut buffer = [0; 1024];
-                while let Ok(n) = reader.read(&mut buffer) {
-                    if n == 0 { break; }
-                    if let Ok(mut stdout_buf) = stdout_clone.lock() {
-                        stdout_buf.extend_from_slice(&buffer[..n]);
-                    }
-                }
-            });
+    for dir in search_dirs {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
 
-            // Поток для чтения stderr
-            thread::spawn(move || {
-                use std::io::Read;
-                let mut reader = stderr_reader;
-                let mut buffer = [0; 1024];
-                while let Ok(n) = reader.read(&mut buffer) {
-                    if n == 0 { break; }
-                    if let Ok(mut stderr_buf) = stderr_clone.lock() {
-                        stderr_buf.extend_from_slice(&buffer[..n]);
-                    }
-                }
-            });
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !file_name.starts_with("hs_err_pid") || !file_name.ends_with(".log") {
+                continue;
+            }
 
-            // Сохраняем процесс
-            let game_process = GameProcess {
-                child,
-                start_time: Instant::now(),
-                stdout,
-                stderr,
+            let modified = match entry.metadata().and_then(|m| m.modified()) {
+                Ok(modified) => modified,
+                Err(_) => continue,
             };
 
-            if let Some(processes_lock) = PROCESSES.get() {
-                let mut processes = processes_lock.lock().unwrap();
-                processes.insert(process_id.clone(), Box::new(game_process));
+            // Старые дампы от предыдущих запусков не интересны
+            if let Some(since) = since {
+                if modified < since {
+                    continue;
+                }
             }
 
-            // Отправляем уведомление об успешном запуске
-            if let Err(e) = app_handle.emit("game-launched", &process_id) {
-                eprintln!("Failed to emit game-launched event: {}", e);
+            let is_newer = match &newest {
+                Some((time, _)) => modified > *time,
+                None => true,
+            };
+            if is_newer {
+                newest = Some((modified, entry.path()));
             }
-
-            Ok(LaunchResult {
-                success: true,
-                process_id: Some(process_id),
-                error: None,
-            })
-        }
-        Err(e) => {
-            let error_msg = format!("Failed to launch game: {}. Check if Java is installed and configured correctly.", e);
-            eprintln!("[ERROR] {}", error_msg);
-            eprintln!("[ERROR] Java path: {}", launch_params.java_path);
-            eprintln!("[ERROR] Game directory: {}", game_dir);
-            eprintln!("[ERROR] Working directory: {:?}", cmd.get_current_dir());
-            eprintln!("[ERROR] Full error: {:?}", e);
-
-            Ok(LaunchResult {
-                success: false,
-                process_id: None,
-                error: Some(error_msg),
-            })
         }
     }
-}
 
-#[tauri::command]
-pub async fn check_game_process(
-    process_id: String,
-) -> Result<ProcessStatus, String> {
-    if let Some(processes_lock) = PROCESSES.get() {
-        let mut processes = processes_lock.lock().unwrap();
-        if let Some(game_process) = processes.get_mut(&process_id) {
-            // Проверяем статус процесса
-            match game_process.child.try_wait() {
-                Ok(Some(status)) => {
-                    let exit_code = status.code();
-                    let stdout = game_process.stdout.lock()
-                        .map(|buf| String::from_utf8_lossy(&buf).to_string())
-                        .unwrap_or_default();
-                    let stderr = game_process.stderr.lock()
-                        .map(|buf| String::from_utf8_lossy(&buf).to_string())
-                        .unwrap_or_default();
-
-                    Ok(ProcessStatus {
-                        running: false,
-                        exit_code,
-                        stdout: Some(stdout),
-                        stderr: Some(stderr),
-                    })
-                }
-                Ok(None) => {
-                    // Процесс все еще работает
-                    Ok(ProcessStatus {
-                        running: true,
-                        exit_code: None,
-                        stdout: None,
-                        stderr: None,
-                    })
-                }
-                Err(_) => {
-                    // Ошибка проверки статуса
-                    Ok(ProcessStatus {
-                        running: true,
-                        exit_code: None,
-                        stdout: None,
-                        stderr: None,
-                    })
-                }
-            }
-        } else {
-            Err("Process not found".to_string())
-        }
-    } else {
-        Err("Process manager not initialized".to_string())
-    }
+    newest.map(|(_, path)| path)
 }
 
-#[tauri::command]
-pub async fn kill_game_process(
-    process_id: String,
-) -> Result<bool, String> {
-    if let Some(processes_lock) = PROCESSES.get() {
-        let mut processes = processes_lock.lock().unwrap();
-        if let Some(mut game_process) = processes.remove(&process_id) {
-            match game_process.child.kill() {
-                Ok(_) => {
-                    println!("Process {} killed successfully", process_id);
-                    Ok(true)
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to kill process: {}", e);
-                    eprintln!("{}", error_msg);
-                    Err(error_msg)
-                }
-            }
-        } else {
-            Err("Process not found".to_string())
-        }
-    } else {
-        Err("Process manager not initialized".to_string())
-    }
+/**
+ * Найти самый свежий crash-reports/crash-*.txt, измененный после начала запуска
+ */
+fn find_crash_report(game_dir: &str, since: SystemTime) -> Option<PathBuf> {
+    fs::read_dir(Path::new(game_dir).join("crash-reports"))
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            file_name.starts_with("crash-") && file_name.ends_with(".txt")
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            (modified >= since).then(|| (modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
 }
 
-// Вспомогательные функции
-fn generate_process_id() -> String {
-    let mut id = NEXT_ID.lock().unwrap();
-    let current_id = *id;
-    *id += 1;
-    format!("game_process_{}", current_id)
+/**
+ * Первые max_lines строк текстового файла
+ */
+fn read_head_lines(path: &Path, max_lines: usize) -> Option<String> {
+    use std::io::{BufRead, BufReader};
+
+    let file = fs::File::open(path).ok()?;
+    let lines: Vec<String> = BufReader::new(file)
+        .lines()
+        .take(max_lines)
+        .map_while(Result::ok)
+        .collect();
+    Some(lines.join("\n"))
 }
 
 /**
@@ -502,7 +1961,7 @@ fn prepare_natives_local(game_dir: &str) -> Result<(), String> {
  * Собрать все нативные библиотеки из всех платформенных подпапок в корень natives
  * Это нужно для Java, которая ищет библиотеки только в корне java.library.path
  */
-fn consolidate_natives(natives_dir: &PathBuf) -> Result<(), String> {
+fn consolidate_natives(natives_dir: &Path) -> Result<(), String> {
     eprintln!("[Launcher Backend] Consolidating native libraries to root natives directory...");
 
     let platform_dirs = ["windows", "linux", "macos", "osx"];
@@ -558,7 +2017,7 @@ fn consolidate_natives(natives_dir: &PathBuf) -> Result<(), String> {
 /**
  * Проверить, существуют ли нативные библиотеки в корневой директории (не рекурсивно)
  */
-fn check_natives_exist_in_root(natives_dir: &PathBuf) -> bool {
+fn check_natives_exist_in_root(natives_dir: &Path) -> bool {
     if let Ok(entries) = fs::read_dir(natives_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
@@ -576,7 +2035,7 @@ fn check_natives_exist_in_root(natives_dir: &PathBuf) -> bool {
 /**
  * Найти все JAR файлы с "natives" в названии
  */
-fn find_natives_jars(libraries_dir: &PathBuf) -> Result<Vec<PathBuf>, String> {
+fn find_natives_jars(libraries_dir: &Path) -> Result<Vec<PathBuf>, String> {
     let mut natives_jars = Vec::new();
 
     if !libraries_dir.exists() {
@@ -609,7 +2068,7 @@ fn find_natives_jars(libraries_dir: &PathBuf) -> Result<Vec<PathBuf>, String> {
 /**
  * Извлечь нативные библиотеки из JAR файла в директорию natives
  */
-fn extract_natives_from_jar(jar_path: &PathBuf, natives_dir: &PathBuf) -> Result<(), String> {
+fn extract_natives_from_jar(jar_path: &Path, natives_dir: &Path) -> Result<(), String> {
     use zip::ZipArchive;
 
     // Открываем JAR файл как ZIP архив
@@ -659,27 +2118,736 @@ fn prepare_natives(game_dir: &str) -> Result<(), String> {
     prepare_natives_local(game_dir)
 }
 
-// Функция очистки мертвых процессов
-#[allow(dead_code)]
-pub fn cleanup_dead_processes() {
-    if let Some(processes_lock) = PROCESSES.get() {
-        let mut processes = processes_lock.lock().unwrap();
-        let mut dead_processes = Vec::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
 
-        for (id, process) in processes.iter_mut() {
-            match process.child.try_wait() {
-                Ok(_) => {
-                    dead_processes.push(id.clone());
-                }
-                Err(_) => {
-                    // Процесс все еще работает
+    fn touch(path: &Path, modified: SystemTime) {
+        File::create(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    /// Процесс-заглушка вместо игры в своей группе процессов, как при запуске
+    #[cfg(unix)]
+    fn spawn_game(registry: &ProcessRegistry, seconds: &str) -> String {
+        let mut cmd = Command::new("sleep");
+        cmd.arg(seconds);
+        register_game(registry, cmd)
+    }
+
+    /// Запустить команду и добавить ее в реестр как игру
+    #[cfg(unix)]
+    fn register_game(registry: &ProcessRegistry, mut cmd: Command) -> String {
+        process_tuning::isolate_process_group(&mut cmd);
+        let child = cmd.spawn().unwrap();
+
+        let process_id = registry.next_process_id();
+        let game_process = GameProcess {
+            child: GameChild::Spawned(child),
+            profile_id: "pack-1".to_string(),
+            start_time: Instant::now(),
+            started_at: SystemTime::now(),
+            game_dir: String::new(),
+            max_ram: "1024".to_string(),
+            post_exit: None,
+            stdout: Arc::new(Mutex::new(LogBuffer::new(1024))),
+            stderr: Arc::new(Mutex::new(LogBuffer::new(1024))),
+            stderr_reader: None,
+            killing: false,
+        };
+        registry.lock().unwrap().insert(process_id.clone(), game_process);
+        process_id
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn registry_handles_concurrent_launches_and_exits() {
+        const GAMES: usize = 50;
+        let registry = ProcessRegistry::new();
+        let launched = Mutex::new(Vec::new());
+        let reaped = Mutex::new(Vec::new());
+        let launching = AtomicBool::new(true);
+
+        thread::scope(|scope| {
+            // Как spawn_process_reaper: убирает завершившиеся игры, пока идут запуски
+            scope.spawn(|| {
+                while launching.load(Ordering::Relaxed) {
+                    let exited = registry.cleanup_dead_processes();
+                    reaped.lock().unwrap().extend(exited.into_iter().map(|(id, _, _)| id));
+                    thread::sleep(Duration::from_millis(5));
                 }
+            });
+
+            let launchers: Vec<_> = (0..GAMES)
+                .map(|i| {
+                    let (registry, launched) = (&registry, &launched);
+                    scope.spawn(move || {
+                        // Половина игр сразу завершается, половина работает до kill_all
+                        let id = spawn_game(registry, if i % 2 == 0 { "0" } else { "30" });
+                        launched.lock().unwrap().push(id);
+                    })
+                })
+                .collect();
+            for launcher in launchers {
+                launcher.join().unwrap();
+            }
+            launching.store(false, Ordering::Relaxed);
+        });
+
+        let mut launched = launched.into_inner().unwrap();
+        launched.sort();
+        launched.dedup();
+        assert_eq!(launched.len(), GAMES, "process ids must be unique");
+
+        // Дожидаемся завершения коротких игр
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut reaped = reaped.into_inner().unwrap();
+        while reaped.len() < GAMES / 2 && Instant::now() < deadline {
+            reaped.extend(registry.cleanup_dead_processes().into_iter().map(|(id, _, _)| id));
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(reaped.len(), GAMES / 2);
+
+        let killed = registry.kill_all().unwrap();
+        assert_eq!(killed.len(), GAMES / 2);
+        assert!(registry.lock().unwrap().is_empty());
+
+        // Каждая игра либо завершилась сама, либо убита - ровно один раз
+        let mut accounted: Vec<String> = reaped.into_iter().chain(killed).collect();
+        accounted.sort();
+        assert_eq!(accounted, launched);
+    }
+
+    /// Параметры запуска 1.12.2 с аккаунтом, как их присылает фронтенд
+    fn test_params() -> LaunchParams {
+        serde_json::from_value(serde_json::json!({
+            "profile_id": "pack-1",
+            "version": "1.12.2",
+            "asset_index": "1.12",
+            "username": "Steve",
+            "uuid": "0f1e2d3c-4b5a-6978-8796-a5b4c3d2e1f0",
+            "access_token": "secret-token",
+            "game_dir": "/games/pack-1",
+            "assets_dir": "/games/assets",
+            "resolution": { "width": 854, "height": 480 },
+            "full_screen": false,
+            "java_path": "java",
+            "java_version": "8",
+            "max_ram": "4096",
+            "jvm_args": [],
+            "client_args": [],
+            "main_class": "net.minecraft.launchwrapper.Launch",
+            "class_path": [],
+            "server_address": null,
+            "server_port": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn client_args_preview_matches_golden_file() {
+        let version: VersionJson = serde_json::from_str(include_str!("../tests/fixtures/version-1.20.4.json")).unwrap();
+        let mut params = test_params();
+        params.version = "1.20.4".to_string();
+        params.asset_index = "12".to_string();
+        // Редактируется только аргумент, равный токену целиком
+        params.client_args = vec!["--demoTag=secret-token-suffix".to_string()];
+        params.server_address = Some("mc.example.org".to_string());
+        params.server_port = Some(25565);
+
+        let args = preview_client_args(&version, params).unwrap();
+        let golden: Vec<&str> = include_str!("../tests/fixtures/client-args-1.20.4.txt").lines().collect();
+        assert_eq!(args, golden);
+    }
+
+    #[test]
+    fn debug_agent_listens_on_loopback_only() {
+        assert_eq!(
+            jdwp_agent_arg(5005).unwrap(),
+            "-agentlib:jdwp=transport=dt_socket,server=y,suspend=n,address=127.0.0.1:5005"
+        );
+        assert!(jdwp_agent_arg(80).is_err());
+    }
+
+    #[test]
+    fn access_token_is_not_substituted_into_jvm_args() {
+        let params = test_params();
+        let vars = launch_template_vars(&params, Path::new("/games/pack-1/natives"), "");
+        let templates = vec![
+            "-Dtoken=${auth_access_token}".to_string(),
+            "-Dsession=${auth_session}".to_string(),
+            "-Dplayer=${auth_player_name}".to_string(),
+        ];
+
+        let jvm_args = arg_template::substitute_all(&templates, &jvm_template_vars(&vars));
+        assert_eq!(jvm_args, vec!["-Dtoken=${auth_access_token}", "-Dsession=${auth_session}", "-Dplayer=Steve"]);
+        assert!(jvm_args.iter().all(|arg| !arg.contains("secret-token")));
+
+        // В аргументах клиента токен по-прежнему нужен
+        assert_eq!(vars.get("auth_access_token"), Some("secret-token"));
+    }
+
+    #[test]
+    fn classifies_spawn_errors() {
+        use std::io::{Error, ErrorKind};
+
+        let code = |stage, error: Error| classify_launch_error(stage, &error);
+        assert_eq!(code(LaunchStage::Spawn, Error::from(ErrorKind::NotFound)), LaunchErrorCode::JavaNotFound);
+        assert_eq!(
+            code(LaunchStage::Spawn, Error::from(ErrorKind::PermissionDenied)),
+            LaunchErrorCode::JavaNotExecutable
+        );
+        assert_eq!(code(LaunchStage::Spawn, Error::from(ErrorKind::OutOfMemory)), LaunchErrorCode::OutOfMemory);
+        #[cfg(unix)]
+        assert_eq!(code(LaunchStage::Spawn, Error::from_raw_os_error(8)), LaunchErrorCode::JavaNotExecutable);
+        assert_eq!(code(LaunchStage::Spawn, Error::other("boom")), LaunchErrorCode::Unknown);
+    }
+
+    #[test]
+    fn classifies_directory_errors() {
+        use std::io::{Error, ErrorKind};
+
+        let code = |stage, error: Error| classify_launch_error(stage, &error);
+        assert_eq!(code(LaunchStage::GameDir, Error::from(ErrorKind::NotFound)), LaunchErrorCode::GameDirMissing);
+        assert_eq!(
+            code(LaunchStage::AssetsDir, Error::from(ErrorKind::PermissionDenied)),
+            LaunchErrorCode::PermissionDenied
+        );
+        assert_eq!(code(LaunchStage::GameDir, Error::other("boom")), LaunchErrorCode::GameDirMissing);
+    }
+
+    #[test]
+    fn launch_failure_serializes_error_code() {
+        let result = LaunchResult::failure(LaunchErrorCode::JavaNotFound, "no java".into());
+        let json = serde_json::to_value(&result).unwrap();
+
+        assert_eq!(json["success"], false);
+        assert_eq!(json["errorCode"], "JavaNotFound");
+        assert_eq!(json["error"], "no java");
+        assert!(json.get("processId").is_none());
+    }
+
+    #[test]
+    fn heap_failure_suggests_a_smaller_heap() {
+        let stderr = "Error occurred during initialization of VM\nCould not reserve enough space for object heap\n";
+        let (code, _) = classify_exit_output(stderr, "16384").unwrap();
+        assert_eq!(code, LaunchErrorCode::OutOfMemory);
+        assert!(classify_exit_output("Exception in thread \"main\"", "4096").is_none());
+
+        assert!(heap_failure_message("16384", 4096).contains("Lower the RAM allocation to 4096 MB"));
+        // Рекомендация не больше выделенного - число не показываем
+        let message = heap_failure_message("2048", 4096);
+        assert!(message.contains("Lower the RAM allocation or use a 64-bit Java"));
+    }
+
+    #[test]
+    fn exit_is_classified_after_stderr_is_drained() {
+        let stderr = Arc::new(Mutex::new(LogBuffer::new(1024)));
+        let output = stderr.clone();
+        // Поток вывода дочитывает канал уже после того, как try_wait сообщил о выходе
+        let reader = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            output.lock().unwrap().push(b"Could not reserve enough space for object heap\n");
+        });
+
+        wait_for_output_reader(reader, Duration::from_secs(10));
+        let stderr = stderr.lock().unwrap().to_string_lossy();
+        assert_eq!(classify_exit_output(&stderr, "16384").unwrap().0, LaunchErrorCode::OutOfMemory);
+
+        // Канал, который держит оставшийся процесс, не задерживает отчет надолго
+        let started = Instant::now();
+        wait_for_output_reader(thread::spawn(|| thread::sleep(Duration::from_secs(3))), Duration::from_millis(50));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn hs_err_log_picks_newest_dump_since_launch() {
+        let dir = tempfile::tempdir().unwrap();
+        let launched = SystemTime::now() - Duration::from_secs(60);
+        touch(&dir.path().join("hs_err_pid100.log"), launched - Duration::from_secs(3600));
+        touch(&dir.path().join("hs_err_pid200.log"), launched + Duration::from_secs(10));
+        touch(&dir.path().join("hs_err_pid300.log"), launched + Duration::from_secs(20));
+        touch(&dir.path().join("hs_err_pid400.txt"), launched + Duration::from_secs(30));
+
+        let dirs = [dir.path().to_path_buf()];
+        assert_eq!(
+            newest_hs_err_log(&dirs, Some(launched)),
+            Some(dir.path().join("hs_err_pid300.log"))
+        );
+    }
+
+    #[test]
+    fn hs_err_log_ignores_dumps_from_previous_launches() {
+        let dir = tempfile::tempdir().unwrap();
+        let launched = SystemTime::now();
+        touch(&dir.path().join("hs_err_pid100.log"), launched - Duration::from_secs(3600));
+
+        let dirs = [dir.path().to_path_buf()];
+        assert_eq!(newest_hs_err_log(&dirs, Some(launched)), None);
+        // get_hs_err_report ищет без ограничения по времени
+        assert_eq!(newest_hs_err_log(&dirs, None), Some(dir.path().join("hs_err_pid100.log")));
+    }
+
+    #[test]
+    fn game_exited_event_carries_hs_err_log() {
+        let payload = GameExited {
+            process_id: "p".into(),
+            exit_code: Some(134),
+            crashed: true,
+            duration_ms: 1,
+            hs_err_log: Some("/games/hs_err_pid1.log".into()),
+            crash_report: None,
+            crash_report_excerpt: None,
+            error: None,
+            error_code: None,
+        };
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["hs_err_log"], "/games/hs_err_pid1.log");
+        assert!(json.get("crash_report").is_none());
+    }
+
+    #[test]
+    fn default_client_args_take_version_and_asset_index_from_params() {
+        let params = test_params();
+        let vars = launch_template_vars(&params, Path::new("/games/pack-1/natives"), "");
+        let args = build_client_args(&params, &vars, None);
+
+        let value = |flag: &str| args.iter().position(|arg| arg == flag).map(|i| args[i + 1].as_str());
+        assert_eq!(value("--version"), Some("1.12.2"));
+        assert_eq!(value("--assetIndex"), Some("1.12"));
+        assert_eq!(value("--accessToken"), Some("secret-token"));
+
+        // Старые клиенты не знают --assetIndex
+        let mut params = test_params();
+        params.asset_index = String::new();
+        let vars = launch_template_vars(&params, Path::new("/games/pack-1/natives"), "");
+        assert!(!build_client_args(&params, &vars, None).contains(&"--assetIndex".to_string()));
+    }
+
+    #[test]
+    fn classpath_uses_platform_separator() {
+        let expected = if std::env::consts::OS == "windows" { ";" } else { ":" };
+        assert_eq!(classpath_separator(), expected);
+
+        let entries = vec!["a.jar".to_string(), "b.jar".to_string()];
+        assert_eq!(join_classpath_entries(&entries).unwrap(), format!("a.jar{}b.jar", expected));
+        assert_eq!(join_classpath_entries(&[]).unwrap(), "");
+
+        // Такой путь JVM разрезала бы на два
+        let bad = vec![format!("mods{}evil.jar", expected)];
+        assert!(join_classpath_entries(&bad).is_err());
+    }
+
+    #[test]
+    fn window_size_or_fullscreen_flags() {
+        let natives = Path::new("/games/pack-1/natives");
+        let windowed = test_params();
+        let args = build_client_args(&windowed, &launch_template_vars(&windowed, natives, ""), None);
+        assert!(args.windows(4).any(|w| w == ["--width", "854", "--height", "480"]));
+        assert!(!args.contains(&"--fullscreen".to_string()));
+
+        let mut fullscreen = test_params();
+        fullscreen.full_screen = true;
+        let args = build_client_args(&fullscreen, &launch_template_vars(&fullscreen, natives, ""), None);
+        assert!(args.contains(&"--fullscreen".to_string()));
+        assert!(!args.contains(&"--width".to_string()) && !args.contains(&"--height".to_string()));
+    }
+
+    #[test]
+    fn heap_sizes_default_and_validation() {
+        assert_eq!(heap_sizes("4096", Some("1024")), Ok((4096, 1024)));
+        assert_eq!(heap_sizes("4096", None), Ok((4096, DEFAULT_MIN_RAM_MB)));
+        // Минимум по умолчанию не превышает максимум
+        assert_eq!(heap_sizes("256", None), Ok((256, 256)));
+
+        assert!(heap_sizes("4G", None).unwrap_err().contains("max_ram"));
+        assert!(heap_sizes("0", None).is_err());
+        assert!(heap_sizes("4096", Some("-1")).unwrap_err().contains("min_ram"));
+        assert!(heap_sizes("1024", Some("2048")).unwrap_err().contains("larger than max_ram"));
+    }
+
+    #[test]
+    fn launch_env_has_no_java_options_by_default() {
+        let mut cmd = Command::new("java");
+        apply_env_vars(&mut cmd, &test_params().env_vars).unwrap();
+        assert!(cmd.get_envs().all(|(key, _)| key != "_JAVA_OPTIONS"));
+    }
+
+    #[test]
+    fn log_lines_split_on_newlines_and_flush_long_lines() {
+        let mut lines = Vec::new();
+        let mut pending = b"first\r\nsecond\npart".to_vec();
+        drain_log_lines(&mut pending, |line| lines.push(line.to_vec()));
+        assert_eq!(lines, vec![b"first\r\n".to_vec(), b"second\n".to_vec()]);
+        assert_eq!(pending, b"part");
+
+        // Строка без перевода строки не копится дольше MAX_LOG_LINE_BYTES
+        lines.clear();
+        let mut pending = vec![b'x'; MAX_LOG_LINE_BYTES];
+        drain_log_lines(&mut pending, |line| lines.push(line.to_vec()));
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].len(), MAX_LOG_LINE_BYTES);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn preflight_reports_missing_java() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut params = test_params();
+        params.java_path = dir.path().join("no-such-java").to_string_lossy().to_string();
+        params.game_dir = dir.path().to_string_lossy().to_string();
+
+        let (code, message) = preflight_check(&params, &[]).err().unwrap();
+        assert_eq!(code, LaunchErrorCode::JavaNotFound);
+        assert!(message.contains("no-such-java"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn preflight_reports_missing_classpath_jar() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let java = dir.path().join("java");
+        fs::write(&java, "#!/bin/sh\necho 'openjdk version \"1.8.0_392\"' >&2\n").unwrap();
+        fs::set_permissions(&java, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut params = test_params();
+        params.java_path = java.to_string_lossy().to_string();
+        // Отсутствующий game_dir создается, а не считается ошибкой
+        params.game_dir = dir.path().join("game").to_string_lossy().to_string();
+        let missing_jar = dir.path().join("libraries/missing.jar").to_string_lossy().to_string();
+
+        let (code, message) = preflight_check(&params, std::slice::from_ref(&missing_jar)).err().unwrap();
+        assert_eq!(code, LaunchErrorCode::ClassPathEntryMissing);
+        assert!(message.contains(&missing_jar));
+        assert!(dir.path().join("game").is_dir());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn summaries_list_every_tracked_launch() {
+        let registry = ProcessRegistry::new();
+        let finished = spawn_game(&registry, "0");
+        let running = spawn_game(&registry, "30");
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let summaries = loop {
+            let summaries = registry.summaries().unwrap();
+            let done = summaries.iter().any(|s| s.process_id == finished && !s.running);
+            if done || Instant::now() > deadline {
+                break summaries;
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        assert_eq!(summaries.len(), 2);
+        let find = |id: &str| summaries.iter().find(|s| s.process_id == id).unwrap();
+        assert!(!find(&finished).running);
+        assert!(find(&running).running);
+        assert!(summaries.iter().all(|s| s.profile_id == "pack-1" && s.pid > 0));
+
+        registry.kill_all().unwrap();
+    }
+
+    #[test]
+    fn library_path_points_at_natives_dir() {
+        let mut params = test_params();
+        assert_eq!(
+            library_path_arg(&natives_dir(&params)),
+            format!("-Djava.library.path={}", Path::new("/games/pack-1").join("natives").display())
+        );
+
+        params.natives_dir = "/games/pack-1/natives-1.20.4".to_string();
+        assert_eq!(library_path_arg(&natives_dir(&params)), "-Djava.library.path=/games/pack-1/natives-1.20.4");
+    }
+
+    #[test]
+    fn demo_token_and_offline_flag_launch_offline() {
+        let natives = Path::new("/games/pack-1/natives");
+        let value = |args: &[String], flag: &str| args.iter().position(|arg| arg == flag).map(|i| args[i + 1].clone());
+        let client_args = |params: &LaunchParams| build_client_args(params, &launch_template_vars(params, natives, ""), None);
+
+        let mut demo = test_params();
+        demo.access_token = "demo".to_string();
+        let args = client_args(&demo);
+        assert_eq!(value(&args, "--accessToken").as_deref(), Some(OFFLINE_ACCESS_TOKEN));
+        assert_eq!(value(&args, "--userType").as_deref(), Some("legacy"));
+
+        // Явный флаг важнее токена: он не уходит в аргументы
+        let mut flagged = test_params();
+        flagged.offline = true;
+        let args = client_args(&flagged);
+        assert_eq!(value(&args, "--accessToken").as_deref(), Some(OFFLINE_ACCESS_TOKEN));
+        assert!(!args.contains(&"secret-token".to_string()));
+
+        let args = client_args(&test_params());
+        assert_eq!(value(&args, "--accessToken").as_deref(), Some("secret-token"));
+        assert_eq!(value(&args, "--userType").as_deref(), Some("mojang"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn attached_kill_skips_reused_pid() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("30");
+        process_tuning::isolate_process_group(&mut cmd);
+        let mut child = cmd.spawn().unwrap();
+        let started = system_info::process_start_time(child.id()).unwrap();
+
+        // Время старта не совпадает: PID уже у другого процесса, его не трогаем
+        let stale = GameChild::Attached { pid: child.id(), start_time: started - 1000 };
+        assert!(stale.kill_target().kill_tree().is_ok());
+        assert!(child.try_wait().unwrap().is_none());
+
+        let attached = GameChild::Attached { pid: child.id(), start_time: started };
+        attached.kill_target().kill_tree().unwrap();
+        assert!(!child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn heap_larger_than_available_memory_is_a_warning() {
+        let memory = system_info::MemoryInfo { total_mb: 16384, available_mb: 6000, suggested_max_ram_mb: 5632 };
+        assert_eq!(memory_warning(4096, &memory), None);
+        assert_eq!(memory_warning(6000, &memory), None);
+
+        let warning = memory_warning(8192, &memory).unwrap();
+        assert!(warning.contains("only 6000 MB of 16384 MB"), "{}", warning);
+        assert!(warning.contains("5632 MB"), "{}", warning);
+
+        // Память не определилась - не пугаем пользователя
+        let unknown = system_info::MemoryInfo { total_mb: 0, available_mb: 0, suggested_max_ram_mb: 2048 };
+        assert_eq!(memory_warning(8192, &unknown), None);
+    }
+
+    #[test]
+    fn wrapper_command_goes_before_java() {
+        let args = |cmd: &Command| -> Vec<String> { cmd.get_args().map(|arg| arg.to_string_lossy().to_string()).collect() };
+
+        let direct = java_command("/opt/java/bin/java", None);
+        assert_eq!(direct.get_program(), "/opt/java/bin/java");
+        assert!(args(&direct).is_empty());
+
+        let wrapper = ["prime-run".to_string(), "--verbose".to_string()];
+        let wrapped = java_command("/opt/java/bin/java", Some(&wrapper));
+        assert_eq!(wrapped.get_program(), "prime-run");
+        assert_eq!(args(&wrapped), ["--verbose", "/opt/java/bin/java"]);
+
+        // Пустая обертка из настроек не ломает запуск
+        for empty in [&[][..], &["".to_string()][..], &["  ".to_string()][..]] {
+            assert_eq!(java_command("java", Some(empty)).get_program(), "java");
+        }
+    }
+
+    #[test]
+    fn user_env_vars_are_added_and_invalid_names_rejected() {
+        let mut cmd = Command::new("java");
+        let env_vars = HashMap::from([
+            ("DRI_PRIME".to_string(), "1".to_string()),
+            ("MESA_GL_VERSION_OVERRIDE".to_string(), "4.5".to_string()),
+        ]);
+        apply_env_vars(&mut cmd, &env_vars).unwrap();
+        let envs: HashMap<String, String> = cmd.get_envs()
+            .filter_map(|(key, value)| Some((key.to_string_lossy().to_string(), value?.to_string_lossy().to_string())))
+            .collect();
+        assert_eq!(envs, env_vars);
+
+        for (key, value) in [("", "1"), ("A=B", "1"), ("NUL\0", "1"), ("OK", "bad\0value")] {
+            let env_vars = HashMap::from([(key.to_string(), value.to_string())]);
+            let result = apply_env_vars(&mut Command::new("java"), &env_vars);
+            assert!(matches!(result, Err(LauncherError::InvalidParams { .. })), "{:?}", key);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn user_env_vars_reach_the_process_and_keep_inherited_ones() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "printf '%s|%s' \"$DRI_PRIME\" \"$PATH\""]);
+        apply_env_vars(&mut cmd, &HashMap::from([("DRI_PRIME".to_string(), "1".to_string())])).unwrap();
+
+        let output = String::from_utf8(cmd.output().unwrap().stdout).unwrap();
+        let (dri_prime, path) = output.split_once('|').unwrap();
+        assert_eq!(dri_prime, "1");
+        assert_eq!(path, std::env::var("PATH").unwrap_or_default());
+    }
+
+    #[test]
+    fn start_on_first_thread_only_on_macos() {
+        let no_args: Vec<String> = Vec::new();
+        assert!(needs_start_on_first_thread("macos", &no_args, false));
+        assert!(!needs_start_on_first_thread("linux", &no_args, false));
+        assert!(!needs_start_on_first_thread("windows", &no_args, false));
+
+        // Уже есть в аргументах пользователя или выключен - второй раз не добавляем
+        let user_args = vec!["-Xss1M".to_string(), START_ON_FIRST_THREAD.to_string()];
+        assert!(!needs_start_on_first_thread("macos", &user_args, false));
+        assert!(!needs_start_on_first_thread("macos", &no_args, true));
+
+        assert_eq!(needs_start_on_first_thread(std::env::consts::OS, &no_args, false), cfg!(target_os = "macos"));
+    }
+
+    #[test]
+    fn newest_crash_report_after_launch_is_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let game_dir = dir.path().to_str().unwrap();
+        let launched = SystemTime::now() - Duration::from_secs(600);
+        assert_eq!(find_crash_report(game_dir, launched), None);
+
+        let reports = dir.path().join("crash-reports");
+        fs::create_dir(&reports).unwrap();
+        touch(&reports.join("crash-2024-01-01_10.00.00-client.txt"), launched - Duration::from_secs(3600));
+        assert_eq!(find_crash_report(game_dir, launched), None);
+
+        touch(&reports.join("crash-2024-01-02_10.00.00-client.txt"), launched + Duration::from_secs(10));
+        touch(&reports.join("crash-2024-01-02_10.05.00-client.txt"), launched + Duration::from_secs(20));
+        touch(&reports.join("crash-2024-01-02_10.06.00-client.log"), launched + Duration::from_secs(30));
+        touch(&reports.join("notes.txt"), launched + Duration::from_secs(40));
+        assert_eq!(
+            find_crash_report(game_dir, launched),
+            Some(reports.join("crash-2024-01-02_10.05.00-client.txt"))
+        );
+    }
+
+    #[test]
+    fn crash_report_excerpt_is_the_first_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("crash.txt");
+        fs::write(&report, "---- Minecraft Crash Report ----\n// Oops.\n\nDescription: Rendering overlay\nmore\n").unwrap();
+
+        assert_eq!(
+            read_head_lines(&report, 4).as_deref(),
+            Some("---- Minecraft Crash Report ----\n// Oops.\n\nDescription: Rendering overlay")
+        );
+        assert_eq!(read_head_lines(&dir.path().join("missing.txt"), 4), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reaper_reports_exit_code_once() {
+        let registry = ProcessRegistry::new();
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "exit 3"]);
+        let crashed = register_game(&registry, cmd);
+        let running = spawn_game(&registry, "30");
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let reaped = loop {
+            let reaped = registry.cleanup_dead_processes();
+            if !reaped.is_empty() || Instant::now() > deadline {
+                break reaped;
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        assert_eq!(reaped.len(), 1);
+        let (process_id, _, exit) = &reaped[0];
+        assert_eq!(process_id, &crashed);
+        assert_eq!(exit.code, Some(3));
+        assert!(!exit.success);
+
+        // Повторный проход не сообщает о той же игре, работающая остается в реестре
+        assert!(registry.cleanup_dead_processes().is_empty());
+        assert!(registry.lock().unwrap().contains_key(&running));
+        registry.kill_all().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn kill_all_kills_child_processes_of_each_game() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("child.pid");
+        let registry = ProcessRegistry::new();
+
+        // Игра со своим дочерним процессом, как java, запущенная через обертку
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", &format!("sleep 30 & echo $! > '{}'; wait", pid_file.display())]);
+        let with_child = register_game(&registry, cmd);
+        let plain = spawn_game(&registry, "30");
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let child_pid = loop {
+            if let Some(pid) = fs::read_to_string(&pid_file).ok().and_then(|pid| pid.trim().parse::<u32>().ok()) {
+                break pid;
             }
+            assert!(Instant::now() < deadline, "game did not start its child");
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        let mut expected = vec![with_child, plain];
+        expected.sort();
+        assert_eq!(registry.kill_all().unwrap(), expected);
+        assert!(registry.lock().unwrap().is_empty());
+
+        while system_info::process_start_time(child_pid).is_some() {
+            assert!(Instant::now() < deadline, "child process {} survived kill_all", child_pid);
+            thread::sleep(Duration::from_millis(20));
         }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn games_survive_launcher_exit_unless_enabled() {
+        let registry = ProcessRegistry::new();
+        let game = spawn_game(&registry, "30");
+
+        // По умолчанию игры переживают выход из лаунчера
+        assert!(registry.kill_on_launcher_exit().is_empty());
+        assert!(registry.lock().unwrap().contains_key(&game));
+
+        registry.kill_on_exit.store(true, Ordering::Relaxed);
+        assert_eq!(registry.kill_on_launcher_exit(), [game]);
+        assert!(registry.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn quick_play_replaces_server_flags_from_1_20() {
+        assert_eq!(server_args("1.12.2", "mc.example.com", Some(25565)), ["--server", "mc.example.com", "--port", "25565"]);
+        assert_eq!(server_args("1.19.4", "mc.example.com", None), ["--server", "mc.example.com"]);
+        assert_eq!(server_args("1.20.4", "mc.example.com", Some(25565)), ["--quickPlayMultiplayer", "mc.example.com:25565"]);
+        assert_eq!(server_args("1.20", "mc.example.com", None), ["--quickPlayMultiplayer", "mc.example.com"]);
+        assert_eq!(server_args("1.20.1-forge-47.2.0", "::1", Some(25566)), ["--quickPlayMultiplayer", "[::1]:25566"]);
+
+        // Снапшоты сравниваются по году и неделе
+        assert!(!supports_quick_play("23w13a"));
+        assert!(supports_quick_play("23w14a"));
+        assert!(supports_quick_play("24w03b"));
+        assert!(!supports_quick_play("22w45a"));
+        assert!(!supports_quick_play("b1.7.3"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn killed_game_is_removed_and_reaped() {
+        let registry = ProcessRegistry::new();
+        assert!(matches!(registry.kill("game_process_404"), Err(LauncherError::ProcessNotFound { .. })));
 
-        for id in dead_processes {
-            processes.remove(&id);
-            println!("Cleaned up dead process: {}", id);
+        let game = spawn_game(&registry, "30");
+        let pid = registry.lock().unwrap()[&game].child.id();
+        registry.kill(&game).unwrap();
+
+        assert!(!registry.lock().unwrap().contains_key(&game));
+        // Процесс дождались через wait(), зомби в /proc не остается
+        assert!(!Path::new(&format!("/proc/{}", pid)).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn games_being_killed_are_not_reported_as_exited() {
+        let registry = ProcessRegistry::new();
+        let game = register_game(&registry, Command::new("true"));
+        registry.lock().unwrap().get_mut(&game).unwrap().killing = true;
+
+        // Завершение убиваемой игры сообщает kill_games, а не реапер
+        thread::sleep(Duration::from_millis(200));
+        assert!(registry.cleanup_dead_processes().is_empty());
+        assert!(registry.lock().unwrap().contains_key(&game));
+
+        registry.lock().unwrap().get_mut(&game).unwrap().killing = false;
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while registry.cleanup_dead_processes().is_empty() {
+            assert!(Instant::now() < deadline, "exited game was not reaped");
+            thread::sleep(Duration::from_millis(20));
         }
     }
-}
\ No newline at end of file
+}
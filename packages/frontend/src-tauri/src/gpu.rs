@@ -0,0 +1,228 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuInfo {
+    pub name: String,
+    /// "nvidia", "amd", "intel" or "unknown"
+    pub vendor: String,
+    pub discrete: bool,
+    /// The GPU the desktop (and by default the game) renders on, when known
+    pub primary: bool,
+}
+
+/// List the system GPUs, marking discrete and primary ones
+#[tauri::command]
+pub async fn detect_gpus() -> Vec<GpuInfo> {
+    tokio::task::spawn_blocking(list_gpus)
+        .await
+        .unwrap_or_default()
+}
+
+pub fn list_gpus() -> Vec<GpuInfo> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::list_gpus()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        windows::list_gpus()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        Vec::new()
+    }
+}
+
+/// Suggest how to run the game on the discrete GPU when an integrated one is primary
+///
+/// Only a hint for the user, nothing is applied automatically.
+pub fn offload_recommendation(gpus: &[GpuInfo]) -> Option<String> {
+    let primary = gpus.iter().find(|g| g.primary)?;
+    if primary.discrete {
+        return None;
+    }
+    let discrete = gpus.iter().find(|g| g.discrete)?;
+
+    // Основной GPU известен только на Linux, поэтому и подсказки - для Mesa/NVIDIA PRIME
+    let hint = if discrete.vendor == "nvidia" {
+        "set __NV_PRIME_RENDER_OFFLOAD=1 and __GLX_VENDOR_LIBRARY_NAME=nvidia (or use the prime-run wrapper)"
+    } else {
+        "set DRI_PRIME=1"
+    };
+    Some(format!(
+        "The game will render on the integrated GPU ({}). To use {}, {}.",
+        primary.name, discrete.name, hint
+    ))
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use super::GpuInfo;
+
+    /// Встроенная графика AMD обычно получает небольшой кусок общей памяти
+    const AMD_DISCRETE_MIN_VRAM: u64 = 2 * 1024 * 1024 * 1024;
+
+    pub fn list_gpus() -> Vec<GpuInfo> {
+        let entries = match fs::read_dir("/sys/class/drm") {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut gpus = Vec::new();
+        let mut seen_slots = Vec::new();
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // card0, card1, ... (без коннекторов вида card0-HDMI-A-1)
+            if !name.starts_with("card") || name.contains('-') {
+                continue;
+            }
+
+            let device = entry.path().join("device");
+            let slot = match fs::canonicalize(&device) {
+                Ok(path) => path.file_name().map(|s| s.to_string_lossy().to_string()).unwrap_or_default(),
+                Err(_) => continue,
+            };
+            if seen_slots.contains(&slot) {
+                continue;
+            }
+
+            let vendor_id = read_hex(&device.join("vendor")).unwrap_or(0) as u16;
+            let device_id = read_hex(&device.join("device")).unwrap_or(0) as u16;
+            let vendor = vendor_name(vendor_id);
+
+            let discrete = match vendor {
+                "nvidia" => true,
+                // Intel Arc - дискретные
+                "intel" => matches!(device_id >> 8, 0x56 | 0xe2),
+                "amd" => read_decimal(&device.join("mem_info_vram_total"))
+                    .is_some_and(|vram| vram >= AMD_DISCRETE_MIN_VRAM),
+                _ => false,
+            };
+
+            gpus.push(GpuInfo {
+                name: lspci_name(&slot)
+                    .unwrap_or_else(|| format!("{} GPU [{:04x}:{:04x}]", vendor, vendor_id, device_id)),
+                vendor: vendor.to_string(),
+                discrete,
+                primary: read_decimal(&device.join("boot_vga")) == Some(1),
+            });
+            seen_slots.push(slot);
+        }
+
+        gpus
+    }
+
+    fn vendor_name(vendor_id: u16) -> &'static str {
+        match vendor_id {
+            0x10de => "nvidia",
+            0x1002 | 0x1022 => "amd",
+            0x8086 => "intel",
+            _ => "unknown",
+        }
+    }
+
+    fn read_hex(path: &Path) -> Option<u64> {
+        let content = fs::read_to_string(path).ok()?;
+        u64::from_str_radix(content.trim().trim_start_matches("0x"), 16).ok()
+    }
+
+    fn read_decimal(path: &Path) -> Option<u64> {
+        fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    /// "Vendor Device" from `lspci -mm`, if lspci is installed
+    fn lspci_name(slot: &str) -> Option<String> {
+        let output = Command::new("lspci").args(["-mm", "-s", slot]).output().ok()?;
+        let line = String::from_utf8_lossy(&output.stdout).lines().next()?.to_string();
+
+        // 01:00.0 "VGA compatible controller" "NVIDIA Corporation" "GA106M [GeForce RTX 3060 Mobile]" ...
+        let fields: Vec<&str> = line.split('"').skip(1).step_by(2).collect();
+        match (fields.get(1), fields.get(2)) {
+            (Some(vendor), Some(device)) => Some(format!("{} {}", vendor, device)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::process::Command;
+    use super::GpuInfo;
+
+    pub fn list_gpus() -> Vec<GpuInfo> {
+        let mut cmd = Command::new("powershell");
+        cmd.args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Get-CimInstance Win32_VideoController | ForEach-Object { $_.Name }",
+        ]);
+
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        let output = match cmd.output() {
+            Ok(output) if output.status.success() => output,
+            _ => return Vec::new(),
+        };
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                let lower = name.to_lowercase();
+                let vendor = if lower.contains("nvidia") {
+                    "nvidia"
+                } else if lower.contains("amd") || lower.contains("radeon") {
+                    "amd"
+                } else if lower.contains("intel") {
+                    "intel"
+                } else {
+                    "unknown"
+                };
+                // Windows не сообщает, какой адаптер основной; различаем только по названию
+                let discrete = vendor == "nvidia"
+                    || lower.contains("radeon rx")
+                    || lower.contains("radeon pro")
+                    || lower.contains("arc ");
+                GpuInfo {
+                    name: name.to_string(),
+                    vendor: vendor.to_string(),
+                    discrete,
+                    primary: false,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gpu(name: &str, vendor: &str, discrete: bool, primary: bool) -> GpuInfo {
+        GpuInfo { name: name.to_string(), vendor: vendor.to_string(), discrete, primary }
+    }
+
+    #[test]
+    fn recommends_offload_only_from_integrated_primary() {
+        let intel = gpu("Intel UHD 630", "intel", false, true);
+        let nvidia = gpu("GeForce RTX 3060", "nvidia", true, false);
+        let hint = offload_recommendation(&[intel.clone(), nvidia]).unwrap();
+        assert!(hint.contains("__NV_PRIME_RENDER_OFFLOAD=1"));
+
+        let radeon = gpu("Radeon RX 6600", "amd", true, false);
+        assert!(offload_recommendation(&[intel.clone(), radeon]).unwrap().contains("DRI_PRIME=1"));
+
+        assert!(offload_recommendation(&[intel]).is_none());
+        assert!(offload_recommendation(&[gpu("GeForce RTX 3060", "nvidia", true, true)]).is_none());
+    }
+}
@@ -0,0 +1,239 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use crate::cancellation::{CancelToken, CancellationRegistry};
+use crate::error::LauncherError;
+
+/// Hash a file in fixed-size chunks without loading it into memory
+pub fn hash_file(path: &Path, algorithm: &str) -> Result<String, String> {
+    match algorithm {
+        "sha1" => hash_file_with::<Sha1>(path, None),
+        "sha256" => hash_file_with::<Sha256>(path, None),
+        _ => Err(format!("Unsupported hash algorithm: {}. Supported: sha1, sha256.", algorithm)),
+    }
+}
+
+/// Streaming hash; with `cancel`, stops between chunks once the operation is cancelled
+fn hash_file_with<D: Digest>(path: &Path, cancel: Option<&CancelToken>) -> Result<String, String> {
+    let mut file = File::open(path)
+        .map_err(|e| format!("Failed to open file for hashing: {}", e))?;
+
+    let mut hasher = D::new();
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        if cancel.is_some_and(CancelToken::is_cancelled) {
+            return Err("Hashing cancelled".to_string());
+        }
+        let n = file.read(&mut buffer)
+            .map_err(|e| format!("Failed to read file for hashing: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// The file exists with the expected size (when known) and SHA-1
+pub fn file_matches_sha1(path: &Path, expected_sha1: &str, size: Option<u64>) -> bool {
+    let size_matches = fs::metadata(path)
+        .is_ok_and(|metadata| metadata.is_file() && size.map_or(true, |size| metadata.len() == size));
+
+    size_matches && hash_file(path, "sha1").is_ok_and(|actual| actual.eq_ignore_ascii_case(expected_sha1.trim()))
+}
+
+/// Check a file against an expected hex digest (case-insensitive)
+#[tauri::command]
+pub async fn verify_file_hash(path: String, algorithm: String, expected: String) -> Result<bool, LauncherError> {
+    if !matches!(algorithm.as_str(), "sha1" | "sha256") {
+        return Err(LauncherError::invalid_params(format!(
+            "Unsupported hash algorithm: {}. Supported: sha1, sha256.",
+            algorithm
+        )));
+    }
+    if !Path::new(&path).is_file() {
+        return Err(LauncherError::not_found(format!("File not found: {}", path)));
+    }
+
+    let actual = tokio::task::spawn_blocking(move || hash_file(Path::new(&path), &algorithm))
+        .await
+        .map_err(|e| format!("Hashing task failed: {}", e))??;
+
+    Ok(actual.eq_ignore_ascii_case(expected.trim()))
+}
+
+/// One file of an installation manifest
+#[derive(Debug, Clone, Deserialize)]
+pub struct HashEntry {
+    pub path: String,
+    pub expected_sha1: String,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum VerifyStatus {
+    Ok,
+    Missing,
+    SizeMismatch,
+    HashMismatch,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyResult {
+    pub path: String,
+    pub status: VerifyStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Verify many files in one call: size first (cheap), then SHA-1
+///
+/// With `operation_id` the pass can be stopped with `cancel_operation`; it then fails
+/// with a `Cancelled` error instead of returning partial results.
+#[tauri::command]
+pub async fn verify_manifest(
+    entries: Vec<HashEntry>,
+    concurrency: usize,
+    operation_id: Option<String>,
+    cancellation: tauri::State<'_, CancellationRegistry>,
+) -> Result<Vec<VerifyResult>, LauncherError> {
+    let operation = cancellation.register(operation_id)?;
+    let token = operation.token();
+
+    stream::iter(entries)
+        .map(|entry| {
+            let token = token.clone();
+            async move {
+                token.check()?;
+                tokio::task::spawn_blocking(move || verify_entry(&entry, &token))
+                    .await
+                    .map_err(|e| format!("Verification task failed: {}", e))?
+            }
+        })
+        .buffered(concurrency.max(1))
+        .try_collect()
+        .await
+}
+
+fn verify_entry(entry: &HashEntry, cancel: &CancelToken) -> Result<VerifyResult, LauncherError> {
+    let path = Path::new(&entry.path);
+    let result = |status, error| VerifyResult {
+        path: entry.path.clone(),
+        status,
+        error,
+    };
+
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => metadata,
+        _ => return Ok(result(VerifyStatus::Missing, None)),
+    };
+
+    if let Some(expected_size) = entry.size {
+        if metadata.len() != expected_size {
+            return Ok(result(VerifyStatus::SizeMismatch, None));
+        }
+    }
+
+    let hashed = hash_file_with::<Sha1>(path, Some(cancel));
+    cancel.check()?;
+    Ok(match hashed {
+        Ok(actual) if actual.eq_ignore_ascii_case(entry.expected_sha1.trim()) => {
+            result(VerifyStatus::Ok, None)
+        }
+        Ok(_) => result(VerifyStatus::HashMismatch, None),
+        Err(e) => result(VerifyStatus::HashMismatch, Some(e)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SHA-1 of "hello"
+    const HELLO_SHA1: &str = "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d";
+
+    fn entry(path: &Path, expected_sha1: &str, size: Option<u64>) -> HashEntry {
+        HashEntry {
+            path: path.to_string_lossy().to_string(),
+            expected_sha1: expected_sha1.to_string(),
+            size,
+        }
+    }
+
+    fn status(entry: &HashEntry) -> VerifyStatus {
+        verify_entry(entry, &CancelToken::default()).unwrap().status
+    }
+
+    #[test]
+    fn hashes_sha1_and_sha256() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        fs::write(&path, "hello").unwrap();
+
+        assert_eq!(hash_file(&path, "sha1").unwrap(), HELLO_SHA1);
+        assert_eq!(
+            hash_file(&path, "sha256").unwrap(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert!(hash_file(&path, "md5").is_err());
+    }
+
+    #[test]
+    fn verify_entry_statuses() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        fs::write(&path, "hello").unwrap();
+
+        assert!(matches!(status(&entry(&path, HELLO_SHA1, Some(5))), VerifyStatus::Ok));
+        assert!(matches!(status(&entry(&path, &HELLO_SHA1.to_uppercase(), None)), VerifyStatus::Ok));
+        assert!(matches!(status(&entry(&path, HELLO_SHA1, Some(6))), VerifyStatus::SizeMismatch));
+        assert!(matches!(status(&entry(&path, &"0".repeat(40), Some(5))), VerifyStatus::HashMismatch));
+        assert!(matches!(
+            status(&entry(&dir.path().join("missing"), HELLO_SHA1, None)),
+            VerifyStatus::Missing
+        ));
+    }
+
+    #[test]
+    fn cancelled_verification_is_an_error_not_a_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        fs::write(&path, "hello").unwrap();
+
+        let token = CancelToken::default();
+        token.cancel();
+        assert!(matches!(
+            verify_entry(&entry(&path, HELLO_SHA1, Some(5)), &token),
+            Err(LauncherError::Cancelled { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn verify_file_hash_known_vectors() {
+        let dir = tempfile::tempdir().unwrap();
+        let abc = dir.path().join("abc.txt");
+        fs::write(&abc, "abc").unwrap();
+        // Больше буфера чтения: хеш считается по частям
+        let million = dir.path().join("million.txt");
+        fs::write(&million, "a".repeat(1_000_000)).unwrap();
+
+        let verify = |path: &Path, algorithm: &str, expected: &str| {
+            verify_file_hash(path.to_string_lossy().to_string(), algorithm.to_string(), expected.to_string())
+        };
+
+        assert!(verify(&abc, "sha1", "a9993e364706816aba3e25717850c26c9cd0d89d").await.unwrap());
+        assert!(verify(&abc, "sha256", "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD").await.unwrap());
+        assert!(verify(&million, "sha1", "34aa973cd4c4daa4f61eeb2bdbad27316534016f").await.unwrap());
+        assert!(verify(&million, "sha256", "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0").await.unwrap());
+        assert!(!verify(&abc, "sha1", HELLO_SHA1).await.unwrap());
+
+        assert!(matches!(verify(&abc, "md5", "").await, Err(LauncherError::InvalidParams { .. })));
+        assert!(matches!(verify(&dir.path().join("missing"), "sha1", "").await, Err(LauncherError::NotFound { .. })));
+    }
+}
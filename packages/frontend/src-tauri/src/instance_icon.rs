@@ -0,0 +1,198 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use crate::{archive, paths};
+use crate::error::LauncherError;
+
+/// Returned when an instance has no icon of its own
+pub const DEFAULT_ICON: &str = "default";
+
+const ICON_SIZE: u32 = 128;
+const ICON_NAMES: [&str; 2] = ["icon.png", "pack.png"];
+
+/// Find an instance icon, cache a resized PNG in the app data dir and return its path
+///
+/// Looks for `icon.png`/`pack.png` in the instance, then the `icon` field of a
+/// modpack `manifest.json`, then icons embedded in a pack archive (`.zip`/`.mrpack`).
+/// Returns [`DEFAULT_ICON`] when nothing is found.
+#[tauri::command]
+pub async fn resolve_instance_icon(instance_dir: String) -> Result<String, LauncherError> {
+    let instance = PathBuf::from(&instance_dir);
+    if !instance.is_dir() {
+        return Err(LauncherError::not_found(format!("Instance directory does not exist: {}", instance_dir)));
+    }
+
+    let cache_dir = paths::app_data_dir()
+        .map(|dir| dir.join("icons"))
+        .ok_or_else(|| "Failed to determine app data directory".to_string())?;
+
+    let icon = tokio::task::spawn_blocking(move || resolve_icon(&instance, &cache_dir))
+        .await
+        .map_err(|e| format!("Icon task failed: {}", e))??;
+    Ok(icon.unwrap_or_else(|| DEFAULT_ICON.to_string()))
+}
+
+/// Cached icon path for the instance, `None` when it has no icon
+///
+/// The cache file name includes a hash of the source image, so an up-to-date icon is
+/// returned without decoding or rewriting it; older versions of it are removed.
+fn resolve_icon(instance: &Path, cache_dir: &Path) -> Result<Option<String>, String> {
+    let png = match find_icon_bytes(instance)? {
+        Some(png) => png,
+        None => return Ok(None),
+    };
+
+    let instance_key = &hex::encode(Sha256::digest(instance.to_string_lossy().as_bytes()))[..16];
+    let content_key = &hex::encode(Sha256::digest(&png))[..16];
+    let cache_path = cache_dir.join(format!("{}-{}.png", instance_key, content_key));
+    if cache_path.is_file() {
+        return Ok(Some(cache_path.to_string_lossy().to_string()));
+    }
+
+    let path = write_resized_icon(&png, &cache_path)?;
+
+    // Иконки этого экземпляра, закэшированные до смены картинки
+    if let Ok(entries) = fs::read_dir(cache_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with(&format!("{}-", instance_key)) && entry.path() != cache_path {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    Ok(Some(path))
+}
+
+fn find_icon_bytes(instance: &Path) -> Result<Option<Vec<u8>>, String> {
+    for name in ICON_NAMES {
+        let path = instance.join(name);
+        if path.is_file() {
+            return fs::read(&path)
+                .map(Some)
+                .map_err(|e| format!("Failed to read {:?}: {}", path, e));
+        }
+    }
+
+    // manifest.json модпака может указывать путь к иконке
+    if let Ok(content) = fs::read_to_string(instance.join("manifest.json")) {
+        if let Ok(json) = serde_json::from_str::<Value>(&content) {
+            if let Some(icon) = json.get("icon").and_then(Value::as_str) {
+                let is_relative = Path::new(icon).components()
+                    .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+                let path = instance.join(icon);
+                if is_relative && path.is_file() {
+                    if let Ok(bytes) = fs::read(&path) {
+                        return Ok(Some(bytes));
+                    }
+                }
+            }
+        }
+    }
+
+    // Иконка внутри архива модпака
+    let entries = fs::read_dir(instance)
+        .map_err(|e| format!("Failed to read instance directory: {}", e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_pack = matches!(
+            path.extension().and_then(|s| s.to_str()),
+            Some("zip") | Some("mrpack")
+        );
+        if !is_pack {
+            continue;
+        }
+
+        for name in ICON_NAMES.iter().chain(["overrides/icon.png"].iter()) {
+            match archive::read_zip_entry(&path, name) {
+                Ok(Some(bytes)) => return Ok(Some(bytes)),
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("[Instance Icon] ⚠️ Failed to read {}: {}", path.display(), e);
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn write_resized_icon(png: &[u8], cache_path: &Path) -> Result<String, String> {
+    let image = image::load_from_memory(png)
+        .map_err(|e| format!("Failed to decode icon: {}", e))?;
+
+    let resized = if image.width() > ICON_SIZE || image.height() > ICON_SIZE {
+        image.resize(ICON_SIZE, ICON_SIZE, image::imageops::FilterType::Lanczos3)
+    } else {
+        image
+    };
+
+    if let Some(parent) = cache_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create icon cache directory: {}", e))?;
+    }
+
+    resized.save_with_format(cache_path, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to write cached icon: {}", e))?;
+
+    Ok(cache_path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_png(path: &Path, size: u32, color: [u8; 4]) {
+        image::RgbaImage::from_pixel(size, size, image::Rgba(color)).save(path).unwrap();
+    }
+
+    #[test]
+    fn instance_without_icon_has_none() {
+        let instance = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        assert_eq!(resolve_icon(instance.path(), cache.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn icon_is_resized_and_cached() {
+        let instance = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        write_png(&instance.path().join("icon.png"), 512, [255, 0, 0, 255]);
+
+        let path = resolve_icon(instance.path(), cache.path()).unwrap().unwrap();
+        let cached = image::open(&path).unwrap();
+        assert_eq!((cached.width(), cached.height()), (ICON_SIZE, ICON_SIZE));
+    }
+
+    #[test]
+    fn up_to_date_cache_is_not_rewritten() {
+        let instance = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        write_png(&instance.path().join("pack.png"), 16, [0, 255, 0, 255]);
+
+        let first = resolve_icon(instance.path(), cache.path()).unwrap().unwrap();
+        // Если бы кэш перезаписывался, этот маркер пропал бы
+        fs::write(&first, b"marker").unwrap();
+        let second = resolve_icon(instance.path(), cache.path()).unwrap().unwrap();
+        assert_eq!(first, second);
+        assert_eq!(fs::read(&second).unwrap(), b"marker");
+    }
+
+    #[test]
+    fn changed_icon_replaces_old_cache_entry() {
+        let instance = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let icon = instance.path().join("icon.png");
+        write_png(&icon, 16, [0, 0, 255, 255]);
+        let old = resolve_icon(instance.path(), cache.path()).unwrap().unwrap();
+
+        write_png(&icon, 16, [255, 255, 0, 255]);
+        let new = resolve_icon(instance.path(), cache.path()).unwrap().unwrap();
+
+        assert_ne!(old, new);
+        assert!(!Path::new(&old).exists());
+        assert!(Path::new(&new).is_file());
+    }
+}
@@ -0,0 +1,281 @@
+use std::path::Path;
+use std::time::Duration;
+use reqwest::header;
+use tokio::process::Command;
+use crate::{archive, hashing};
+use crate::error::LauncherError;
+
+/// Adoptium (Temurin) release assets are published on GitHub next to their
+/// `<file>.sha256.txt` checksum and `<file>.sig` GPG signature
+const ADOPTIUM_RELEASES_PREFIX: &str = "https://github.com/adoptium/";
+
+/// Adoptium API; its `/v3/binary/...` links redirect to the GitHub release assets
+const ADOPTIUM_API_PREFIX: &str = "https://api.adoptium.net/";
+
+/// Fingerprint of the Adoptium release signing key (see "Verifying Temurin binaries" in the
+/// Adoptium docs); a signature by any other key in the user's keyring is not accepted
+const ADOPTIUM_KEY_FINGERPRINT: &str = "3B04D753C9050D9A5D343F39843C48A565F8F04B";
+
+/// Verify a downloaded Java archive against the vendor's published checksum, then extract it
+///
+/// `download_url` is an Adoptium GitHub release asset or an Adoptium API binary link, which
+/// is resolved to the release asset it redirects to. Verification is mandatory: archives from
+/// unsupported vendors, a missing checksum or signature, or a mismatch refuse the install.
+/// A signature that can't be checked on this system (no `gpg`, vendor key not imported) is a
+/// `VerificationUnavailable` error. Returns `dest_dir`.
+#[tauri::command]
+pub async fn install_java_archive(
+    archive_path: String,
+    download_url: String,
+    dest_dir: String,
+) -> Result<String, LauncherError> {
+    let download_url = match download_source(&download_url) {
+        Some(DownloadSource::Release) => download_url,
+        Some(DownloadSource::Api) => resolve_api_redirect(&download_url).await?,
+        None => {
+            return Err(LauncherError::invalid_params(format!(
+                "Refusing to install Java from {}: only Adoptium releases can be verified",
+                download_url
+            )));
+        }
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let expected = fetch_vendor_sha256(&client, &download_url).await?;
+
+    let archive = archive_path.clone();
+    let actual = tokio::task::spawn_blocking(move || hashing::hash_file(Path::new(&archive), "sha256"))
+        .await
+        .map_err(|e| format!("Hashing task failed: {}", e))??;
+
+    eprintln!("[Java Install] Expected SHA-256: {}", expected);
+    eprintln!("[Java Install] Actual SHA-256:   {}", actual);
+    if !actual.eq_ignore_ascii_case(&expected) {
+        return Err(LauncherError::from(format!(
+            "Java archive checksum mismatch: expected {}, got {}. The file was not extracted.",
+            expected, actual
+        )));
+    }
+
+    verify_vendor_signature(&client, &download_url, &archive_path).await?;
+
+    let archive = archive_path.clone();
+    let dest = dest_dir.clone();
+    tokio::task::spawn_blocking(move || {
+        let (archive, dest) = (Path::new(&archive), Path::new(&dest));
+        if archive_path_is_zip(archive) {
+            archive::extract_zip_matching(archive, dest, |_| true).map(|_| ())
+        } else {
+            archive::extract_tar_gz(archive, dest)
+        }
+    })
+    .await
+    .map_err(|e| format!("Extraction task failed: {}", e))??;
+
+    eprintln!("[Java Install] ✅ Verified and extracted {} into {}", archive_path, dest_dir);
+    Ok(dest_dir)
+}
+
+/// Where a Java download link points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DownloadSource {
+    /// Adoptium release asset on GitHub
+    Release,
+    /// Adoptium API binary link
+    Api,
+}
+
+fn download_source(url: &str) -> Option<DownloadSource> {
+    if url.starts_with(ADOPTIUM_RELEASES_PREFIX) {
+        Some(DownloadSource::Release)
+    } else if url.starts_with(ADOPTIUM_API_PREFIX) {
+        Some(DownloadSource::Api)
+    } else {
+        None
+    }
+}
+
+/// Release asset an Adoptium API binary link redirects to
+///
+/// Checksum and signature are published next to the release asset, so they are looked up
+/// there. A redirect anywhere else is refused.
+async fn resolve_api_redirect(api_url: &str) -> Result<String, LauncherError> {
+    // Редирект не проходим: нужен сам адрес ассета, рядом с ним лежат .sha256.txt и .sig
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client.get(api_url)
+        .send()
+        .await
+        .map_err(|e| LauncherError::network(format!("Failed to resolve Adoptium download link: {}", e)))?;
+
+    let location = response.headers()
+        .get(header::LOCATION)
+        .and_then(|location| location.to_str().ok())
+        .filter(|_| response.status().is_redirection());
+    match location {
+        Some(location) if download_source(location) == Some(DownloadSource::Release) => {
+            eprintln!("[Java Install] {} resolves to {}", api_url, location);
+            Ok(location.to_string())
+        }
+        Some(location) => Err(LauncherError::invalid_params(format!(
+            "Refusing to install Java from {}: it redirects outside Adoptium releases ({})",
+            api_url, location
+        ))),
+        None => Err(LauncherError::network(format!(
+            "Adoptium API did not return a download for {}: HTTP {}",
+            api_url,
+            response.status()
+        ))),
+    }
+}
+
+fn archive_path_is_zip(path: &Path) -> bool {
+    path.extension().and_then(|s| s.to_str()).is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// `<hash>  <file name>` from the vendor's `.sha256.txt`
+async fn fetch_vendor_sha256(client: &reqwest::Client, download_url: &str) -> Result<String, String> {
+    let checksum_url = format!("{}.sha256.txt", download_url);
+    let response = client.get(&checksum_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch vendor checksum: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Vendor checksum is unavailable: HTTP {}", response.status()));
+    }
+
+    let text = response.text()
+        .await
+        .map_err(|e| format!("Failed to read vendor checksum: {}", e))?;
+
+    text.split_whitespace()
+        .next()
+        .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_lowercase)
+        .ok_or_else(|| "Vendor checksum file is malformed".to_string())
+}
+
+/// Check the detached `.sig` with the system `gpg` against the pinned Adoptium key
+///
+/// Fails closed: no signature, no gpg, the key missing from the keyring or a signature by
+/// another key all refuse the install. Missing gpg or key is `VerificationUnavailable`.
+async fn verify_vendor_signature(
+    client: &reqwest::Client,
+    download_url: &str,
+    archive_path: &str,
+) -> Result<(), LauncherError> {
+    let signature_url = format!("{}.sig", download_url);
+    let response = client.get(&signature_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch vendor signature: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Vendor signature is unavailable: HTTP {}", response.status()).into());
+    }
+    let signature = response.bytes()
+        .await
+        .map_err(|e| format!("Failed to read vendor signature: {}", e))?;
+
+    let signature_path = format!("{}.sig", archive_path);
+    tokio::fs::write(&signature_path, &signature)
+        .await
+        .map_err(|e| format!("Failed to save vendor signature: {}", e))?;
+
+    let mut cmd = Command::new("gpg");
+    cmd.args(["--batch", "--status-fd", "1", "--verify", &signature_path, archive_path])
+        .kill_on_drop(true);
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = cmd.output().await;
+    let _ = tokio::fs::remove_file(&signature_path).await;
+
+    let output = output.map_err(|e| LauncherError::verification_unavailable(format!(
+        "Cannot verify the Java archive signature, gpg is not available ({}). Install GnuPG and import the Adoptium key {}.",
+        e, ADOPTIUM_KEY_FINGERPRINT
+    )))?;
+
+    check_signature_status(&String::from_utf8_lossy(&output.stdout), ADOPTIUM_KEY_FINGERPRINT)?;
+    eprintln!("[Java Install] ✅ Vendor signature is valid");
+    Ok(())
+}
+
+/// Accept only a `VALIDSIG` whose signing or primary key is `fingerprint`
+fn check_signature_status(status: &str, fingerprint: &str) -> Result<(), LauncherError> {
+    let signed_by_vendor = status.lines()
+        .filter_map(|line| line.strip_prefix("[GNUPG:] VALIDSIG "))
+        .any(|fields| {
+            // <fingerprint> <date> <timestamp> <expires> <version> <reserved> <pubkey algo>
+            // <hash algo> <class> <primary key fingerprint>
+            let fields: Vec<&str> = fields.split_whitespace().collect();
+            let is_vendor_key = |key: Option<&&str>| key.is_some_and(|key| key.eq_ignore_ascii_case(fingerprint));
+            is_vendor_key(fields.first()) || is_vendor_key(fields.get(9))
+        });
+    if signed_by_vendor {
+        return Ok(());
+    }
+
+    if status.contains("[GNUPG:] NO_PUBKEY") {
+        return Err(LauncherError::verification_unavailable(format!(
+            "Cannot verify the Java archive signature: the Adoptium key {} is not in the gpg keyring. The file was not extracted.",
+            fingerprint
+        )));
+    }
+    Err("Java archive signature verification failed. The file was not extracted.".to_string().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FINGERPRINT: &str = "3B04D753C9050D9A5D343F39843C48A565F8F04B";
+
+    #[test]
+    fn accepts_signature_by_pinned_key() {
+        let status = "[GNUPG:] GOODSIG 843C48A565F8F04B Adoptium GPG Key\n\
+            [GNUPG:] VALIDSIG 1111111111111111111111111111111111111111 2024-01-16 1705363200 0 4 0 1 10 00 3b04d753c9050d9a5d343f39843c48a565f8f04b\n";
+        assert!(check_signature_status(status, FINGERPRINT).is_ok());
+    }
+
+    #[test]
+    fn rejects_signature_by_other_key() {
+        let status = "[GNUPG:] GOODSIG 0000000000000000 Someone Else\n\
+            [GNUPG:] VALIDSIG 2222222222222222222222222222222222222222 2024-01-16 1705363200 0 4 0 1 10 00 2222222222222222222222222222222222222222\n";
+        assert!(check_signature_status(status, FINGERPRINT).is_err());
+    }
+
+    #[test]
+    fn missing_key_or_bad_signature_fails_closed() {
+        let missing = check_signature_status("[GNUPG:] ERRSIG 843C48A565F8F04B 1 10 00 1705363200 9\n[GNUPG:] NO_PUBKEY 843C48A565F8F04B\n", FINGERPRINT);
+        assert!(matches!(missing, Err(LauncherError::VerificationUnavailable { .. })));
+        assert!(check_signature_status("[GNUPG:] BADSIG 843C48A565F8F04B Adoptium GPG Key\n", FINGERPRINT).is_err());
+        assert!(check_signature_status("", FINGERPRINT).is_err());
+    }
+
+
+    #[test]
+    fn only_adoptium_download_links_are_accepted() {
+        assert_eq!(
+            download_source("https://github.com/adoptium/temurin17-binaries/releases/download/jdk-17.0.9%2B9/OpenJDK17U-jre_x64_linux_hotspot_17.0.9_9.tar.gz"),
+            Some(DownloadSource::Release)
+        );
+        assert_eq!(
+            download_source("https://api.adoptium.net/v3/binary/latest/17/ga/linux/x64/jre/hotspot/normal/eclipse"),
+            Some(DownloadSource::Api)
+        );
+        assert_eq!(download_source("https://cdn.azul.com/zulu/bin/zulu17-linux_x64.tar.gz"), None);
+        assert_eq!(download_source("https://github.com/adoptium.evil.com/jre.tar.gz"), None);
+        assert_eq!(download_source("https://api.adoptium.net.evil.com/v3/binary/latest"), None);
+    }
+}
@@ -1,47 +1,97 @@
 use std::env;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use crate::error::LauncherError;
+use crate::version_json;
 
 /// Represents a Java installation
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct JavaInstallation {
     pub path: PathBuf,
     pub version: String,
+    /// 8 for "1.8.0_402", 17 for "17.0.1"; None when the version is unknown
+    pub major_version: Option<u32>,
     pub is_64_bit: bool,
 }
 
+/// Scan for Java installations, newest major version first
+///
+/// Runs on a blocking thread: every candidate is probed with `java -version`.
+#[tauri::command]
+pub async fn detect_java() -> Result<Vec<JavaInstallation>, LauncherError> {
+    let mut installations = tokio::task::spawn_blocking(find_java_installations)
+        .await
+        .map_err(|e| format!("Java scan task failed: {}", e))?;
+
+    sort_newest_first(&mut installations);
+    Ok(installations)
+}
+
+/// Highest major version first, unknown versions last
+fn sort_newest_first(installations: &mut [JavaInstallation]) {
+    installations.sort_by_key(|java| std::cmp::Reverse(java.major_version));
+}
+
 /// Find Java installations on the system
+///
+/// Looks at the well-known install directories, the Java on PATH and `JAVA_HOME`.
 pub fn find_java_installations() -> Vec<JavaInstallation> {
+    // SDKMAN, asdf, Nix и прочие менеджеры версий не кладут JDK в стандартные места
+    let mut candidates = get_java_search_paths();
+    candidates.extend(path_java_home());
+    candidates.extend(env::var_os("JAVA_HOME").map(PathBuf::from));
+    collect_installations(candidates)
+}
+
+/// Probe every existing candidate home once
+///
+/// Candidates are deduplicated by their resolved path, so a symlink such as
+/// `/usr/lib/jvm/default-java` or the PATH Java does not show up next to its target;
+/// the first spelling of a path is the one shown.
+fn collect_installations(candidates: impl IntoIterator<Item = PathBuf>) -> Vec<JavaInstallation> {
     let mut installations = Vec::new();
+    let mut seen = Vec::new();
 
-    // Common Java installation paths by platform
-    let search_paths = get_java_search_paths();
+    for path in candidates {
+        if !path.exists() || !mark_seen(&mut seen, &path) {
+            continue;
+        }
 
-    for path in search_paths {
-        if path.exists() {
-            // Try to determine version
-            let version = detect_java_version(&path);
+        // Try to determine version and architecture
+        let (version, is_64_bit) = detect_java_version(&path);
 
-            installations.push(JavaInstallation {
-                path: path.clone(),
-                version,
-                is_64_bit: true, // Assume 64-bit for modern systems
-            });
-        }
+        installations.push(JavaInstallation {
+            path,
+            major_version: parse_major_version(&version),
+            version,
+            is_64_bit,
+        });
     }
 
-    // Also check JAVA_HOME environment variable
-    if let Ok(java_home) = env::var("JAVA_HOME") {
-        let java_path = PathBuf::from(java_home);
-        if java_path.exists() && !installations.iter().any(|j| j.path == java_path) {
-            installations.push(JavaInstallation {
-                path: java_path,
-                version: "JAVA_HOME".to_string(),
-                is_64_bit: true,
-            });
-        }
+    installations
+}
+
+/// Remember the resolved `path`; false if it was already seen
+fn mark_seen(seen: &mut Vec<PathBuf>, path: &Path) -> bool {
+    let resolved = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if seen.contains(&resolved) {
+        return false;
     }
+    seen.push(resolved);
+    true
+}
 
-    installations
+/// Java home of the `java` found on PATH, with symlinks (/usr/bin/java, shims) resolved
+fn path_java_home() -> Option<PathBuf> {
+    let java_bin = std::fs::canonicalize(resolve_java_command("java")?).ok()?;
+    let bin_dir = java_bin.parent()?;
+    if bin_dir.file_name()? != "bin" {
+        return None;
+    }
+    bin_dir.parent().map(Path::to_path_buf)
 }
 
 fn get_java_search_paths() -> Vec<PathBuf> {
@@ -61,11 +111,12 @@ fn get_java_search_paths() -> Vec<PathBuf> {
             ]);
         }
         "macos" => {
-            // macOS Java paths
-            paths.extend(vec![
-                PathBuf::from("/Library/Java/JavaVirtualMachines"),
-                PathBuf::from("/System/Library/Java"),
-            ]);
+            // macOS Java paths: each *.jdk bundle (Oracle, Temurin, Homebrew casks) is a separate install
+            paths.extend(jdk_bundle_homes(Path::new("/Library/Java/JavaVirtualMachines")));
+            if let Some(home) = env::var_os("HOME") {
+                paths.extend(jdk_bundle_homes(&PathBuf::from(home).join("Library/Java/JavaVirtualMachines")));
+            }
+            paths.push(PathBuf::from("/System/Library/Java"));
         }
         "windows" => {
             // Windows Java paths (using environment variables)
@@ -77,6 +128,10 @@ fn get_java_search_paths() -> Vec<PathBuf> {
             }
             paths.push(PathBuf::from("C:\\Program Files\\Java"));
             paths.push(PathBuf::from("C:\\Program Files (x86)\\Java"));
+
+            // Установщики Oracle, Adoptium, Azul и Microsoft регистрируют JavaHome в реестре
+            #[cfg(target_os = "windows")]
+            paths.extend(registry::java_homes());
         }
         _ => {}
     }
@@ -84,32 +139,295 @@ fn get_java_search_paths() -> Vec<PathBuf> {
     paths
 }
 
-fn detect_java_version(java_path: &Path) -> String {
-    // Try to run java -version
-    if let Some(java_bin) = find_java_executable(java_path) {
-        use std::process::Command;
+#[cfg(target_os = "windows")]
+mod registry {
+    use std::path::PathBuf;
+    use winreg::enums::{HKEY_LOCAL_MACHINE, KEY_READ, KEY_WOW64_32KEY, KEY_WOW64_64KEY};
+    use winreg::RegKey;
 
-        if let Ok(output) = Command::new(&java_bin).arg("-version").output() {
-            if let Ok(version_str) = String::from_utf8(output.stderr) {
-                // Parse version from output
-                // Format: "openjdk version \"17.0.1\" ..."
-                if let Some(start) = version_str.find('"') {
-                    if let Some(end) = version_str[start + 1..].find('"') {
-                        return version_str[start + 1..start + 1 + end].to_string();
-                    }
-                }
+    /// (vendor key, subkey under each version, value holding the Java home)
+    const JAVA_KEYS: &[(&str, &str, &str)] = &[
+        (r"SOFTWARE\JavaSoft\Java Runtime Environment", "", "JavaHome"),
+        (r"SOFTWARE\JavaSoft\Java Development Kit", "", "JavaHome"),
+        (r"SOFTWARE\JavaSoft\JRE", "", "JavaHome"),
+        (r"SOFTWARE\JavaSoft\JDK", "", "JavaHome"),
+        (r"SOFTWARE\Eclipse Adoptium\JDK", r"hotspot\MSI", "Path"),
+        (r"SOFTWARE\Eclipse Adoptium\JRE", r"hotspot\MSI", "Path"),
+        (r"SOFTWARE\Eclipse Foundation\JDK", r"hotspot\MSI", "Path"),
+        (r"SOFTWARE\Microsoft\JDK", r"hotspot\MSI", "Path"),
+        (r"SOFTWARE\Azul Systems\Zulu", "", "InstallationPath"),
+    ];
+
+    /// Java homes registered under HKLM, from both the 64-bit and the 32-bit registry views
+    pub fn java_homes() -> Vec<PathBuf> {
+        let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+        [KEY_WOW64_64KEY, KEY_WOW64_32KEY]
+            .into_iter()
+            .flat_map(|view| java_homes_in(&hklm, view))
+            .collect()
+    }
+
+    /// Java homes from the vendor keys under `root` in one registry view
+    pub fn java_homes_in(root: &RegKey, view: u32) -> Vec<PathBuf> {
+        let mut homes = Vec::new();
+
+        for &(vendor_key, version_subkey, value_name) in JAVA_KEYS {
+            let vendor = match root.open_subkey_with_flags(vendor_key, KEY_READ | view) {
+                Ok(vendor) => vendor,
+                Err(_) => continue,
+            };
 
-                // Fallback: extract version number
-                for line in version_str.lines() {
-                    if line.contains("version") {
-                        return line.trim().to_string();
-                    }
+            // Подключи - версии ("17", "1.8.0_402", "17.0.10.7"), внутри них путь к Java
+            for version in vendor.enum_keys().flatten() {
+                let key_path = if version_subkey.is_empty() {
+                    version
+                } else {
+                    format!(r"{}\{}", version, version_subkey)
+                };
+                let home: Option<String> = vendor.open_subkey_with_flags(&key_path, KEY_READ | view)
+                    .and_then(|key| key.get_value(value_name))
+                    .ok();
+                if let Some(home) = home.filter(|home| !home.trim().is_empty()) {
+                    homes.push(PathBuf::from(home.trim()));
                 }
             }
         }
+
+        homes
+    }
+}
+
+/// Java homes inside macOS `*.jdk` bundles: `<bundle>/Contents/Home`
+fn jdk_bundle_homes(dir: &Path) -> Vec<PathBuf> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut homes: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jdk"))
+        .map(|bundle| bundle.join("Contents").join("Home"))
+        .filter(|home| home.is_dir())
+        .collect();
+    homes.sort();
+    homes
+}
+
+/// Version string and bitness of the Java at `java_path`
+///
+/// Bitness comes from the `-version` banner, then from the executable's header;
+/// if both are inconclusive a 64-bit JVM is assumed.
+fn detect_java_version(java_path: &Path) -> (String, bool) {
+    match find_java_executable(java_path) {
+        Some(java_bin) => inspect_java_binary(&java_bin),
+        None => ("Unknown".to_string(), true),
+    }
+}
+
+fn inspect_java_binary(java_bin: &Path) -> (String, bool) {
+    let banner = run_java_version(java_bin);
+
+    let version = banner.as_deref()
+        .and_then(parse_version_banner)
+        .unwrap_or_else(|| "Unknown".to_string());
+    let is_64_bit = banner.as_deref()
+        .and_then(parse_banner_is_64_bit)
+        .or_else(|| binary_is_64_bit(java_bin))
+        .unwrap_or(true);
+
+    (version, is_64_bit)
+}
+
+/// Сломанный или сетевой JDK может не ответить вовсе
+const JAVA_VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Run `java -version` and return its banner (printed to stderr)
+///
+/// The process is killed after [`JAVA_VERSION_TIMEOUT`]; that counts as no answer.
+fn run_java_version(java_bin: &Path) -> Option<String> {
+    run_java_version_within(java_bin, JAVA_VERSION_TIMEOUT)
+}
+
+fn run_java_version_within(java_bin: &Path, timeout: Duration) -> Option<String> {
+    let mut child = Command::new(java_bin)
+        .arg("-version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            _ => {
+                eprintln!("[Java] ⚠️ {} -version did not finish in {:?}, skipping", java_bin.display(), timeout);
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+
+    // Баннер короткий и помещается в буфер канала, читать его можно после выхода
+    let mut banner = String::new();
+    child.stderr.take()?.read_to_string(&mut banner).ok()?;
+    Some(banner)
+}
+
+fn parse_version_banner(banner: &str) -> Option<String> {
+    // Parse version from output
+    // Format: "openjdk version \"17.0.1\" ..."
+    if let Some(start) = banner.find('"') {
+        if let Some(end) = banner[start + 1..].find('"') {
+            return Some(banner[start + 1..start + 1 + end].to_string());
+        }
+    }
+
+    // Fallback: extract version number
+    banner.lines()
+        .find(|line| line.contains("version"))
+        .map(|line| line.trim().to_string())
+}
+
+/// Describe the Java executable the game is about to be launched with
+///
+/// Unlike the installs from [`find_java_installations`], `path` is the binary itself.
+pub fn probe_java_binary(java_bin: &Path) -> JavaInstallation {
+    let (version, is_64_bit) = inspect_java_binary(java_bin);
+    JavaInstallation {
+        path: java_bin.to_path_buf(),
+        major_version: parse_major_version(&version),
+        version,
+        is_64_bit,
+    }
+}
+
+/// Java majors a Minecraft version runs on
+#[derive(Debug, Clone, Copy)]
+pub struct JavaRequirement {
+    pub min: u32,
+    /// Старые версии на LWJGL 2 не запускаются на новой Java
+    pub max: Option<u32>,
+}
+
+/// Built-in table: Java 8 up to 1.16, 17 for 1.17-1.20.4, 21 from 1.20.5
+///
+/// Versions that are not "1.x[.y]" (snapshots like "24w14a") have no known requirement.
+pub fn java_requirement(mc_version: &str) -> Option<JavaRequirement> {
+    let (minor, patch) = version_json::release_number(mc_version)?;
+
+    let requirement = match (minor, patch) {
+        (..=16, _) => JavaRequirement { min: 8, max: Some(8) },
+        (17..=19, _) | (20, ..=4) => JavaRequirement { min: 17, max: None },
+        _ => JavaRequirement { min: 21, max: None },
+    };
+    Some(requirement)
+}
+
+/// Check the Java major version against the Minecraft version; an unknown version passes
+pub fn java_meets_requirement(java: &JavaInstallation, mc_version: &str) -> Result<(), String> {
+    let (Some(major), Some(requirement)) = (java.major_version, java_requirement(mc_version)) else {
+        return Ok(());
+    };
+
+    if major < requirement.min {
+        return Err(format!(
+            "Minecraft {} requires Java {} or newer, but the selected Java is {}.",
+            mc_version, requirement.min, major
+        ));
     }
+    if let Some(max) = requirement.max.filter(|&max| major > max) {
+        return Err(format!(
+            "Minecraft {} is meant to run on Java {}; Java {} may fail to start it.",
+            mc_version, max, major
+        ));
+    }
+    Ok(())
+}
+
+/// Check whether a detected Java can run the given Minecraft version
+#[tauri::command]
+pub async fn check_java_requirement(java: JavaInstallation, minecraft_version: String) -> Result<(), LauncherError> {
+    check_installation(&java, &minecraft_version)
+}
+
+/// The install still exists and its major version suits `mc_version`
+fn check_installation(java: &JavaInstallation, mc_version: &str) -> Result<(), LauncherError> {
+    // path - каталог установки или сам бинарник (probe_java_binary)
+    if !java.path.is_file() && find_java_executable(&java.path).is_none() {
+        return Err(LauncherError::java_not_found(format!(
+            "Java was not found at {}. Pick another Java in settings.",
+            java.path.display()
+        )));
+    }
+    java_meets_requirement(java, mc_version)
+        .map_err(|message| LauncherError::JavaIncompatible { message })
+}
+
+/// Major Java version from both numbering schemes: "1.8.0_402" -> 8, "21.0.2" -> 21
+pub fn parse_major_version(version: &str) -> Option<u32> {
+    let mut parts = version.trim().split(['.', '_', '-', '+']);
+    let leading_number = |part: &str| -> Option<u32> {
+        let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok()
+    };
+
+    match leading_number(parts.next()?)? {
+        // Старая схема 1.x до Java 9
+        1 => parts.next().and_then(leading_number),
+        major => Some(major),
+    }
+}
+
+/// "64-Bit Server VM" vs "Client VM" / "32-Bit" in the `java -version` banner
+fn parse_banner_is_64_bit(banner: &str) -> Option<bool> {
+    let banner = banner.to_lowercase();
+    if banner.contains("64-bit") {
+        Some(true)
+    } else if banner.contains("32-bit") || banner.contains("client vm") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Bitness from the executable header: ELF class, PE machine type or Mach-O magic
+fn binary_is_64_bit(path: &Path) -> Option<bool> {
+    let mut file = File::open(path).ok()?;
+    let mut header = [0u8; 64];
+    file.read_exact(&mut header).ok()?;
 
-    "Unknown".to_string()
+    match header {
+        // ELF: EI_CLASS 1 - 32-bit, 2 - 64-bit
+        [0x7f, b'E', b'L', b'F', class, ..] => match class {
+            1 => Some(false),
+            2 => Some(true),
+            _ => None,
+        },
+        // PE: смещение заголовка в e_lfanew, за сигнатурой идет тип машины
+        [b'M', b'Z', ..] => {
+            let pe_offset = u32::from_le_bytes([header[0x3c], header[0x3d], header[0x3e], header[0x3f]]);
+            let mut pe = [0u8; 6];
+            file.seek(SeekFrom::Start(pe_offset as u64)).ok()?;
+            file.read_exact(&mut pe).ok()?;
+            if &pe[..4] != b"PE\0\0" {
+                return None;
+            }
+            match u16::from_le_bytes([pe[4], pe[5]]) {
+                0x8664 | 0xaa64 => Some(true),
+                0x014c => Some(false),
+                _ => None,
+            }
+        }
+        [0xcf, 0xfa, 0xed, 0xfe, ..] => Some(true),
+        [0xce, 0xfa, 0xed, 0xfe, ..] => Some(false),
+        _ => None,
+    }
 }
 
 fn find_java_executable(java_path: &Path) -> Option<PathBuf> {
@@ -120,16 +438,249 @@ fn find_java_executable(java_path: &Path) -> Option<PathBuf> {
     };
 
     // Check common locations
-    let candidates = vec![
-        java_path.join("bin").join(bin_name),
-        java_path.join(bin_name),
-    ];
+    [java_path.join("bin").join(bin_name), java_path.join(bin_name)]
+        .into_iter()
+        .find(|candidate| candidate.exists())
+}
+
+/// Resolve the configured Java command to a file on disk
+///
+/// A bare name such as "java" is looked up in PATH the same way the OS would
+/// when spawning it; anything with a directory part is taken as-is.
+pub fn resolve_java_command(java_path: &str) -> Option<PathBuf> {
+    let path = Path::new(java_path);
+    if path.components().count() > 1 || path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
 
-    for candidate in candidates {
-        if candidate.exists() {
+    let path_var = env::var_os("PATH")?;
+    env::split_paths(&path_var).find_map(|dir| {
+        let candidate = dir.join(java_path);
+        if candidate.is_file() {
             return Some(candidate);
         }
+        if env::consts::OS == "windows" {
+            let exe = dir.join(format!("{}.exe", java_path));
+            if exe.is_file() {
+                return Some(exe);
+            }
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_java_is_reported_as_java_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let java = JavaInstallation {
+            path: dir.path().join("jdk-17"),
+            version: "17.0.1".to_string(),
+            major_version: Some(17),
+            is_64_bit: true,
+        };
+        let err = check_installation(&java, "1.20.4").unwrap_err();
+        assert!(matches!(err, LauncherError::JavaNotFound { .. }));
+        assert_eq!(serde_json::to_value(&err).unwrap()["kind"], "JavaNotFound");
     }
 
-    None
+    #[test]
+    fn old_java_is_reported_as_incompatible() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("bin")).unwrap();
+        std::fs::write(dir.path().join("bin").join(if cfg!(windows) { "java.exe" } else { "java" }), "").unwrap();
+        let java = JavaInstallation {
+            path: dir.path().to_path_buf(),
+            version: "1.8.0_402".to_string(),
+            major_version: Some(8),
+            is_64_bit: true,
+        };
+        assert!(matches!(check_installation(&java, "1.20.4"), Err(LauncherError::JavaIncompatible { .. })));
+        assert!(check_installation(&java, "1.12.2").is_ok());
+    }
+
+    const BANNER_64: &str = "openjdk version \"17.0.10\" 2024-01-16\n\
+        OpenJDK Runtime Environment Temurin-17.0.10+7 (build 17.0.10+7)\n\
+        OpenJDK 64-Bit Server VM Temurin-17.0.10+7 (build 17.0.10+7, mixed mode, sharing)\n";
+    const BANNER_32: &str = "java version \"1.8.0_391\"\n\
+        Java(TM) SE Runtime Environment (build 1.8.0_391-b13)\n\
+        Java HotSpot(TM) Client VM (build 25.391-b13, mixed mode, sharing)\n";
+
+    #[test]
+    fn banner_reports_architecture() {
+        assert_eq!(parse_banner_is_64_bit(BANNER_64), Some(true));
+        assert_eq!(parse_banner_is_64_bit(BANNER_32), Some(false));
+        assert_eq!(parse_banner_is_64_bit("openjdk version \"21\"\n"), None);
+
+        assert_eq!(parse_version_banner(BANNER_64).as_deref(), Some("17.0.10"));
+        assert_eq!(parse_version_banner(BANNER_32).as_deref(), Some("1.8.0_391"));
+    }
+
+    #[test]
+    fn binary_header_reports_architecture() {
+        let dir = tempfile::tempdir().unwrap();
+        let elf = |class: u8| {
+            let mut header = vec![0u8; 64];
+            header[..5].copy_from_slice(&[0x7f, b'E', b'L', b'F', class]);
+            header
+        };
+        std::fs::write(dir.path().join("java64"), elf(2)).unwrap();
+        std::fs::write(dir.path().join("java32"), elf(1)).unwrap();
+        std::fs::write(dir.path().join("script"), "#!/bin/sh\n").unwrap();
+
+        assert_eq!(binary_is_64_bit(&dir.path().join("java64")), Some(true));
+        assert_eq!(binary_is_64_bit(&dir.path().join("java32")), Some(false));
+        assert_eq!(binary_is_64_bit(&dir.path().join("script")), None);
+    }
+
+    #[test]
+    fn major_version_from_both_schemes() {
+        assert_eq!(parse_major_version("1.8.0_402"), Some(8));
+        assert_eq!(parse_major_version("11.0.20"), Some(11));
+        assert_eq!(parse_major_version("17"), Some(17));
+        assert_eq!(parse_major_version("21.0.2"), Some(21));
+        assert_eq!(parse_major_version("Unknown"), None);
+        assert_eq!(parse_major_version(""), None);
+    }
+
+    #[test]
+    fn detected_java_is_sorted_newest_first() {
+        let java = |version: &str| JavaInstallation {
+            path: PathBuf::from(format!("/jvm/{}", version)),
+            version: version.to_string(),
+            major_version: parse_major_version(version),
+            is_64_bit: true,
+        };
+        let mut installations = vec![java("1.8.0_402"), java("Unknown"), java("21.0.2"), java("17.0.10")];
+        sort_newest_first(&mut installations);
+
+        let versions: Vec<&str> = installations.iter().map(|java| java.version.as_str()).collect();
+        assert_eq!(versions, ["21.0.2", "17.0.10", "1.8.0_402", "Unknown"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hanging_java_times_out() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let java = dir.path().join("java");
+        std::fs::write(&java, "#!/bin/sh\nexec sleep 30\n").unwrap();
+        std::fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let started = Instant::now();
+        assert_eq!(run_java_version_within(&java, Duration::from_millis(300)), None);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn jdk_bundles_resolve_to_contents_home() {
+        let dir = tempfile::tempdir().unwrap();
+        for bundle in ["temurin-21.jdk", "zulu-17.jdk"] {
+            std::fs::create_dir_all(dir.path().join(bundle).join("Contents/Home/bin")).unwrap();
+        }
+        // Битый bundle без Contents/Home и посторонний каталог пропускаются
+        std::fs::create_dir_all(dir.path().join("broken.jdk/Contents")).unwrap();
+        std::fs::create_dir_all(dir.path().join("notes")).unwrap();
+
+        assert_eq!(
+            jdk_bundle_homes(dir.path()),
+            vec![
+                dir.path().join("temurin-21.jdk/Contents/Home"),
+                dir.path().join("zulu-17.jdk/Contents/Home"),
+            ]
+        );
+        assert!(jdk_bundle_homes(&dir.path().join("missing")).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_and_path_java_homes_are_listed_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let jdk = dir.path().join("java-17-openjdk");
+        std::fs::create_dir_all(jdk.join("bin")).unwrap();
+        let default_java = dir.path().join("default-java");
+        std::os::unix::fs::symlink(&jdk, &default_java).unwrap();
+        let other = dir.path().join("java-21-openjdk");
+        std::fs::create_dir_all(other.join("bin")).unwrap();
+
+        // Java из PATH приходит уже разрешенным путем: тот же JDK, что и default-java
+        let path_home = std::fs::canonicalize(&jdk).unwrap();
+        let candidates = vec![default_java.clone(), jdk.clone(), other.clone(), path_home, dir.path().join("missing")];
+
+        let paths: Vec<PathBuf> = collect_installations(candidates).into_iter().map(|java| java.path).collect();
+        assert_eq!(paths, vec![default_java, other]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn seen_paths_compare_by_resolved_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let jdk = dir.path().join("jdk");
+        std::fs::create_dir(&jdk).unwrap();
+        let link = dir.path().join("current");
+        std::os::unix::fs::symlink(&jdk, &link).unwrap();
+
+        let mut seen = Vec::new();
+        assert!(mark_seen(&mut seen, &link));
+        assert!(!mark_seen(&mut seen, &jdk));
+        assert!(!mark_seen(&mut seen, &dir.path().join("./jdk")));
+
+        // Несуществующий путь сравнивается как есть
+        assert!(mark_seen(&mut seen, Path::new("/no/such/jdk")));
+        assert!(!mark_seen(&mut seen, Path::new("/no/such/jdk")));
+    }
+
+    #[test]
+    fn java_requirement_by_minecraft_version() {
+        let java = |major: u32| JavaInstallation {
+            path: PathBuf::from("/jvm"),
+            version: major.to_string(),
+            major_version: Some(major),
+            is_64_bit: true,
+        };
+        assert!(java_meets_requirement(&java(8), "1.12.2").is_ok());
+        assert!(java_meets_requirement(&java(8), "1.16.5").is_ok());
+        assert!(java_meets_requirement(&java(21), "1.8.9").unwrap_err().contains("Java 8"));
+        assert!(java_meets_requirement(&java(8), "1.20.4").unwrap_err().contains("Java 17"));
+        assert!(java_meets_requirement(&java(17), "1.17.1").is_ok());
+        assert!(java_meets_requirement(&java(17), "1.20.5").unwrap_err().contains("Java 21"));
+        assert!(java_meets_requirement(&java(21), "1.21").is_ok());
+        // Снапшоты без известного требования не проверяются
+        assert!(java_meets_requirement(&java(8), "24w14a").is_ok());
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn registry_keys_resolve_to_java_homes() {
+        use winreg::enums::{HKEY_CURRENT_USER, KEY_WOW64_64KEY};
+        use winreg::RegKey;
+
+        // Раскладка HKLM в отдельном ключе HKCU, чтобы не трогать настоящий реестр
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        let scratch = format!(r"Software\ALauncherTests\java-{}", std::process::id());
+        let (root, _) = hkcu.create_subkey(&scratch).unwrap();
+
+        let (oracle, _) = root.create_subkey(r"SOFTWARE\JavaSoft\JDK\17").unwrap();
+        oracle.set_value("JavaHome", &r"C:\Program Files\Java\jdk-17").unwrap();
+        let (adoptium, _) = root.create_subkey(r"SOFTWARE\Eclipse Adoptium\JDK\21.0.2.13\hotspot\MSI").unwrap();
+        adoptium.set_value("Path", &r"C:\Program Files\Eclipse Adoptium\jdk-21.0.2.13-hotspot\ ").unwrap();
+        // Ключ версии без пути пропускается
+        root.create_subkey(r"SOFTWARE\JavaSoft\JRE\1.8").unwrap();
+
+        let mut homes = registry::java_homes_in(&root, KEY_WOW64_64KEY);
+        homes.sort();
+        hkcu.delete_subkey_all(&scratch).unwrap();
+
+        assert_eq!(
+            homes,
+            vec![
+                PathBuf::from(r"C:\Program Files\Eclipse Adoptium\jdk-21.0.2.13-hotspot\"),
+                PathBuf::from(r"C:\Program Files\Java\jdk-17"),
+            ]
+        );
+    }
 }
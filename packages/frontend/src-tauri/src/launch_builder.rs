@@ -0,0 +1,327 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use serde::Deserialize;
+use crate::accounts::AccountInfo;
+use crate::arg_template::{self, TemplateVars};
+use crate::error::LauncherError;
+use crate::game_launcher::{self, LaunchParams, Resolution};
+use crate::process_tuning::ProcessPriority;
+use crate::version_json::{self, Argument, Features, OsInfo, VersionJson};
+
+/// Where the files of a resolved version live
+#[derive(Debug, Clone, Deserialize)]
+pub struct LaunchPaths {
+    pub game_dir: String,
+    pub assets_dir: String,
+    pub libraries_dir: String,
+    /// Contains `<id>/<id>.jar` of the client
+    pub versions_dir: String,
+    /// Empty - `<game_dir>/natives`, as in `LaunchParams`
+    #[serde(default)]
+    pub natives_dir: String,
+}
+
+/// Profile settings that are not part of the version json
+#[derive(Debug, Clone, Deserialize)]
+pub struct LaunchOptions {
+    pub profile_id: String,
+    pub java_path: String,
+    #[serde(default)]
+    pub java_version: String,
+    pub max_ram: String,
+    #[serde(default)]
+    pub min_ram: Option<String>,
+    pub resolution: Resolution,
+    #[serde(default)]
+    pub full_screen: bool,
+    /// Extra JVM arguments, added after the version's own
+    #[serde(default)]
+    pub jvm_args: Vec<String>,
+    /// Extra client arguments, added before the version's own
+    #[serde(default)]
+    pub client_args: Vec<String>,
+    #[serde(default)]
+    pub server_address: Option<String>,
+    #[serde(default)]
+    pub server_port: Option<i32>,
+    #[serde(default)]
+    pub minimize_on_launch: bool,
+    #[serde(default)]
+    pub cpu_affinity: Option<Vec<usize>>,
+    #[serde(default)]
+    pub wrapper_command: Option<Vec<String>>,
+    #[serde(default)]
+    pub disable_start_on_first_thread: bool,
+    #[serde(default)]
+    pub env_vars: HashMap<String, String>,
+    #[serde(default)]
+    pub pre_launch: Option<Vec<String>>,
+    #[serde(default)]
+    pub post_exit: Option<Vec<String>>,
+    #[serde(default)]
+    pub process_priority: ProcessPriority,
+    #[serde(default)]
+    pub structured_logs: bool,
+    #[serde(default)]
+    pub debug_port: Option<u16>,
+    #[serde(default)]
+    pub output_buffer_bytes: Option<usize>,
+}
+
+/// Turn a resolved version json into ready `LaunchParams` for `launch_game_client`
+///
+/// The classpath holds the libraries allowed on this OS plus the client jar. JVM and
+/// game argument templates are filtered by their rules and `${...}` is substituted.
+/// `-cp` and `-Djava.library.path` from the version are dropped, `launch_game_client`
+/// passes its own.
+pub fn build_launch_params(
+    version: &VersionJson,
+    paths: &LaunchPaths,
+    account: &AccountInfo,
+    options: &LaunchOptions,
+) -> Result<LaunchParams, LauncherError> {
+    let mut params = LaunchParams {
+        profile_id: options.profile_id.clone(),
+        version: version.id.clone(),
+        asset_index: version.asset_index.as_ref()
+            .map(|index| index.id.clone())
+            .or_else(|| version.assets.clone())
+            .unwrap_or_default(),
+        username: account.username.clone(),
+        uuid: account.uuid.clone(),
+        access_token: account.access_token.clone().unwrap_or_default(),
+        offline: account.account_type == "offline",
+        game_dir: paths.game_dir.clone(),
+        assets_dir: paths.assets_dir.clone(),
+        resolution: options.resolution.clone(),
+        full_screen: options.full_screen,
+        java_path: options.java_path.clone(),
+        java_version: options.java_version.clone(),
+        max_ram: options.max_ram.clone(),
+        min_ram: options.min_ram.clone(),
+        jvm_args: Vec::new(),
+        client_args: options.client_args.clone(),
+        game_args: None,
+        main_class: version.main_class.clone(),
+        class_path: version_class_path(version, paths),
+        server_address: options.server_address.clone(),
+        server_port: options.server_port,
+        minimize_on_launch: options.minimize_on_launch,
+        cpu_affinity: options.cpu_affinity.clone(),
+        wrapper_command: options.wrapper_command.clone(),
+        disable_start_on_first_thread: options.disable_start_on_first_thread,
+        env_vars: options.env_vars.clone(),
+        pre_launch: options.pre_launch.clone(),
+        post_exit: options.post_exit.clone(),
+        process_priority: options.process_priority,
+        structured_logs: options.structured_logs,
+        debug_port: options.debug_port,
+        natives_dir: paths.natives_dir.clone(),
+        output_buffer_bytes: options.output_buffer_bytes,
+    };
+
+    let vars = version_template_vars(version, &params)?;
+
+    let jvm_templates = without_launcher_owned_jvm_args(allowed_args(&version.arguments.jvm, &OsInfo::current(), &Features::default()));
+    let mut jvm_args = arg_template::substitute_all(&jvm_templates, &game_launcher::jvm_template_vars(&vars));
+    jvm_args.extend(options.jvm_args.iter().cloned());
+
+    params.jvm_args = jvm_args;
+    params.game_args = Some(version_game_args(version, &vars));
+    Ok(params)
+}
+
+/// `${...}` values for the argument templates of `version`: the launch values plus the version type
+pub fn version_template_vars(version: &VersionJson, params: &LaunchParams) -> Result<TemplateVars, LauncherError> {
+    let natives_dir = game_launcher::natives_dir(params);
+    let class_path = game_launcher::join_classpath_entries(&game_launcher::build_class_path(params))?;
+    let mut vars = game_launcher::launch_template_vars(params, &natives_dir, &class_path);
+    if let Some(version_type) = &version.version_type {
+        vars.set("version_type", version_type.clone());
+    }
+    Ok(vars)
+}
+
+/// Game arguments of `version` allowed on this OS with `${...}` expanded, i.e. `LaunchParams::game_args`
+pub fn version_game_args(version: &VersionJson, vars: &TemplateVars) -> Vec<String> {
+    // --width/--height добавляет launch_game_client, поэтому has_custom_resolution выключена
+    let templates = allowed_args(&version.arguments.game, &OsInfo::current(), &Features::default());
+    arg_template::substitute_all(&templates, vars)
+}
+
+/// Build `LaunchParams` from a version json (see `version_json::resolve_version`)
+#[tauri::command]
+pub async fn build_version_launch_params(
+    version: VersionJson,
+    paths: LaunchPaths,
+    account: AccountInfo,
+    options: LaunchOptions,
+) -> Result<LaunchParams, LauncherError> {
+    build_launch_params(&version, &paths, &account, &options)
+}
+
+/// Absolute classpath: allowed libraries in manifest order, then the client jar
+fn version_class_path(version: &VersionJson, paths: &LaunchPaths) -> Vec<String> {
+    let libraries_dir = Path::new(&paths.libraries_dir);
+    let (os, features) = (OsInfo::current(), Features::default());
+    let mut seen = HashSet::new();
+    let mut class_path = Vec::new();
+
+    for library in &version.libraries {
+        // Старые natives-библиотеки без artifact распаковываются, а не добавляются в classpath
+        let natives_only = library.natives.is_some()
+            && library.downloads.as_ref().map_or(true, |d| d.artifact.is_none());
+        if natives_only || !version_json::rule_allows(&library.rules, &os, &features) {
+            continue;
+        }
+
+        if let Some(path) = library.artifact_path() {
+            let entry = libraries_dir.join(path).to_string_lossy().to_string();
+            if seen.insert(entry.clone()) {
+                class_path.push(entry);
+            }
+        }
+    }
+
+    let jar_id = version.client_jar_id();
+    class_path.push(
+        Path::new(&paths.versions_dir)
+            .join(jar_id)
+            .join(format!("{}.jar", jar_id))
+            .to_string_lossy()
+            .to_string(),
+    );
+    class_path
+}
+
+pub(crate) fn allowed_args(args: &[Argument], os: &OsInfo, features: &Features) -> Vec<String> {
+    args.iter().flat_map(|arg| arg.allowed_values(os, features)).collect()
+}
+
+/// Drop `-cp ${classpath}` and `-Djava.library.path=...`, launch_game_client adds them itself
+fn without_launcher_owned_jvm_args(args: Vec<String>) -> Vec<String> {
+    let mut result = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "-cp" || arg == "-classpath" {
+            args.next();
+            continue;
+        }
+        if arg.starts_with("-Djava.library.path=") {
+            continue;
+        }
+        result.push(arg);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version() -> VersionJson {
+        serde_json::from_value(serde_json::json!({
+            "id": "1.20.4",
+            "type": "release",
+            "mainClass": "net.minecraft.client.main.Main",
+            "assetIndex": {"id": "12", "url": "", "sha1": ""},
+            "arguments": {
+                "game": [
+                    "--username", "${auth_player_name}",
+                    "--version", "${version_name}",
+                    "--assetIndex", "${assets_index_name}",
+                    "--accessToken", "${auth_access_token}",
+                    {"rules": [{"action": "allow", "features": {"is_demo_user": true}}], "value": "--demo"}
+                ],
+                "jvm": [
+                    "-Djava.library.path=${natives_directory}",
+                    "-Dtoken=${auth_access_token}",
+                    "-cp", "${classpath}",
+                    "-Dminecraft.launcher.version=${version_name}"
+                ]
+            },
+            "libraries": [
+                {"name": "com.mojang:brigadier:1.2.9"},
+                {"name": "com.mojang:brigadier:1.2.9"},
+                {"name": "org.lwjgl:lwjgl-platform:2.9.4", "natives": {"linux": "natives-linux", "windows": "natives-windows", "osx": "natives-osx"}},
+                {"name": "com.example:other-os-only:1.0", "rules": [{"action": "allow", "os": {"name": "no-such-os"}}]}
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn paths() -> LaunchPaths {
+        serde_json::from_value(serde_json::json!({
+            "game_dir": "/games/pack-1",
+            "assets_dir": "/games/assets",
+            "libraries_dir": "/games/libraries",
+            "versions_dir": "/games/versions"
+        }))
+        .unwrap()
+    }
+
+    fn account() -> AccountInfo {
+        serde_json::from_value(serde_json::json!({
+            "id": "account-1",
+            "username": "Steve",
+            "uuid": "069a79f444e94726a5befca90e38aaf5",
+            "account_type": "microsoft",
+            "access_token": "secret-token"
+        }))
+        .unwrap()
+    }
+
+    fn options() -> LaunchOptions {
+        serde_json::from_value(serde_json::json!({
+            "profile_id": "pack-1",
+            "java_path": "java",
+            "max_ram": "4096",
+            "resolution": {"width": 854, "height": 480},
+            "jvm_args": ["-XX:+UseG1GC"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn class_path_has_allowed_libraries_then_client_jar() {
+        let params = build_launch_params(&version(), &paths(), &account(), &options()).unwrap();
+
+        let libraries = Path::new("/games/libraries");
+        let client_jar = Path::new("/games/versions").join("1.20.4").join("1.20.4.jar");
+        assert_eq!(params.class_path, vec![
+            libraries.join("com/mojang/brigadier/1.2.9/brigadier-1.2.9.jar").to_string_lossy().to_string(),
+            client_jar.to_string_lossy().to_string(),
+        ]);
+        assert_eq!(params.main_class, "net.minecraft.client.main.Main");
+        assert_eq!(params.asset_index, "12");
+        assert!(!params.offline);
+    }
+
+    #[test]
+    fn argument_templates_are_filtered_and_substituted() {
+        let params = build_launch_params(&version(), &paths(), &account(), &options()).unwrap();
+
+        let game_args = params.game_args.unwrap();
+        assert_eq!(game_args, [
+            "--username", "Steve",
+            "--version", "1.20.4",
+            "--assetIndex", "12",
+            "--accessToken", "secret-token",
+        ]);
+
+        // -cp и java.library.path добавляет launch_game_client, токен в JVM-аргументы не попадает
+        assert!(!params.jvm_args.iter().any(|arg| arg == "-cp" || arg.starts_with("-Djava.library.path=")));
+        assert!(!params.jvm_args.iter().any(|arg| arg.contains("secret-token")));
+        assert!(params.jvm_args.contains(&"-Dminecraft.launcher.version=1.20.4".to_string()));
+        assert_eq!(params.jvm_args.last().map(String::as_str), Some("-XX:+UseG1GC"));
+    }
+
+    #[test]
+    fn launcher_owned_jvm_args_are_dropped() {
+        let args = ["-cp", "${classpath}", "-Xss1M", "-classpath", "a.jar", "-Djava.library.path=/natives"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(without_launcher_owned_jvm_args(args), ["-Xss1M"]);
+    }
+}
@@ -0,0 +1,122 @@
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use crate::process_tuning;
+
+/// A hook still running after this long is killed and counts as failed
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Run a user hook command (`command[0]` with the rest as arguments) and wait for it
+///
+/// The hook runs in the game directory, its stdout and stderr go to the launcher log.
+/// A non-zero exit code or running past [`HOOK_TIMEOUT`] is an error. An empty command
+/// does nothing. Blocks, so async callers run it in `spawn_blocking`.
+pub fn run_hook(name: &str, command: &[String], game_dir: &str) -> Result<(), String> {
+    run_hook_within(name, command, game_dir, HOOK_TIMEOUT)
+}
+
+fn run_hook_within(name: &str, command: &[String], game_dir: &str, timeout: Duration) -> Result<(), String> {
+    let Some((program, args)) = command.split_first() else {
+        return Ok(());
+    };
+
+    // Аргументы хука могут содержать токены и пароли, в лог пишем только программу
+    eprintln!("[Launcher Backend] Running {} hook: {} ({} arguments)", name, program, args.len());
+    let mut cmd = Command::new(program);
+    cmd.args(args)
+        .current_dir(game_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Своя группа процессов, чтобы по таймауту убить и запущенные хуком процессы
+    process_tuning::isolate_process_group(&mut cmd);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = cmd.spawn()
+        .map_err(|e| format!("Failed to run {} hook '{}': {}", name, program, e))?;
+    forward_lines(name.to_string(), child.stdout.take());
+    forward_lines(format!("{} stderr", name), child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            Ok(None) => {
+                let _ = process_tuning::kill_process_tree(&mut child);
+                let _ = child.wait();
+                return Err(format!("{} hook '{}' did not finish in {:?} and was killed", name, program, timeout));
+            }
+            Err(e) => return Err(format!("Failed to wait for {} hook '{}': {}", name, program, e)),
+        }
+    };
+
+    if !status.success() {
+        return Err(format!("{} hook '{}' failed with {}", name, program, status));
+    }
+    eprintln!("[Launcher Backend] ✅ {} hook finished", name);
+    Ok(())
+}
+
+/// Copy the hook's output to the launcher log line by line
+///
+/// Read on a separate thread so a chatty hook can't fill the pipe and stall; the thread
+/// ends when every process holding the pipe has exited.
+fn forward_lines(label: String, stream: Option<impl Read + Send + 'static>) {
+    let Some(stream) = stream else {
+        return;
+    };
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            eprintln!("[Launcher Backend] [{}] {}", label, line);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell(script: &str) -> Vec<String> {
+        vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+    }
+
+    #[test]
+    fn empty_hook_does_nothing() {
+        assert!(run_hook("pre-launch", &[], ".").is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hook_runs_in_game_dir_and_reports_exit_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let game_dir = dir.path().to_str().unwrap();
+
+        run_hook("pre-launch", &shell("echo ready > marker"), game_dir).unwrap();
+        assert!(dir.path().join("marker").is_file());
+
+        let error = run_hook("pre-launch", &shell("exit 3"), game_dir).unwrap_err();
+        assert!(error.contains("failed"), "{}", error);
+
+        let missing = vec!["/nonexistent/hook".to_string()];
+        assert!(run_hook("pre-launch", &missing, game_dir).unwrap_err().contains("Failed to run"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hanging_hook_is_killed_after_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let started = Instant::now();
+
+        let error = run_hook_within("pre-launch", &shell("sleep 30"), dir.path().to_str().unwrap(), Duration::from_millis(200))
+            .unwrap_err();
+        assert!(error.contains("did not finish"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
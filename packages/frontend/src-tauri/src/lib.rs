@@ -1,21 +1,51 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::fs::{self, File};
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 use tauri::Manager;
-use sha2::{Sha256, Digest};
-use std::io::Read;
+use error::LauncherError;
 
 #[cfg(not(debug_assertions))]
 use tauri::{menu::{Menu, MenuItem}, tray::{TrayIconBuilder, TrayIconEvent}};
 
+mod accounts;
+mod arg_template;
+mod archive;
+mod assets;
+mod cancellation;
+mod client_jar;
+mod deep_link;
+mod diagnostics;
+mod download;
+mod error;
+mod file_transfer;
+mod fs_utils;
 mod game_launcher;
+mod gpu;
+mod hashing;
+mod instance_icon;
+mod java_install;
 mod java_locator;
-
-#[tauri::command]
-fn find_java_installations() -> Vec<java_locator::JavaInstallation> {
-    java_locator::find_java_installations()
-}
+mod launch_builder;
+mod launch_hooks;
+mod libraries;
+mod loader_detector;
+mod log4j;
+mod log_buffer;
+mod logger;
+mod microsoft_login;
+mod mods;
+mod network;
+mod paths;
+mod permissions;
+mod process_tuning;
+mod screenshots;
+mod server_ping;
+mod servers_dat;
+mod shortcuts;
+mod system_info;
+mod version_json;
+mod version_manifest;
 
 // ===== FILE OPERATIONS COMMANDS =====
 
@@ -35,173 +65,338 @@ async fn get_arch() -> String {
 }
 
 #[tauri::command]
-async fn file_exists(path: String) -> Result<bool, String> {
+async fn file_exists(path: String) -> Result<bool, LauncherError> {
     Ok(std::path::Path::new(&path).exists())
 }
 
 #[tauri::command]
-async fn read_file(path: String) -> Result<String, String> {
+async fn read_file(path: String) -> Result<String, LauncherError> {
     fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read file: {}", e))
+        .map_err(|e| LauncherError::io("Failed to read file", e))
 }
 
 #[tauri::command]
-async fn write_file(path: String, content: String) -> Result<(), String> {
+async fn write_file(path: String, content: String) -> Result<(), LauncherError> {
     fs::write(&path, content)
-        .map_err(|e| format!("Failed to write file: {}", e))
+        .map_err(|e| LauncherError::io("Failed to write file", e))
 }
 
+/// Read a binary file as base64
 #[tauri::command]
-async fn calculate_file_hash(path: String, algorithm: String) -> Result<String, String> {
-    if algorithm != "sha256" {
-        return Err(format!("Unsupported hash algorithm: {}. Only sha256 is supported.", algorithm));
-    }
+async fn read_file_bytes(path: String) -> Result<String, LauncherError> {
+    use base64::Engine;
 
-    let mut file = File::open(&path)
-        .map_err(|e| format!("Failed to open file for hashing: {}", e))?;
+    let bytes = fs::read(&path)
+        .map_err(|e| LauncherError::io("Failed to read file", e))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
 
-    let mut hasher = Sha256::new();
-    let mut buffer = [0u8; 8192];
+/// Write base64 content to a file as raw bytes
+#[tauri::command]
+async fn write_file_bytes(path: String, base64_content: String) -> Result<(), LauncherError> {
+    use base64::Engine;
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(base64_content.trim())
+        .map_err(|e| LauncherError::invalid_params(format!("Invalid base64 content: {}", e)))?;
+    fs::write(&path, bytes)
+        .map_err(|e| LauncherError::io("Failed to write file", e))
+}
 
-    loop {
-        let n = file.read(&mut buffer)
-            .map_err(|e| format!("Failed to read file for hashing: {}", e))?;
-        if n == 0 {
-            break;
-        }
-        hasher.update(&buffer[..n]);
+/// Delete a file or directory
+///
+/// Directories need `recursive` unless they are empty. A symlink is removed itself,
+/// its target is never touched.
+#[tauri::command]
+async fn delete_file(path: String, recursive: bool) -> Result<(), LauncherError> {
+    let metadata = fs::symlink_metadata(&path)
+        .map_err(|e| LauncherError::io("Failed to get file metadata", e))?;
+
+    if !metadata.is_dir() {
+        return fs::remove_file(&path)
+            .map_err(|e| LauncherError::io("Failed to delete file", e));
     }
 
-    Ok(hex::encode(hasher.finalize()))
+    if recursive {
+        return fs::remove_dir_all(&path)
+            .map_err(|e| LauncherError::io("Failed to delete directory", e));
+    }
+
+    let is_empty = fs::read_dir(&path)
+        .map_err(|e| LauncherError::io("Failed to read directory", e))?
+        .next()
+        .is_none();
+    if !is_empty {
+        return Err(LauncherError::invalid_params(format!(
+            "Directory is not empty: {} (pass recursive to delete its contents)",
+            path
+        )));
+    }
+
+    fs::remove_dir(&path)
+        .map_err(|e| LauncherError::io("Failed to delete directory", e))
 }
 
+/// Cut a runaway log down to its last `keep_bytes` (from a line boundary); returns the bytes freed
 #[tauri::command]
-async fn get_file_info(path: String) -> Result<FileInfo, String> {
-    let metadata = fs::metadata(&path)
-        .map_err(|e| format!("Failed to get file metadata: {}", e))?;
+async fn truncate_file_to_tail(path: String, keep_bytes: u64) -> Result<u64, LauncherError> {
+    tokio::task::spawn_blocking(move || fs_utils::truncate_to_tail(Path::new(&path), keep_bytes))
+        .await
+        .map_err(|e| format!("Truncate task failed: {}", e))?
+        .map_err(|e| LauncherError::io("Failed to truncate file", e))
+}
 
-    Ok(FileInfo {
-        size: metadata.len(),
-        is_file: metadata.is_file(),
-        is_dir: metadata.is_dir(),
-        modified: metadata.modified()
-            .ok()
-            .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as i64),
-    })
+#[tauri::command]
+async fn calculate_file_hash(path: String, algorithm: String) -> Result<String, LauncherError> {
+    if algorithm != "sha256" {
+        return Err(LauncherError::invalid_params(format!(
+            "Unsupported hash algorithm: {}. Only sha256 is supported.",
+            algorithm
+        )));
+    }
+
+    Ok(hashing::hash_file(Path::new(&path), &algorithm)?)
+}
+
+#[tauri::command]
+async fn get_file_info(path: String) -> Result<FileInfo, LauncherError> {
+    file_info(Path::new(&path))
+        .map_err(|e| LauncherError::io("Failed to get file metadata", e))
 }
 
 #[derive(serde::Serialize)]
 struct FileInfo {
+    path: String,
     size: u64,
     is_file: bool,
     is_dir: bool,
+    is_symlink: bool,
+    is_readonly: bool,
+    /// Миллисекунды с эпохи Unix; None, если ФС не знает время (или оно раньше 1970)
     modified: Option<i64>,
+    created: Option<u64>,
 }
 
+/// Metadata of the link target; a dangling symlink is described by the link itself
+fn file_info(path: &Path) -> std::io::Result<FileInfo> {
+    let link_metadata = fs::symlink_metadata(path)?;
+    let metadata = fs::metadata(path).unwrap_or_else(|_| link_metadata.clone());
+    let millis = |time: std::io::Result<std::time::SystemTime>| {
+        time.ok()?.duration_since(std::time::UNIX_EPOCH).ok().map(|d| d.as_millis())
+    };
+
+    Ok(FileInfo {
+        path: path.to_string_lossy().to_string(),
+        size: metadata.len(),
+        is_file: metadata.is_file(),
+        is_dir: metadata.is_dir(),
+        is_symlink: link_metadata.file_type().is_symlink(),
+        is_readonly: metadata.permissions().readonly(),
+        modified: millis(metadata.modified()).map(|ms| ms as i64),
+        created: millis(metadata.created()).map(|ms| ms as u64),
+    })
+}
+
+/// How deep `list_directory` descends when recursive
+const MAX_LIST_DEPTH: usize = 16;
+
+/// Children of a directory (all descendants with `recursive`), directories first
+///
+/// Symlinked directories are followed, but a link back into an ancestor is skipped
+/// instead of looping.
 #[tauri::command]
-async fn ensure_dir(path: String) -> Result<(), String> {
-    fs::create_dir_all(&path)
-        .map_err(|e| format!("Failed to create directory: {}", e))
+async fn list_directory(path: String, recursive: bool) -> Result<Vec<FileInfo>, LauncherError> {
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(LauncherError::not_found(format!("Directory not found: {}", path)));
+    }
+
+    let mut entries = tokio::task::spawn_blocking(move || {
+        let walker = walkdir::WalkDir::new(&root)
+            .min_depth(1)
+            .max_depth(if recursive { MAX_LIST_DEPTH } else { 1 })
+            .follow_links(true);
+
+        let mut entries = Vec::new();
+        for entry in walker {
+            match entry {
+                Ok(entry) => {
+                    if let Ok(info) = file_info(entry.path()) {
+                        entries.push(info);
+                    }
+                }
+                Err(e) if e.loop_ancestor().is_some() => {
+                    eprintln!("[Launcher Backend] ⚠️ Skipping symlink loop: {}", e);
+                }
+                Err(e) => eprintln!("[Launcher Backend] ⚠️ Failed to list entry: {}", e),
+            }
+        }
+        entries
+    })
+    .await
+    .map_err(|e| format!("Directory listing task failed: {}", e))?;
+
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.path.cmp(&b.path)));
+    Ok(entries)
 }
 
 #[tauri::command]
-async fn get_updates_dir() -> Result<String, String> {
-    let base_dir = if cfg!(target_os = "windows") {
-        dirs::data_local_dir()
-    } else if cfg!(target_os = "macos") {
-        dirs::data_dir()
-    } else {
-        dirs::data_local_dir()
-    };
+async fn ensure_dir(path: String) -> Result<(), LauncherError> {
+    fs::create_dir_all(&path)
+        .map_err(|e| LauncherError::io("Failed to create directory", e))
+}
 
-    match base_dir {
-        Some(dir) => Ok(dir.join("ALauncher").to_string_lossy().to_string()),
-        None => Err("Failed to determine updates directory".to_string()),
+#[tauri::command]
+async fn get_updates_dir() -> Result<String, LauncherError> {
+    match paths::app_data_dir() {
+        Some(dir) => Ok(dir.to_string_lossy().to_string()),
+        None => Err("Failed to determine updates directory".to_string().into()),
     }
 }
 
+/// Open a launcher directory in the file manager, creating it if missing
+///
+/// `kind`: "data", "logs", "instances" (the client root from `get_updates_dir`, i.e. the data dir) or "config".
 #[tauri::command]
-async fn download_file(
-    url: String,
-    dest_path: String,
-    _on_progress: bool,
-    access_token: Option<String>,
-    _app: tauri::AppHandle,
-) -> Result<(), String> {
-    use reqwest::header;
-
-    let client = reqwest::Client::new();
-    let mut request = client.get(&url);
-
-    if let Some(token) = access_token {
-        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+async fn open_app_directory(kind: String, app: tauri::AppHandle) -> Result<String, LauncherError> {
+    let dir = match kind.as_str() {
+        "data" | "instances" => paths::app_data_dir(),
+        "logs" => paths::logs_dir(),
+        // Настройки tauri-plugin-store лежат в config-директории приложения
+        "config" => app.path().app_config_dir().ok(),
+        _ => return Err(LauncherError::invalid_params(format!(
+            "Unknown directory kind: {}. Expected data, logs, instances or config.",
+            kind
+        ))),
     }
+    .ok_or_else(|| format!("Failed to determine {} directory", kind))?;
 
-    let response = request.send()
-        .await
-        .map_err(|e| format!("Failed to initiate download: {}", e))?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| LauncherError::io("Failed to create directory", e))?;
+    open::that(&dir)
+        .map_err(|e| LauncherError::io(&format!("Failed to open directory {}", dir.display()), e))?;
 
-    if !response.status().is_success() {
-        return Err(format!("Download failed with status: {}", response.status()));
-    }
+    Ok(dir.to_string_lossy().to_string())
+}
 
-    let _total_bytes = response.content_length().unwrap_or(0);
+/// Schemes `open_url` hands to the system; `file:`, `javascript:` and the like are refused
+const ALLOWED_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
 
-    if let Some(parent) = Path::new(&dest_path).parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create destination directory: {}", e))?;
+/// Open a web or mailto link in the default handler
+#[tauri::command]
+async fn open_url(url: String) -> Result<(), LauncherError> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| LauncherError::invalid_params(format!("Invalid URL {}: {}", url, e)))?;
+    if !ALLOWED_URL_SCHEMES.contains(&parsed.scheme()) {
+        return Err(LauncherError::invalid_params(format!(
+            "Refusing to open URL with scheme '{}'. Only http, https and mailto are allowed.",
+            parsed.scheme()
+        )));
     }
 
-    let mut file = File::create(&dest_path)
-        .map_err(|e| format!("Failed to create destination file: {}", e))?;
+    open::that(parsed.as_str())
+        .map_err(|e| LauncherError::io(&format!("Failed to open {}", parsed), e))
+}
 
-    use std::io::Write;
-    let body = response.bytes().await.map_err(|e| format!("Failed to get response body: {}", e))?;
+/// Open an existing local directory in the file manager
+///
+/// Files are only revealed in their folder, never opened, so the webview can't start
+/// executables or scripts through this command.
+#[tauri::command]
+async fn open_path(path: String) -> Result<(), LauncherError> {
+    let path = fs::canonicalize(&path)
+        .map_err(|_| LauncherError::not_found(format!("Path not found: {}", path)))?;
+    if !path.is_dir() {
+        return reveal_in_explorer(path.to_string_lossy().to_string()).await;
+    }
 
-    // Write the entire body at once
-    file.write_all(&body)
-        .map_err(|e| format!("Failed to write downloaded content: {}", e))?;
+    open::that(&path)
+        .map_err(|e| LauncherError::io(&format!("Failed to open {}", path.display()), e))
+}
 
+/// Show a file or directory in the system file manager
+///
+/// Windows and macOS select the item in its folder; on Linux the containing folder
+/// is opened with `xdg-open`, since there is no portable way to select a file.
+#[tauri::command]
+async fn reveal_in_explorer(path: String) -> Result<(), LauncherError> {
+    let path = fs::canonicalize(&path)
+        .map_err(|_| LauncherError::not_found(format!("Path not found: {}", path)))?;
+
+    let mut command = reveal_command(&path);
+
+    // explorer возвращает ненулевой код даже при успехе, поэтому статус не проверяем
+    let mut child = command.spawn().map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            LauncherError::not_found(format!(
+                "No file manager available to show {} ({:?} not found)",
+                path.display(),
+                command.get_program()
+            ))
+        } else {
+            LauncherError::io(&format!("Failed to show {} in the file manager", path.display()), e)
+        }
+    })?;
+    tokio::task::spawn_blocking(move || child.wait());
     Ok(())
 }
 
+/// File manager command that shows the canonical `path`, see `reveal_in_explorer`
+fn reveal_command(path: &Path) -> std::process::Command {
+    #[cfg(target_os = "windows")]
+    {
+        let mut command = std::process::Command::new("explorer");
+        // explorer ожидает "/select,<путь>" одним аргументом; путь без префикса \\?\
+        let display = path.to_string_lossy();
+        command.arg(format!("/select,{}", display.trim_start_matches(r"\\?\")));
+        command
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let mut command = std::process::Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let folder = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+        let mut command = std::process::Command::new("xdg-open");
+        command.arg(folder);
+        command
+    }
+}
+
 // ===== WINDOW MANAGEMENT COMMANDS =====
 
+fn main_window(app: &tauri::AppHandle) -> Result<tauri::WebviewWindow, LauncherError> {
+    app.get_webview_window("main")
+        .ok_or_else(|| "Window not found".to_string().into())
+}
+
 #[tauri::command]
-async fn window_minimize(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        window.minimize().map_err(|e| e.to_string())
-    } else {
-        Err("Window not found".to_string())
-    }
+async fn window_minimize(app: tauri::AppHandle) -> Result<(), LauncherError> {
+    Ok(main_window(&app)?.minimize()?)
 }
 
 #[tauri::command]
-async fn window_maximize(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        window.maximize().map_err(|e| e.to_string())
-    } else {
-        Err("Window not found".to_string())
-    }
+async fn window_maximize(app: tauri::AppHandle) -> Result<(), LauncherError> {
+    Ok(main_window(&app)?.maximize()?)
 }
 
 #[tauri::command]
-async fn window_toggle_maximize(app: tauri::AppHandle) -> Result<bool, String> {
-    if let Some(window) = app.get_webview_window("main") {
-        let is_maximized = window.is_maximized().map_err(|e| e.to_string())?;
-        if is_maximized {
-            window.unmaximize().map_err(|e| e.to_string())?;
-        } else {
-            window.maximize().map_err(|e| e.to_string())?;
-        }
-        Ok(!is_maximized)
+async fn window_toggle_maximize(app: tauri::AppHandle) -> Result<bool, LauncherError> {
+    let window = main_window(&app)?;
+    let is_maximized = window.is_maximized()?;
+    if is_maximized {
+        window.unmaximize()?;
     } else {
-        Err("Window not found".to_string())
+        window.maximize()?;
     }
+    Ok(!is_maximized)
 }
 
 #[tauri::command]
-async fn window_close(app: tauri::AppHandle) -> Result<(), String> {
+async fn window_close(app: tauri::AppHandle) -> Result<(), LauncherError> {
     #[cfg(not(debug_assertions))]
     {
         app.exit(0);
@@ -209,36 +404,152 @@ async fn window_close(app: tauri::AppHandle) -> Result<(), String> {
     #[cfg(debug_assertions)]
     {
         if let Some(window) = app.get_webview_window("main") {
-            window.close().map_err(|e| e.to_string())?;
+            window.close()?;
         }
     }
     Ok(())
 }
 
 #[tauri::command]
-async fn window_hide(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        window.hide().map_err(|e| e.to_string())
+async fn window_hide(app: tauri::AppHandle) -> Result<(), LauncherError> {
+    Ok(main_window(&app)?.hide()?)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+struct WindowState {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    maximized: bool,
+}
+
+fn window_state_path() -> Result<std::path::PathBuf, String> {
+    paths::app_data_dir()
+        .map(|dir| dir.join("window-state.json"))
+        .ok_or_else(|| "Failed to determine app data directory".to_string())
+}
+
+fn load_window_state() -> Option<WindowState> {
+    let path = window_state_path().ok()?;
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_main_window_state(window: &tauri::WebviewWindow) -> Result<(), String> {
+    let maximized = window.is_maximized().map_err(|e| e.to_string())?;
+
+    // A maximized window reports the screen geometry, so keep the last normal one
+    let state = if maximized {
+        WindowState { maximized: true, ..load_window_state().unwrap_or_default() }
     } else {
-        Err("Window not found".to_string())
+        let position = window.outer_position().map_err(|e| e.to_string())?;
+        let size = window.outer_size().map_err(|e| e.to_string())?;
+        WindowState {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+            maximized: false,
+        }
+    };
+
+    let path = window_state_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
     }
+
+    let json = serde_json::to_string_pretty(&state)
+        .map_err(|e| format!("Failed to serialize window state: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write window state: {}", e))
 }
 
-#[tauri::command]
-async fn open_devtools(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        window.open_devtools();
-        Ok(())
-    } else {
-        Err("Window not found".to_string())
+fn restore_main_window_state(window: &tauri::WebviewWindow) -> Result<bool, String> {
+    let state = match load_window_state() {
+        Some(state) => state,
+        None => return Ok(false),
+    };
+
+    if state.width > 0 && state.height > 0 {
+        window.set_size(tauri::PhysicalSize::new(state.width, state.height))
+            .map_err(|e| e.to_string())?;
+        window.set_position(tauri::PhysicalPosition::new(state.x, state.y))
+            .map_err(|e| e.to_string())?;
+    }
+
+    if state.maximized {
+        window.maximize().map_err(|e| e.to_string())?;
     }
+
+    Ok(true)
+}
+
+#[tauri::command]
+async fn save_window_state(app: tauri::AppHandle) -> Result<(), LauncherError> {
+    Ok(save_main_window_state(&main_window(&app)?)?)
+}
+
+#[tauri::command]
+async fn restore_window_state(app: tauri::AppHandle) -> Result<bool, LauncherError> {
+    Ok(restore_main_window_state(&main_window(&app)?)?)
+}
+
+#[tauri::command]
+async fn open_devtools(app: tauri::AppHandle) -> Result<(), LauncherError> {
+    main_window(&app)?.open_devtools();
+    Ok(())
+}
+
+// ===== LIFECYCLE COMMANDS =====
+
+/// Delay before restarting so the command's response reaches the frontend
+const RESTART_DELAY_MS: u64 = 500;
+
+#[tauri::command]
+async fn restart_launcher(app: tauri::AppHandle) -> Result<(), LauncherError> {
+    // app.restart() передал бы те же аргументы, и перезапущенный лаунчер снова запустил бы
+    // профиль из --launch-profile / alauncher://, поэтому запускаем себя сами без них.
+    // Запущенные игры - отдельные процессы, они продолжают работать после перезапуска.
+    let env = app.env();
+    let binary = tauri::process::current_binary(&env)
+        .map_err(|e| LauncherError::io("Failed to locate launcher executable", e))?;
+    let args = deep_link::without_launch_args(env.args_os.get(1..).unwrap_or_default());
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(RESTART_DELAY_MS)).await;
+        eprintln!("ALauncher restarting...");
+        // Сначала освобождаем single-instance (cleanup плагинов), иначе новый процесс
+        // передаст аргументы этому и сразу завершится
+        app.cleanup_before_exit();
+        match std::process::Command::new(&binary).args(&args).spawn() {
+            Ok(_) => std::process::exit(0),
+            // Лаунчер остаётся открытым, перезапустить можно вручную
+            Err(e) => eprintln!("❌ Failed to restart launcher: {}", e),
+        }
+    });
+
+    Ok(())
 }
 
 // ===== MAIN APPLICATION =====
 
 pub fn run() {
+    logger::init_logger();
+
+    // Профиль для автозапуска из ярлыка или ссылки: --launch-profile <id> / alauncher://launch/<id>
+    let args: Vec<String> = std::env::args().collect();
+    let launch_profile = deep_link::parse_launch_profile_arg(&args);
+
     let result = std::panic::catch_unwind(|| {
         tauri::Builder::default()
+            // Single instance: a second launch forwards its arguments to the running launcher
+            .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+                deep_link::handle_launch_request(app, &argv);
+            }))
+            // alauncher://launch/<profile_id> links
+            .plugin(tauri_plugin_deep_link::init())
             // Updater plugin for automatic updates
             .plugin(tauri_plugin_updater::Builder::new().build())
             // Store plugin for persistent data storage
@@ -248,6 +559,9 @@ pub fn run() {
             // .plugin(tauri_plugin_devtools::init())
             // .plugin(tauri_plugin_shell::init())
             // .plugin(tauri_plugin_dialog::init())
+            .manage(deep_link::PendingLaunch::new(launch_profile))
+            .manage(game_launcher::ProcessRegistry::restore())
+            .manage(cancellation::CancellationRegistry::default())
             .invoke_handler(tauri::generate_handler![
                 // File operations
                 get_app_version,
@@ -256,25 +570,127 @@ pub fn run() {
                 file_exists,
                 read_file,
                 write_file,
+                read_file_bytes,
+                write_file_bytes,
+                delete_file,
+                file_transfer::copy_path,
+                file_transfer::move_path,
+                truncate_file_to_tail,
                 calculate_file_hash,
+                hashing::verify_manifest,
+                cancellation::cancel_operation,
+                hashing::verify_file_hash,
+                archive::extract_zip_filtered,
+                archive::extract_archive,
                 get_file_info,
+                list_directory,
                 ensure_dir,
                 get_updates_dir,
-                download_file,
-                find_java_installations,
+                open_app_directory,
+                open_url,
+                open_path,
+                reveal_in_explorer,
+                download::download_file,
+                java_locator::detect_java,
+                java_locator::check_java_requirement,
                 // Window management
                 window_minimize,
                 window_maximize,
                 window_toggle_maximize,
                 window_close,
                 window_hide,
+                save_window_state,
+                restore_window_state,
                 open_devtools,
+                // Lifecycle
+                restart_launcher,
+                diagnostics::get_startup_diagnostics,
+                logger::get_recent_logs,
+                logger::get_log_file_path_command,
                 // Game launcher
                 game_launcher::launch_game_client,
                 game_launcher::check_game_process,
-                game_launcher::kill_game_process
+                game_launcher::kill_game_process,
+                game_launcher::kill_all_processes,
+                game_launcher::set_kill_games_on_exit,
+                game_launcher::list_game_processes,
+                game_launcher::get_hs_err_report,
+                game_launcher::path_separator,
+                game_launcher::join_classpath,
+                game_launcher::resolve_client_args,
+                log4j::read_structured_log,
+                // Instance inspection
+                loader_detector::detect_loader,
+                client_jar::detect_jar_version,
+                servers_dat::read_servers_dat,
+                servers_dat::write_servers_dat,
+                mods::find_duplicate_mods,
+                mods::merge_mods,
+                mods::list_mods,
+                instance_icon::resolve_instance_icon,
+                screenshots::export_screenshots,
+                permissions::check_instance_permissions,
+                version_json::parse_version_json,
+                version_json::resolve_version,
+                launch_builder::build_version_launch_params,
+                version_manifest::check_version_update,
+                version_manifest::list_minecraft_versions,
+                version_manifest::fetch_version_list,
+                version_manifest::fetch_version_json,
+                download::download_and_extract,
+                assets::download_assets,
+                libraries::download_libraries,
+                gpu::detect_gpus,
+                system_info::get_memory_info,
+                system_info::suggest_ram_mb,
+                system_info::get_cpu_count,
+                process_tuning::get_optimized_jvm_args,
+                java_install::install_java_archive,
+                // Shortcuts and launch requests
+                shortcuts::create_instance_shortcut,
+                deep_link::take_launch_profile,
+                // Accounts
+                accounts::load_accounts,
+                accounts::save_accounts,
+                accounts::list_accounts_with_status,
+                accounts::set_active_account,
+                accounts::generate_offline_profile,
+                microsoft_login::ms_begin_device_login,
+                microsoft_login::ms_poll_device_login,
+                microsoft_login::ms_refresh_token,
+                // Network
+                network::check_tcp_reachable,
+                server_ping::ping_server
             ])
             .setup(|app| {
+                let mut startup = diagnostics::StartupDiagnostics::collect();
+                if startup.data_dir.is_none() {
+                    startup.warn("Failed to determine app data directory");
+                }
+                if !startup.logger_initialized {
+                    startup.warn("File logger is not initialized, logs go to stderr only");
+                }
+
+                // Игры, запущенные до перезапуска лаунчера
+                game_launcher::watch_restored_processes(app.handle());
+                game_launcher::spawn_process_reaper(app.handle().clone());
+
+                {
+                    use tauri_plugin_deep_link::DeepLinkExt;
+
+                    // На Linux/Windows схема регистрируется при запуске (в сборке - установщиком)
+                    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+                    if let Err(e) = app.deep_link().register_all() {
+                        startup.warn(format!("Failed to register URL scheme: {}", e));
+                    }
+
+                    let handle = app.handle().clone();
+                    app.deep_link().on_open_url(move |event| {
+                        let urls: Vec<String> = event.urls().iter().map(|u| u.to_string()).collect();
+                        deep_link::handle_urls(&handle, &urls);
+                    });
+                }
+
                 // Создаем системный трей (только в продакшн)
                 #[cfg(not(debug_assertions))]
                 {
@@ -316,24 +732,38 @@ pub fn run() {
                 }
 
                 if let Some(window) = app.get_webview_window("main") {
-                    #[cfg(not(debug_assertions))]
+                    if let Err(e) = restore_main_window_state(&window) {
+                        startup.warn(format!("Failed to restore window state: {}", e));
+                    }
+
                     let window_clone = window.clone();
                     let _ = window.on_window_event(move |event| {
                         #[cfg(not(debug_assertions))]
                         if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                            let _ = save_main_window_state(&window_clone);
                             api.prevent_close();
                             let _ = window_clone.hide();
                         }
                         #[cfg(debug_assertions)]
                         if let tauri::WindowEvent::CloseRequested { .. } = event {
                             // В debug режиме просто закрываем
+                            let _ = save_main_window_state(&window_clone);
                         }
                     });
                     let _ = window.show();
+                } else {
+                    startup.warn("Main window not found");
                 }
+
+                app.manage(diagnostics::DiagnosticsState(std::sync::Mutex::new(startup)));
                 Ok(())
             })
-            .run(tauri::generate_context!())
+            .build(tauri::generate_context!())
+            .map(|app| app.run(|app_handle, event| {
+                if let tauri::RunEvent::ExitRequested { .. } = event {
+                    game_launcher::handle_launcher_exit(app_handle);
+                }
+            }))
     });
 
     #[cfg(debug_assertions)]
@@ -354,3 +784,190 @@ pub fn run() {
     #[cfg(not(debug_assertions))]
     let _ = result;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn open_url_refuses_non_web_schemes() {
+        for url in ["file:///etc/passwd", "javascript:alert(1)", "C:\\Windows\\System32\\calc.exe", "not a url"] {
+            let error = open_url(url.to_string()).await.unwrap_err();
+            assert!(matches!(error, LauncherError::InvalidParams { .. }), "{}: {:?}", url, error);
+        }
+    }
+
+    #[tokio::test]
+    async fn open_path_requires_an_existing_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing").to_string_lossy().to_string();
+        assert!(matches!(open_path(missing).await, Err(LauncherError::NotFound { .. })));
+    }
+
+    #[test]
+    fn file_info_describes_files_directories_and_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("options.txt");
+        fs::write(&file, "fov:70").unwrap();
+
+        let info = file_info(&file).unwrap();
+        assert!(info.is_file && !info.is_dir && !info.is_symlink && !info.is_readonly);
+        assert_eq!(info.size, 6);
+        assert!(info.modified.is_some_and(|ms| ms > 0));
+
+        let info = file_info(dir.path()).unwrap();
+        assert!(info.is_dir && !info.is_file);
+
+        let mut permissions = fs::metadata(&file).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&file, permissions).unwrap();
+        assert!(file_info(&file).unwrap().is_readonly);
+
+        // Время до 1970 не роняет команду, а превращается в None
+        let old = dir.path().join("old.txt");
+        let before_epoch = std::time::UNIX_EPOCH - std::time::Duration::from_secs(3600);
+        if fs::File::create(&old).unwrap().set_modified(before_epoch).is_ok() {
+            assert_eq!(file_info(&old).unwrap().modified, None);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_info_reports_symlinks_and_dangling_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target.txt");
+        fs::write(&target, "data").unwrap();
+        std::os::unix::fs::symlink(&target, dir.path().join("link")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("missing"), dir.path().join("dangling")).unwrap();
+
+        let link = file_info(&dir.path().join("link")).unwrap();
+        assert!(link.is_symlink && link.is_file);
+        assert_eq!(link.size, 4);
+
+        let dangling = file_info(&dir.path().join("dangling")).unwrap();
+        assert!(dangling.is_symlink && !dangling.is_file && !dangling.is_dir);
+    }
+
+    #[tokio::test]
+    async fn list_directory_lists_children_directories_first() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("mods").join("config")).unwrap();
+        fs::write(dir.path().join("options.txt"), "").unwrap();
+        fs::write(dir.path().join("mods").join("sodium.jar"), "").unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+
+        let names = |entries: Vec<FileInfo>| -> Vec<String> {
+            entries.into_iter()
+                .map(|info| Path::new(&info.path).strip_prefix(dir.path()).unwrap().to_string_lossy().replace('\\', "/"))
+                .collect()
+        };
+        assert_eq!(names(list_directory(root.clone(), false).await.unwrap()), ["mods", "options.txt"]);
+        assert_eq!(
+            names(list_directory(root, true).await.unwrap()),
+            ["mods", "mods/config", "mods/sodium.jar", "options.txt"]
+        );
+
+        let missing = dir.path().join("missing").to_string_lossy().to_string();
+        assert!(matches!(list_directory(missing, false).await, Err(LauncherError::NotFound { .. })));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn recursive_listing_skips_symlink_loops() {
+        let dir = tempfile::tempdir().unwrap();
+        let saves = dir.path().join("saves");
+        fs::create_dir(&saves).unwrap();
+        std::os::unix::fs::symlink(dir.path(), saves.join("back-to-root")).unwrap();
+
+        let entries = list_directory(dir.path().to_string_lossy().to_string(), true).await.unwrap();
+        let paths: Vec<&str> = entries.iter().map(|info| info.path.as_str()).collect();
+        assert_eq!(paths, [saves.to_string_lossy()]);
+    }
+
+    #[tokio::test]
+    async fn binary_files_round_trip_through_base64() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("level.dat").to_string_lossy().to_string();
+        // Не UTF-8: read_file на таком файле падает
+        let bytes: Vec<u8> = vec![0x1f, 0x8b, 0x08, 0x00, 0xff, 0xfe, 0x00, 0x80];
+
+        write_file_bytes(path.clone(), "H4sIAP/+AIA=\n".to_string()).await.unwrap();
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+        assert_eq!(read_file_bytes(path.clone()).await.unwrap(), "H4sIAP/+AIA=");
+        assert!(read_file(path.clone()).await.is_err());
+
+        let invalid = write_file_bytes(path.clone(), "not base64!".to_string()).await;
+        assert!(matches!(invalid, Err(LauncherError::InvalidParams { .. })));
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+    }
+
+    #[tokio::test]
+    async fn delete_file_removes_directories_only_when_asked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = |name: &str| dir.path().join(name).to_string_lossy().to_string();
+        fs::write(path("crash.txt"), "").unwrap();
+        fs::create_dir(path("empty")).unwrap();
+        fs::create_dir_all(path("mods/disabled")).unwrap();
+        fs::write(path("mods/disabled/old.jar"), "").unwrap();
+
+        delete_file(path("crash.txt"), false).await.unwrap();
+        assert!(!Path::new(&path("crash.txt")).exists());
+
+        delete_file(path("empty"), false).await.unwrap();
+        assert!(!Path::new(&path("empty")).exists());
+
+        let not_empty = delete_file(path("mods"), false).await;
+        assert!(matches!(not_empty, Err(LauncherError::InvalidParams { .. })));
+        assert!(Path::new(&path("mods/disabled/old.jar")).exists());
+
+        delete_file(path("mods"), true).await.unwrap();
+        assert!(!Path::new(&path("mods")).exists());
+
+        assert!(matches!(delete_file(path("missing"), true).await, Err(LauncherError::NotFound { .. })));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn delete_file_removes_symlink_not_its_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let instance = dir.path().join("instance");
+        fs::create_dir(&instance).unwrap();
+        fs::write(instance.join("options.txt"), "").unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&instance, &link).unwrap();
+
+        delete_file(link.to_string_lossy().to_string(), true).await.unwrap();
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert!(instance.join("options.txt").exists());
+    }
+
+    #[test]
+    fn reveal_command_targets_the_item_or_its_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        let screenshot = root.join("screenshot.png");
+        fs::write(&screenshot, "").unwrap();
+
+        let command = reveal_command(&screenshot);
+        let args: Vec<String> = command.get_args().map(|arg| arg.to_string_lossy().to_string()).collect();
+        if cfg!(target_os = "windows") {
+            assert_eq!(command.get_program(), "explorer");
+            assert_eq!(args, [format!("/select,{}", screenshot.to_string_lossy().trim_start_matches(r"\\?\"))]);
+        } else if cfg!(target_os = "macos") {
+            assert_eq!(command.get_program(), "open");
+            assert_eq!(args, ["-R".to_string(), screenshot.to_string_lossy().to_string()]);
+        } else {
+            assert_eq!(command.get_program(), "xdg-open");
+            assert_eq!(args, [root.to_string_lossy().to_string()]);
+            let args: Vec<_> = reveal_command(&root).get_args().map(|arg| arg.to_os_string()).collect();
+            assert_eq!(args, [root.clone().into_os_string()]);
+        }
+    }
+
+    #[tokio::test]
+    async fn reveal_in_explorer_requires_an_existing_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing").to_string_lossy().to_string();
+        assert!(matches!(reveal_in_explorer(missing).await, Err(LauncherError::NotFound { .. })));
+    }
+}
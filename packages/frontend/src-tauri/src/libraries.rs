@@ -0,0 +1,246 @@
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+use crate::download::{self, BatchProgress, RetryPolicy};
+use crate::error::LauncherError;
+use crate::hashing;
+use crate::version_json::{self, Artifact, Features, Library, OsInfo, VersionJson};
+
+/// Repository of libraries that name no `url` of their own
+const MOJANG_LIBRARIES_URL: &str = "https://libraries.minecraft.net/";
+
+const LIBRARY_CONCURRENCY: usize = 8;
+
+/// Local library jars of a version
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DownloadedLibraries {
+    /// Jars for the classpath, in version json order
+    pub class_path: Vec<String>,
+    /// Natives jars to unpack into the natives directory (see `extract_archive`)
+    pub natives: Vec<String>,
+}
+
+/// One jar to fetch
+struct LibraryFile {
+    /// Empty for jars produced by a loader installer instead of downloaded
+    url: String,
+    path: PathBuf,
+    sha1: Option<String>,
+    size: Option<u64>,
+    native: bool,
+}
+
+/// Download the libraries of a version into `libraries_dir`
+///
+/// Libraries and natives excluded by their OS rules are skipped, as are jars already
+/// present with the right SHA-1 (or, without a known hash, already present at all).
+/// Libraries without `downloads` are fetched from their Maven repository by coordinate.
+/// Network errors are retried per `retry`. Aggregate progress is emitted as
+/// `download-progress` under `download_id`.
+#[tauri::command]
+pub async fn download_libraries(
+    version: VersionJson,
+    libraries_dir: String,
+    download_id: String,
+    retry: Option<RetryPolicy>,
+    app: tauri::AppHandle,
+) -> Result<DownloadedLibraries, LauncherError> {
+    let libraries_dir = PathBuf::from(&libraries_dir);
+    let files = library_files(&version, &libraries_dir);
+
+    let mut result = DownloadedLibraries::default();
+    let mut seen = HashSet::new();
+    for file in &files {
+        if !seen.insert(file.path.clone()) {
+            continue;
+        }
+        let path = file.path.to_string_lossy().to_string();
+        if file.native {
+            result.natives.push(path);
+        } else {
+            result.class_path.push(path);
+        }
+    }
+
+    let missing = tokio::task::spawn_blocking(move || {
+        let mut seen = HashSet::new();
+        files.into_iter()
+            .filter(|file| seen.insert(file.path.clone()))
+            .filter(|file| !library_file_present(file))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Library check task failed: {}", e))?;
+
+    // Такие jar создает установщик загрузчика (Forge), скачать их неоткуда
+    let (missing, not_downloadable): (Vec<_>, Vec<_>) = missing.into_iter().partition(|file| !file.url.is_empty());
+    for file in &not_downloadable {
+        eprintln!("[Libraries] ⚠️ {} is missing and has no download URL", file.path.display());
+    }
+
+    let bytes: u64 = missing.iter().filter_map(|file| file.size).sum();
+    eprintln!(
+        "[Libraries] {} libraries for {}, {} to download",
+        result.class_path.len() + result.natives.len(),
+        version.id,
+        missing.len()
+    );
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(120))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let progress = BatchProgress::new(&app, download_id, bytes);
+    let policy = retry.unwrap_or_default();
+
+    let results = stream::iter(&missing)
+        .map(|file| {
+            let (client, app, progress) = (&client, &app, &progress);
+            async move {
+                download::download_verified(client, &file.url, &file.path, file.sha1.as_deref(), policy, app, progress).await
+            }
+        })
+        .buffer_unordered(LIBRARY_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+    progress.emit();
+
+    let errors: Vec<String> = results.into_iter().filter_map(Result::err).collect();
+    if let Some(first) = errors.first() {
+        eprintln!("[Libraries] ❌ {} libraries failed to download", errors.len());
+        return Err(LauncherError::network(format!(
+            "{} of {} libraries failed to download: {}",
+            errors.len(),
+            missing.len(),
+            first
+        )));
+    }
+
+    eprintln!("[Libraries] ✅ Libraries for {} are ready", version.id);
+    Ok(result)
+}
+
+/// Artifacts and natives allowed on this system
+fn library_files(version: &VersionJson, libraries_dir: &Path) -> Vec<LibraryFile> {
+    let (os, features) = (OsInfo::current(), Features::default());
+    let mut files = Vec::new();
+
+    for library in &version.libraries {
+        if !version_json::rule_allows(&library.rules, &os, &features) {
+            continue;
+        }
+
+        let downloads = library.downloads.as_ref();
+        match downloads.and_then(|d| d.artifact.as_ref()) {
+            Some(artifact) => {
+                if let Some(file) = artifact_file(library, artifact, None, libraries_dir, false) {
+                    files.push(file);
+                }
+            }
+            // Без downloads - библиотека загрузчика из Maven-репозитория
+            None if downloads.is_none() && library.natives.is_none() => {
+                if let Some(file) = maven_file(library, None, libraries_dir, false) {
+                    files.push(file);
+                }
+            }
+            None => {}
+        }
+
+        if let Some(classifier) = library.native_classifier(&os) {
+            let native = match downloads.and_then(|d| d.classifiers.as_ref()) {
+                Some(classifiers) => classifiers.get(&classifier)
+                    .and_then(|artifact| artifact_file(library, artifact, Some(&classifier), libraries_dir, true)),
+                None => maven_file(library, Some(&classifier), libraries_dir, true),
+            };
+            files.extend(native);
+        }
+    }
+
+    files
+}
+
+fn artifact_file(
+    library: &Library,
+    artifact: &Artifact,
+    classifier: Option<&str>,
+    libraries_dir: &Path,
+    native: bool,
+) -> Option<LibraryFile> {
+    let path = artifact.path.clone().or_else(|| version_json::maven_path(&library.name, classifier))?;
+    Some(LibraryFile {
+        url: artifact.url.clone(),
+        path: library_path(libraries_dir, &path)?,
+        sha1: artifact.sha1.clone(),
+        size: (artifact.size > 0).then_some(artifact.size),
+        native,
+    })
+}
+
+fn maven_file(library: &Library, classifier: Option<&str>, libraries_dir: &Path, native: bool) -> Option<LibraryFile> {
+    let path = version_json::maven_path(&library.name, classifier)?;
+    let repository = library.url.as_deref().unwrap_or(MOJANG_LIBRARIES_URL);
+    Some(LibraryFile {
+        url: format!("{}/{}", repository.trim_end_matches('/'), path),
+        path: library_path(libraries_dir, &path)?,
+        sha1: None,
+        size: None,
+        native,
+    })
+}
+
+/// `relative` under `libraries_dir`, or None when the version json names an absolute
+/// path or climbs out with `..`
+fn library_path(libraries_dir: &Path, relative: &str) -> Option<PathBuf> {
+    let relative_path = Path::new(relative);
+    let inside = relative_path.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !inside {
+        eprintln!("[Libraries] ⚠️ Skipping library outside the libraries directory: {}", relative);
+        return None;
+    }
+    Some(libraries_dir.join(relative_path))
+}
+
+fn library_file_present(file: &LibraryFile) -> bool {
+    match &file.sha1 {
+        Some(sha1) => hashing::file_matches_sha1(&file.path, sha1, file.size),
+        None => file.path.is_file(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn library_paths_stay_inside_libraries_dir() {
+        let dir = Path::new("libraries");
+        assert_eq!(
+            library_path(dir, "org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3.jar"),
+            Some(dir.join("org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3.jar"))
+        );
+        assert_eq!(library_path(dir, "../../.bashrc"), None);
+        assert_eq!(library_path(dir, "org/../../outside.jar"), None);
+        assert_eq!(library_path(dir, "/etc/cron.d/evil.jar"), None);
+        #[cfg(windows)]
+        assert_eq!(library_path(dir, "C:\\Windows\\evil.jar"), None);
+    }
+
+    #[test]
+    fn traversing_libraries_are_skipped() {
+        let version: VersionJson = serde_json::from_str(r#"{
+            "id": "evil",
+            "libraries": [
+                {"name": "com.example:good:1.0", "downloads": {"artifact": {"path": "com/example/good/1.0/good-1.0.jar", "url": "https://example.com/good.jar"}}},
+                {"name": "com.example:artifact:1.0", "downloads": {"artifact": {"path": "../../evil.jar", "url": "https://example.com/evil.jar"}}},
+                {"name": "com.example:maven:../../..", "url": "https://example.com/maven/"},
+                {"name": "com.example:absolute:1.0", "downloads": {"artifact": {"path": "/tmp/evil.jar", "url": "https://example.com/evil.jar"}}}
+            ]
+        }"#).unwrap();
+
+        let dir = Path::new("libraries");
+        let paths: Vec<PathBuf> = library_files(&version, dir).into_iter().map(|file| file.path).collect();
+        assert_eq!(paths, vec![dir.join("com/example/good/1.0/good-1.0.jar")]);
+    }
+}
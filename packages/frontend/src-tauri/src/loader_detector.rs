@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+use serde_json::Value;
+use crate::error::LauncherError;
+
+/// Mod loader installed in an instance
+#[derive(Debug, Clone, Serialize)]
+pub struct LoaderInfo {
+    /// One of "vanilla", "fabric", "forge", "quilt", "neoforge"
+    pub loader: String,
+    pub loader_version: Option<String>,
+    pub minecraft_version: Option<String>,
+}
+
+impl LoaderInfo {
+    fn vanilla(minecraft_version: Option<String>) -> Self {
+        LoaderInfo {
+            loader: "vanilla".to_string(),
+            loader_version: None,
+            minecraft_version,
+        }
+    }
+}
+
+/// Detect which mod loader (if any) an instance uses
+#[tauri::command]
+pub async fn detect_loader(game_dir: String, version_dir: String) -> Result<LoaderInfo, LauncherError> {
+    let game_path = Path::new(&game_dir);
+    let version_path = Path::new(&version_dir);
+
+    if !game_path.is_dir() {
+        return Err(LauncherError::not_found(format!("Game directory does not exist: {}", game_dir)));
+    }
+
+    // The version json is the most reliable source: it lists the loader libraries
+    let mut minecraft_version = None;
+    for json in read_version_jsons(version_path)? {
+        if let Some(info) = detect_from_version_json(&json) {
+            return Ok(info);
+        }
+        if minecraft_version.is_none() {
+            minecraft_version = json.get("id").and_then(Value::as_str).map(str::to_string);
+        }
+    }
+
+    // Fall back to the marker directories loaders leave in the game dir
+    if let Some(loader) = detect_from_markers(game_path) {
+        return Ok(LoaderInfo {
+            loader: loader.to_string(),
+            loader_version: None,
+            minecraft_version,
+        });
+    }
+
+    Ok(LoaderInfo::vanilla(minecraft_version))
+}
+
+fn read_version_jsons(version_dir: &Path) -> Result<Vec<Value>, String> {
+    let mut jsons = Vec::new();
+
+    if !version_dir.is_dir() {
+        return Ok(jsons);
+    }
+
+    let entries = fs::read_dir(version_dir)
+        .map_err(|e| format!("Failed to read version directory: {}", e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("[Loader Detector] ⚠️ Failed to read {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        match serde_json::from_str::<Value>(&content) {
+            Ok(json) => jsons.push(json),
+            Err(e) => eprintln!("[Loader Detector] ⚠️ Failed to parse {}: {}", path.display(), e),
+        }
+    }
+
+    Ok(jsons)
+}
+
+fn detect_from_version_json(json: &Value) -> Option<LoaderInfo> {
+    let minecraft_version = json.get("inheritsFrom")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    let libraries = json.get("libraries").and_then(Value::as_array)?;
+
+    for library in libraries {
+        let name = match library.get("name").and_then(Value::as_str) {
+            Some(name) => name,
+            None => continue,
+        };
+
+        let mut parts = name.split(':');
+        let (group, artifact, version) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(g), Some(a), Some(v)) => (g, a, v),
+            _ => continue,
+        };
+
+        let loader = match (group, artifact) {
+            ("net.fabricmc", "fabric-loader") => "fabric",
+            ("org.quiltmc", "quilt-loader") => "quilt",
+            ("net.neoforged", "neoforge") | ("net.neoforged.fancymodloader", "loader") => "neoforge",
+            ("net.minecraftforge", "forge") | ("net.minecraftforge", "fmlloader") => "forge",
+            _ => continue,
+        };
+
+        // Forge versions are published as "<mc>-<forge>", e.g. "1.20.1-47.2.0"
+        let (mc_from_coordinate, loader_version) = if loader == "forge" {
+            match version.split_once('-') {
+                Some((mc, forge)) => (Some(mc.to_string()), forge.to_string()),
+                None => (None, version.to_string()),
+            }
+        } else {
+            (None, version.to_string())
+        };
+
+        return Some(LoaderInfo {
+            loader: loader.to_string(),
+            loader_version: Some(loader_version),
+            minecraft_version: minecraft_version.or(mc_from_coordinate),
+        });
+    }
+
+    None
+}
+
+fn detect_from_markers(game_dir: &Path) -> Option<&'static str> {
+    if game_dir.join(".quilt").is_dir() {
+        Some("quilt")
+    } else if game_dir.join(".fabric").is_dir() {
+        Some("fabric")
+    } else if game_dir.join("config").join("neoforge-common.toml").is_file() {
+        Some("neoforge")
+    } else if game_dir.join("config").join("forge-common.toml").is_file()
+        || game_dir.join("config").join("fml.toml").is_file()
+    {
+        Some("forge")
+    } else {
+        None
+    }
+}
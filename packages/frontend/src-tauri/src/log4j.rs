@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use crate::error::LauncherError;
+
+/// Launcher-provided Log4j2 config, written into the game dir at launch
+const LOG4J_CONFIG: &str = include_str!("../resources/log4j2-alauncher.xml");
+const LOG4J_CONFIG_FILE: &str = "alauncher-log4j2.xml";
+const STRUCTURED_LOG_FILE: &str = "alauncher-structured.log";
+
+/// Write the Log4j config into the game dir and return the JVM flag pointing at it
+///
+/// Returns `None` when the classpath has no Log4j (very old versions), so the
+/// launch silently proceeds with the game's own logging, and when `jvm_args` already
+/// set a config (e.g. Mojang's patched one from the version JSON), which must win.
+pub fn prepare_log4j_config(game_dir: &Path, class_path: &[String], jvm_args: &[String]) -> Option<String> {
+    if !uses_log4j(class_path) {
+        return None;
+    }
+    if sets_log4j_config(jvm_args) {
+        eprintln!("[Launcher Backend] JVM arguments already set a Log4j config, keeping it");
+        return None;
+    }
+
+    let config_path = game_dir.join(LOG4J_CONFIG_FILE);
+    if let Err(e) = fs::write(&config_path, LOG4J_CONFIG) {
+        eprintln!("[Launcher Backend] ⚠️ Failed to write Log4j config: {}", e);
+        return None;
+    }
+
+    Some(format!("-Dlog4j.configurationFile={}", config_path.to_string_lossy()))
+}
+
+fn uses_log4j(class_path: &[String]) -> bool {
+    class_path.iter().any(|entry| {
+        Path::new(entry)
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("log4j-core"))
+    })
+}
+
+fn sets_log4j_config(jvm_args: &[String]) -> bool {
+    jvm_args.iter().any(|arg| {
+        arg.starts_with("-Dlog4j.configurationFile=") || arg.starts_with("-Dlog4j2.configurationFile=")
+    })
+}
+
+fn structured_log_path(game_dir: &str) -> PathBuf {
+    Path::new(game_dir).join("logs").join(STRUCTURED_LOG_FILE)
+}
+
+/// One parsed Log4j record
+#[derive(Debug, Clone, Serialize)]
+pub struct Log4jRecord {
+    pub time: String,
+    pub level: String,
+    pub thread: String,
+    pub logger: String,
+    pub message: String,
+}
+
+fn parse_record(line: &str) -> Option<Log4jRecord> {
+    let mut parts = line.splitn(5, '|');
+    Some(Log4jRecord {
+        time: parts.next()?.to_string(),
+        level: parts.next()?.to_string(),
+        thread: parts.next()?.to_string(),
+        logger: parts.next()?.to_string(),
+        message: parts.next()?.replace("\\n", "\n"),
+    })
+}
+
+/// Read the last `max_lines` records of the structured Log4j output
+#[tauri::command]
+pub async fn read_structured_log(game_dir: String, max_lines: usize) -> Result<Vec<Log4jRecord>, LauncherError> {
+    let path = structured_log_path(&game_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read structured log: {}", e))?;
+
+    let records: Vec<Log4jRecord> = content.lines().filter_map(parse_record).collect();
+    let skip = records.len().saturating_sub(max_lines);
+    Ok(records.into_iter().skip(skip).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn every_message_pattern_disables_lookups() {
+        assert_eq!(LOG4J_CONFIG.matches("%msg").count(), LOG4J_CONFIG.matches("%msg{nolookups}").count());
+        assert!(LOG4J_CONFIG.contains("target=\"SYSTEM_OUT\""));
+        assert!(LOG4J_CONFIG.contains("<AppenderRef ref=\"SysOut\" />"));
+    }
+
+    #[test]
+    fn config_is_only_used_with_log4j_on_the_classpath() {
+        let dir = tempfile::tempdir().unwrap();
+        let old_version = strings(&["/libs/lwjgl-2.9.jar", "/versions/1.6.4.jar"]);
+        assert_eq!(prepare_log4j_config(dir.path(), &old_version, &[]), None);
+
+        let class_path = strings(&["/libs/log4j-api-2.19.0.jar", "/libs/log4j-core-2.19.0.jar"]);
+        let flag = prepare_log4j_config(dir.path(), &class_path, &[]).unwrap();
+        assert!(flag.starts_with("-Dlog4j.configurationFile="));
+        assert!(dir.path().join(LOG4J_CONFIG_FILE).is_file());
+    }
+
+    #[test]
+    fn user_log4j_config_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let class_path = strings(&["/libs/log4j-core-2.19.0.jar"]);
+        let jvm_args = strings(&["-Xss1M", "-Dlog4j.configurationFile=client-1.12.xml"]);
+
+        assert_eq!(prepare_log4j_config(dir.path(), &class_path, &jvm_args), None);
+        assert!(!dir.path().join(LOG4J_CONFIG_FILE).exists());
+    }
+
+    #[test]
+    fn parses_structured_records() {
+        let record = parse_record("2024-01-01T10:00:00.000|WARN|Render thread|net.minecraft.Foo|a|b\\nc").unwrap();
+        assert_eq!(record.level, "WARN");
+        assert_eq!(record.thread, "Render thread");
+        assert_eq!(record.logger, "net.minecraft.Foo");
+        assert_eq!(record.message, "a|b\nc");
+        assert!(parse_record("garbage").is_none());
+    }
+}
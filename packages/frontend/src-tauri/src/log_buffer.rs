@@ -0,0 +1,83 @@
+use std::collections::VecDeque;
+
+/// Default cap for the captured stdout/stderr of a single stream
+pub const DEFAULT_OUTPUT_BUFFER_BYTES: usize = 1024 * 1024;
+
+/// Keeps only the most recent `capacity` bytes of a process output stream
+///
+/// When full, the oldest bytes are dropped up to the next line break so the
+/// buffer starts with a whole line whenever one is available.
+pub struct LogBuffer {
+    data: VecDeque<u8>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        LogBuffer {
+            data: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        // Кусок больше всего буфера - из него остается только хвост
+        if bytes.len() >= self.capacity {
+            self.data.clear();
+            let tail = &bytes[bytes.len() - self.capacity..];
+            // Перевод строки в самом конце не считается: иначе не осталось бы ничего
+            let start = tail[..tail.len() - 1].iter()
+                .position(|&b| b == b'\n')
+                .map_or(0, |pos| pos + 1);
+            self.data.extend(&tail[start..]);
+            return;
+        }
+
+        let overflow = (self.data.len() + bytes.len()).saturating_sub(self.capacity);
+        if overflow > 0 {
+            let cut = self.data.range(overflow..)
+                .position(|&b| b == b'\n')
+                .map_or(overflow, |pos| overflow + pos + 1);
+            self.data.drain(..cut);
+        }
+        self.data.extend(bytes);
+    }
+
+    pub fn to_string_lossy(&self) -> String {
+        let (front, back) = self.data.as_slices();
+        let mut bytes = Vec::with_capacity(self.data.len());
+        bytes.extend_from_slice(front);
+        bytes.extend_from_slice(back);
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_recent_whole_lines_under_cap() {
+        let mut buffer = LogBuffer::new(32);
+        for i in 0..100 {
+            buffer.push(format!("line {:03}\n", i).as_bytes());
+            assert!(buffer.data.len() <= 32);
+        }
+        let text = buffer.to_string_lossy();
+        assert!(text.starts_with("line "));
+        assert!(text.ends_with("line 099\n"));
+        assert!(!text.contains("line 000"));
+    }
+
+    #[test]
+    fn oversized_chunk_keeps_its_tail() {
+        let mut buffer = LogBuffer::new(16);
+        buffer.push(b"old\n");
+        buffer.push(b"aaaaaaaaaaaa\nbbbbbbbbbbbb\ncc\n");
+        assert_eq!(buffer.to_string_lossy(), "cc\n");
+
+        // Без переводов строки режем по байтам
+        buffer.push(&[b'x'; 40]);
+        assert_eq!(buffer.to_string_lossy(), "x".repeat(16));
+    }
+}
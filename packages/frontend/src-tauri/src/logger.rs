@@ -1,21 +1,118 @@
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 use chrono::Utc;
+use crate::error::LauncherError;
+
+/// Rotate the log once it grows past this size
+pub const DEFAULT_MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// How many rotated files (`<name>.1` ... `<name>.N`) are kept per log
+pub const DEFAULT_ROTATED_LOGS: usize = 3;
+
+/// How many logs of previous launches are kept besides the current one
+pub const DEFAULT_KEPT_LOGS: usize = 10;
+
+/// Environment variable with the minimum level written to the log file, e.g. `ALAUNCHER_LOG=debug`
+pub const LOG_LEVEL_ENV: &str = "ALAUNCHER_LOG";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Trace => "TRACE",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<LogLevel> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "trace" => Some(LogLevel::Trace),
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "error" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+
+    /// Level from `ALAUNCHER_LOG`, `Info` when unset or unknown
+    fn from_env() -> LogLevel {
+        match std::env::var(LOG_LEVEL_ENV) {
+            Ok(value) => LogLevel::parse(&value).unwrap_or_else(|| {
+                eprintln!("[ALauncher] Unknown {}={}, using info", LOG_LEVEL_ENV, value);
+                LogLevel::Info
+            }),
+            Err(_) => LogLevel::Info,
+        }
+    }
+}
+
+/// Environment variable selecting the file format: `text` (default) or `json`
+pub const LOG_FORMAT_ENV: &str = "ALAUNCHER_LOG_FORMAT";
+
+/// Target of ordinary launcher messages in JSON records
+const DEFAULT_TARGET: &str = "alauncher";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// `[ts] [LEVEL] message`
+    Text,
+    /// One `{ "ts", "level", "msg", "target" }` object per line
+    Json,
+}
+
+impl LogFormat {
+    fn from_env() -> LogFormat {
+        match std::env::var(LOG_FORMAT_ENV) {
+            Ok(value) if value.trim().eq_ignore_ascii_case("json") => LogFormat::Json,
+            _ => LogFormat::Text,
+        }
+    }
+}
 
 pub struct FileLogger {
     #[allow(dead_code)]
     file: Mutex<Option<std::fs::File>>,
     log_path: Mutex<Option<PathBuf>>,
+    max_bytes: u64,
+    rotated_logs: usize,
+    /// Lines below this level are not written to the file
+    min_level: LogLevel,
+    /// Still print lines below `min_level` to stderr
+    echo_filtered: bool,
+    format: LogFormat,
 }
 
 impl FileLogger {
     #[allow(dead_code)]
     pub fn new() -> Self {
+        Self::with_rotation(DEFAULT_MAX_LOG_BYTES, DEFAULT_ROTATED_LOGS)
+    }
+
+    /// Logger that rotates its file past `max_bytes`, keeping `rotated_logs` old parts
+    pub fn with_rotation(max_bytes: u64, rotated_logs: usize) -> Self {
         let logger = FileLogger {
             file: Mutex::new(None),
             log_path: Mutex::new(None),
+            max_bytes,
+            rotated_logs,
+            min_level: LogLevel::from_env(),
+            // В отладочной сборке отфильтрованное видно хотя бы в консоли
+            echo_filtered: cfg!(debug_assertions),
+            format: LogFormat::from_env(),
         };
 
         // Инициализация лог-файла
@@ -71,8 +168,24 @@ impl FileLogger {
         }
 
         // Записываем путь к лог-файлу для удобства
-        let latest_log_path = log_dir.join("latest.log");
-        std::fs::write(&latest_log_path, log_file_path.to_string_lossy().as_bytes())?;
+        write_latest_pointer(&log_dir, &log_file_path)?;
+
+        prune_old_logs(&log_dir, &log_file_path, DEFAULT_KEPT_LOGS);
+
+        if self.format == LogFormat::Json {
+            // Заголовок одной записью, чтобы его было удобно разбирать
+            let info = serde_json::json!({
+                "os": std::env::consts::OS,
+                "arch": std::env::consts::ARCH,
+                "version": env!("CARGO_PKG_VERSION"),
+                "executable": std::env::current_exe().unwrap_or_else(|_| PathBuf::from("unknown")),
+                "working_directory": std::env::current_dir().unwrap_or_else(|_| PathBuf::from("unknown")),
+                "log_file": log_file_path,
+                "log_level": self.min_level.as_str(),
+            });
+            self.write_entry(LogLevel::Info, "system", "ALauncher log started", Some(info));
+            return Ok(());
+        }
 
         // Записываем заголовок лога
         self.write_log(&format!(
@@ -87,6 +200,7 @@ impl FileLogger {
         self.write_log(&format!("Executable: {}", std::env::current_exe().unwrap_or_else(|_| PathBuf::from("unknown")).display()));
         self.write_log(&format!("Working Directory: {}", std::env::current_dir().unwrap_or_else(|_| PathBuf::from("unknown")).display()));
         self.write_log(&format!("Log file: {}", log_file_path.display()));
+        self.write_log(&format!("Log level: {} (set {} to change)", self.min_level.as_str(), LOG_LEVEL_ENV));
         self.write_log("=== System Info End ===\n");
 
         Ok(())
@@ -94,10 +208,50 @@ impl FileLogger {
 
     #[allow(dead_code)]
     pub fn write_log(&self, message: &str) {
+        self.log(LogLevel::Info, message);
+    }
+
+    /// Write a `[LEVEL]`-tagged line; lines below the minimum level skip the file
+    pub fn log(&self, level: LogLevel, message: &str) {
+        self.write_entry(level, DEFAULT_TARGET, message, None);
+    }
+
+    /// One log entry in the configured format; `info` is only kept in JSON mode
+    fn write_entry(&self, level: LogLevel, target: &str, message: &str, info: Option<serde_json::Value>) {
+        if level < self.min_level {
+            if self.echo_filtered {
+                eprintln!("[ALauncher] [{}] {}", level.as_str(), message);
+            }
+            return;
+        }
+
         if let Ok(mut file_guard) = self.file.lock() {
             if let Some(ref mut file) = *file_guard {
-                let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
-                let log_line = format!("[{}] {}\n", timestamp, message);
+                let log_line = match self.format {
+                    LogFormat::Text => {
+                        let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+                        format!("[{}] [{}] {}\n", timestamp, level.as_str(), message)
+                    }
+                    LogFormat::Json => {
+                        let mut entry = serde_json::json!({
+                            "ts": Utc::now().to_rfc3339(),
+                            "level": level.as_str(),
+                            "msg": message,
+                            "target": target,
+                        });
+                        if let Some(info) = info {
+                            entry["info"] = info;
+                        }
+                        format!("{}\n", entry)
+                    }
+                };
+
+                let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+                if size > 0 && size + log_line.len() as u64 > self.max_bytes {
+                    if let Some(fresh) = self.rotate() {
+                        *file = fresh;
+                    }
+                }
 
                 // Пишем в файл
                 let _ = file.write_all(log_line.as_bytes());
@@ -106,17 +260,44 @@ impl FileLogger {
         }
 
         // Также выводим в stderr (попадает в консоль)
-        eprintln!("[ALauncher] {}", message);
+        eprintln!("[ALauncher] [{}] {}", level.as_str(), message);
+    }
+
+    /// Shift `<name>.1` → `<name>.2` ..., move the active file to `<name>.1` and reopen it empty
+    ///
+    /// The active file keeps its name, so `latest.log` still points at it.
+    fn rotate(&self) -> Option<File> {
+        let log_path = self.log_path.lock().ok()?.clone()?;
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", log_path.display(), n));
+
+        if self.rotated_logs == 0 {
+            let _ = std::fs::remove_file(&log_path);
+        } else {
+            let _ = std::fs::remove_file(rotated(self.rotated_logs));
+            for n in (1..self.rotated_logs).rev() {
+                let _ = std::fs::rename(rotated(n), rotated(n + 1));
+            }
+            if let Err(e) = std::fs::rename(&log_path, rotated(1)) {
+                eprintln!("[ALauncher] Failed to rotate log file: {}", e);
+                return None;
+            }
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&log_path).ok()?;
+        if let Some(log_dir) = log_path.parent() {
+            let _ = write_latest_pointer(log_dir, &log_path);
+        }
+        Some(file)
     }
 
     #[allow(dead_code)]
     pub fn write_error(&self, error: &str) {
-        self.write_log(&format!("ERROR: {}", error));
+        self.log(LogLevel::Error, error);
     }
 
-    #[allow(dead_code)]
-    pub fn write_panic(&self, panic_info: &str) {
-        self.write_log(&format!("!!! PANIC !!!: {}", panic_info));
+    /// Path of the file this logger writes to, `None` if it could not be opened
+    pub fn log_path(&self) -> Option<PathBuf> {
+        self.log_path.lock().ok()?.clone()
     }
 
     #[allow(dead_code)]
@@ -141,66 +322,265 @@ impl FileLogger {
     }
 }
 
-// Глобальный логгер
-#[allow(dead_code)]
-static mut LOGGER: Option<FileLogger> = None;
-#[allow(dead_code)]
-static INIT: std::sync::Once = std::sync::Once::new();
-
-#[allow(dead_code)]
-pub fn init_logger() {
-    INIT.call_once(|| {
-        eprintln!("[ALauncher] Initializing logger...");
+/// `latest.log` holds the path of the active log file
+fn write_latest_pointer(log_dir: &Path, log_file_path: &Path) -> std::io::Result<()> {
+    std::fs::write(log_dir.join("latest.log"), log_file_path.to_string_lossy().as_bytes())
+}
 
-        unsafe {
-            LOGGER = Some(FileLogger::new());
+/// Delete `alauncher_*.log` of old launches (with their rotated parts), keeping the newest `keep`
+///
+/// `current` and `latest.log` are never touched.
+fn prune_old_logs(log_dir: &Path, current: &Path, keep: usize) {
+    let entries = match std::fs::read_dir(log_dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    let mut logs: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.as_path() != current)
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("alauncher_") && name.ends_with(".log"))
+        })
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .collect();
+
+    // Новые первыми
+    logs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+
+    for (_, path) in logs.into_iter().skip(keep) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            eprintln!("[ALauncher] Failed to delete old log {}: {}", path.display(), e);
+            continue;
         }
-
-        eprintln!("[ALauncher] Logger initialized");
-
-        // Устанавливаем panic hook
-        std::panic::set_hook(Box::new(|panic_info| {
-            let location = panic_info.location().unwrap_or_else(|| std::panic::Location::caller());
-            let msg = match panic_info.payload().downcast_ref::<&str>() {
-                Some(s) => *s,
-                None => match panic_info.payload().downcast_ref::<String>() {
-                    Some(s) => &s[..],
-                    None => "Box<Any>",
-                },
-            };
-
-            let panic_details = format!("{} at {}:{}:{}", msg, location.file(), location.line(), location.column());
-
-            unsafe {
-                if let Some(ref logger) = LOGGER {
-                    logger.write_panic(&panic_details);
-                }
+        for n in 1.. {
+            let rotated = PathBuf::from(format!("{}.{}", path.display(), n));
+            if std::fs::remove_file(rotated).is_err() {
+                break;
             }
+        }
+    }
+}
+
+// Глобальный логгер: пишут потоки чтения вывода игры и обработчики команд, FileLogger - Sync
+static LOGGER: OnceLock<FileLogger> = OnceLock::new();
 
-            eprintln!("[ALauncher PANIC] {}", panic_details);
-        }));
+/// Open the log file once; later calls do nothing
+///
+/// Panics keep the default hook (message and backtrace on stderr), the logger doesn't replace it.
+pub fn init_logger() {
+    LOGGER.get_or_init(|| {
+        eprintln!("[ALauncher] Initializing logger...");
+        FileLogger::new()
     });
 }
 
 #[allow(dead_code)]
 pub fn log_message(message: &str) {
-    unsafe {
-        if let Some(ref logger) = LOGGER {
-            logger.write_log(message);
-        }
+    if let Some(logger) = LOGGER.get() {
+        logger.write_log(message);
     }
 }
 
 #[allow(dead_code)]
-pub fn log_error(error: &str) {
-    unsafe {
-        if let Some(ref logger) = LOGGER {
-            logger.write_error(error);
-        }
+pub fn log_at(level: LogLevel, message: &str) {
+    if let Some(logger) = LOGGER.get() {
+        logger.log(level, message);
     }
 }
 
 #[allow(dead_code)]
+pub fn log_error(error: &str) {
+    if let Some(logger) = LOGGER.get() {
+        logger.write_error(error);
+    }
+}
+
+/// Log file of the running launcher; `None` until `init_logger` succeeded
+pub fn current_log_path() -> Option<PathBuf> {
+    LOGGER.get()?.log_path()
+}
+
 pub fn get_latest_log_path() -> Option<PathBuf> {
     FileLogger::get_log_file_path()
 }
+
+/// Log file of this session, or the one `latest.log` points to
+fn active_log_path() -> Option<PathBuf> {
+    current_log_path().or_else(get_latest_log_path)
+}
+
+/// Last `max_lines` lines of the active log, for the in-app log viewer
+///
+/// Empty when no log file exists yet.
+#[tauri::command]
+pub async fn get_recent_logs(max_lines: usize) -> Result<Vec<String>, LauncherError> {
+    let path = match active_log_path() {
+        Some(path) if path.is_file() => path,
+        _ => return Ok(Vec::new()),
+    };
+
+    tokio::task::spawn_blocking(move || read_last_lines(&path, max_lines))
+        .await
+        .map_err(|e| format!("Log read task failed: {}", e))?
+        .map_err(|e| LauncherError::io("Failed to read log file", e))
+}
+
+/// Path of the active log file so the UI can open its folder; `None` before the first log
+#[tauri::command]
+pub async fn get_log_file_path_command() -> Option<String> {
+    active_log_path().map(|path| path.to_string_lossy().to_string())
+}
+
+/// Read the file backwards in blocks until `max_lines` lines are found
+fn read_last_lines(path: &Path, max_lines: usize) -> std::io::Result<Vec<String>> {
+    const BLOCK: u64 = 8 * 1024;
+
+    if max_lines == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut file = File::open(path)?;
+    let mut pos = file.seek(SeekFrom::End(0))?;
+    let mut tail: Vec<u8> = Vec::new();
+
+    // Нужно на один перевод строки больше: первая строка блока может быть неполной
+    while pos > 0 && tail.iter().filter(|&&b| b == b'\n').count() <= max_lines {
+        let read = BLOCK.min(pos);
+        pos -= read;
+        file.seek(SeekFrom::Start(pos))?;
+
+        let mut block = vec![0u8; read as usize];
+        file.read_exact(&mut block)?;
+        block.extend_from_slice(&tail);
+        tail = block;
+    }
+
+    let text = String::from_utf8_lossy(&tail);
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+    Ok(lines[start..].iter().map(|line| line.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Logger writing to `log_path` instead of the user's data directory
+    fn logger_at(log_path: &Path, max_bytes: u64, rotated_logs: usize, min_level: LogLevel, format: LogFormat) -> FileLogger {
+        let file = OpenOptions::new().create(true).append(true).open(log_path).unwrap();
+        FileLogger {
+            file: Mutex::new(Some(file)),
+            log_path: Mutex::new(Some(log_path.to_path_buf())),
+            max_bytes,
+            rotated_logs,
+            min_level,
+            echo_filtered: false,
+            format,
+        }
+    }
+
+    #[test]
+    fn log_rotates_by_size_and_keeps_configured_parts() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join("alauncher_test.log");
+        let logger = logger_at(&log_path, 200, 2, LogLevel::Info, LogFormat::Text);
+
+        for i in 0..40 {
+            logger.write_log(&format!("line {:02}", i));
+        }
+
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", log_path.display(), n));
+        assert!(rotated(1).is_file());
+        assert!(rotated(2).is_file());
+        assert!(!rotated(3).exists());
+        for path in [log_path.clone(), rotated(1), rotated(2)] {
+            assert!(std::fs::metadata(&path).unwrap().len() <= 200, "{}", path.display());
+        }
+
+        // Активный файл сохраняет имя, последняя строка в нем, latest.log указывает на него
+        assert!(std::fs::read_to_string(&log_path).unwrap().ends_with("line 39\n"));
+        let latest = std::fs::read_to_string(dir.path().join("latest.log")).unwrap();
+        assert_eq!(PathBuf::from(latest), log_path);
+    }
+
+    #[test]
+    fn pruning_keeps_newest_logs_current_file_and_pointer() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        let log = |name: &str, age_secs: u64| {
+            let path = dir.path().join(name);
+            File::create(&path).unwrap().set_modified(base - std::time::Duration::from_secs(age_secs)).unwrap();
+            path
+        };
+
+        let newest = log("alauncher_2024-01-05.log", 10);
+        let second = log("alauncher_2024-01-04.log", 20);
+        let old = log("alauncher_2024-01-03.log", 30);
+        let old_part = log("alauncher_2024-01-03.log.1", 30);
+        let oldest = log("alauncher_2024-01-02.log", 40);
+        // Текущий файл старше всех, но удалять его нельзя
+        let current = log("alauncher_2024-01-01.log", 50);
+        let pointer = log("latest.log", 60);
+        let unrelated = log("crash.txt", 70);
+
+        prune_old_logs(dir.path(), &current, 2);
+
+        for kept in [&newest, &second, &current, &pointer, &unrelated] {
+            assert!(kept.exists(), "{} was deleted", kept.display());
+        }
+        for deleted in [&old, &old_part, &oldest] {
+            assert!(!deleted.exists(), "{} was kept", deleted.display());
+        }
+    }
+
+    #[test]
+    fn log_levels_parse_and_filter_the_file() {
+        assert_eq!(LogLevel::parse(" Warning "), Some(LogLevel::Warn));
+        assert_eq!(LogLevel::parse("DEBUG"), Some(LogLevel::Debug));
+        assert_eq!(LogLevel::parse("verbose"), None);
+        assert!(LogLevel::Trace < LogLevel::Info && LogLevel::Warn < LogLevel::Error);
+
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join("alauncher_test.log");
+        let logger = logger_at(&log_path, DEFAULT_MAX_LOG_BYTES, 0, LogLevel::Warn, LogFormat::Text);
+        logger.log(LogLevel::Debug, "probing java");
+        logger.write_log("launching");
+        logger.log(LogLevel::Warn, "low memory");
+        logger.write_error("crashed");
+
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("[WARN] low memory"), "{}", lines[0]);
+        assert!(lines[1].ends_with("[ERROR] crashed"), "{}", lines[1]);
+    }
+
+    #[test]
+    fn json_format_writes_one_object_per_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join("alauncher_test.log");
+        let logger = logger_at(&log_path, DEFAULT_MAX_LOG_BYTES, 0, LogLevel::Info, LogFormat::Json);
+        logger.write_log("line with \"quotes\"\nand a newline");
+        logger.write_entry(LogLevel::Info, "system", "ALauncher log started", Some(serde_json::json!({ "os": "linux" })));
+
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        let entries: Vec<serde_json::Value> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0]["level"], "INFO");
+        assert_eq!(entries[0]["target"], DEFAULT_TARGET);
+        assert_eq!(entries[0]["msg"], "line with \"quotes\"\nand a newline");
+        assert!(chrono::DateTime::parse_from_rfc3339(entries[0]["ts"].as_str().unwrap()).is_ok());
+        assert!(entries[0].get("info").is_none());
+
+        assert_eq!(entries[1]["target"], "system");
+        assert_eq!(entries[1]["info"]["os"], "linux");
+    }
+}
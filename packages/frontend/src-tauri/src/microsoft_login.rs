@@ -0,0 +1,460 @@
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::Emitter;
+use crate::accounts::MINECRAFT_PROFILE_URL;
+use crate::error::LauncherError;
+
+/// Azure application id, provided at build time; login is disabled without it
+const MS_CLIENT_ID: Option<&str> = option_env!("ALAUNCHER_MS_CLIENT_ID");
+const MS_SCOPE: &str = "XboxLive.signin offline_access";
+
+const DEVICE_CODE_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/devicecode";
+const TOKEN_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/token";
+const XBL_AUTH_URL: &str = "https://user.auth.xboxlive.com/user/authenticate";
+const XSTS_AUTH_URL: &str = "https://xsts.auth.xboxlive.com/xsts/authorize";
+const MINECRAFT_LOGIN_URL: &str = "https://api.minecraftservices.com/authentication/login_with_xbox";
+
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Polling interval when the device code response does not name one
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Each `slow_down` answer adds this much to the interval (RFC 8628)
+const SLOW_DOWN_STEP: Duration = Duration::from_secs(5);
+/// Code lifetime when the response has no `expires_in`; Microsoft issues 15 minutes
+const DEVICE_CODE_LIFETIME: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceLogin {
+    pub user_code: String,
+    pub verification_uri: String,
+    pub device_code: String,
+    /// Seconds until the code expires, pass back to `ms_poll_device_login`
+    pub expires_in: u64,
+    /// Minimum seconds between polls, pass back to `ms_poll_device_login`
+    pub interval: u64,
+}
+
+/// Signed-in Minecraft account; `expires_at` is a unix timestamp of the Minecraft token
+#[derive(Debug, Clone, Serialize)]
+pub struct MinecraftSession {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: i64,
+    pub uuid: String,
+    pub username: String,
+}
+
+/// Payload of the "ms-login-progress" event
+#[derive(Debug, Clone, Serialize)]
+struct LoginProgress<'a> {
+    stage: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_code: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verification_uri: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    interval: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct MsTokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OAuthError {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct XboxAuthResponse {
+    #[serde(rename = "Token")]
+    token: String,
+    #[serde(rename = "DisplayClaims")]
+    display_claims: XboxDisplayClaims,
+}
+
+#[derive(Deserialize)]
+struct XboxDisplayClaims {
+    xui: Vec<XboxUserInfo>,
+}
+
+#[derive(Deserialize)]
+struct XboxUserInfo {
+    uhs: String,
+}
+
+#[derive(Deserialize)]
+struct XstsError {
+    #[serde(rename = "XErr", default)]
+    xerr: u64,
+}
+
+#[derive(Deserialize)]
+struct MinecraftLoginResponse {
+    access_token: String,
+    expires_in: i64,
+}
+
+#[derive(Deserialize)]
+struct MinecraftProfile {
+    id: String,
+    name: String,
+}
+
+/// Start a Microsoft device-code login
+///
+/// The user opens `verification_uri` and enters `user_code`; the same data is
+/// emitted as "ms-login-progress" with stage `waiting_for_user`.
+#[tauri::command]
+pub async fn ms_begin_device_login(app: tauri::AppHandle) -> Result<DeviceLogin, LauncherError> {
+    let client = http_client()?;
+    let response = client.post(DEVICE_CODE_URL)
+        .form(&[("client_id", client_id()?), ("scope", MS_SCOPE)])
+        .send()
+        .await
+        .map_err(|e| LauncherError::network(format!("Failed to request a device code: {}", e)))?;
+
+    if !response.status().is_success() {
+        let error: Option<OAuthError> = response.json().await.ok();
+        return Err(LauncherError::auth_failed(format!(
+            "Microsoft rejected the device code request: {}",
+            error.map(|e| e.error_description.unwrap_or(e.error)).unwrap_or_default()
+        )));
+    }
+
+    let code: DeviceCodeResponse = response.json()
+        .await
+        .map_err(|e| LauncherError::network(format!("Invalid device code response: {}", e)))?;
+
+    emit_progress(&app, LoginProgress {
+        stage: "waiting_for_user",
+        user_code: Some(&code.user_code),
+        verification_uri: Some(&code.verification_uri),
+    });
+    eprintln!("[Microsoft Login] Device code issued, waiting for the user");
+
+    let (interval, lifetime) = poll_schedule(code.interval, code.expires_in);
+    Ok(DeviceLogin {
+        user_code: code.user_code,
+        verification_uri: code.verification_uri,
+        device_code: code.device_code,
+        expires_in: lifetime.as_secs(),
+        interval: interval.as_secs(),
+    })
+}
+
+/// Wait for the user to finish the device-code login and sign in to Minecraft
+///
+/// Polls every `interval` seconds (as returned by `ms_begin_device_login`) until the
+/// code is approved, declined or `expires_in` runs out, then runs the Xbox Live, XSTS
+/// and Minecraft steps. An account without Minecraft fails with `NoMinecraft`.
+#[tauri::command]
+pub async fn ms_poll_device_login(
+    device_code: String,
+    interval: Option<u64>,
+    expires_in: Option<u64>,
+    app: tauri::AppHandle,
+) -> Result<MinecraftSession, LauncherError> {
+    let client = http_client()?;
+    let client_id = client_id()?;
+    let (mut interval, lifetime) = poll_schedule(interval, expires_in);
+    let deadline = Instant::now() + lifetime;
+
+    let token = loop {
+        if Instant::now() >= deadline {
+            return Err(LauncherError::auth_failed("The login code expired, start the login again"));
+        }
+        tokio::time::sleep(interval).await;
+
+        let response = client.post(TOKEN_URL)
+            .form(&[
+                ("grant_type", DEVICE_CODE_GRANT),
+                ("client_id", client_id),
+                ("device_code", device_code.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| LauncherError::network(format!("Failed to poll the login status: {}", e)))?;
+
+        if response.status().is_success() {
+            break response.json::<MsTokenResponse>()
+                .await
+                .map_err(|e| LauncherError::network(format!("Invalid token response: {}", e)))?;
+        }
+
+        let error: OAuthError = response.json()
+            .await
+            .map_err(|e| LauncherError::network(format!("Invalid token error response: {}", e)))?;
+        keep_polling(error, &mut interval)?;
+    };
+
+    let refresh_token = token.refresh_token.unwrap_or_default();
+    minecraft_session(&client, &app, &token.access_token, refresh_token).await
+}
+
+/// Interval and lifetime from the device code response, Microsoft's defaults when absent
+fn poll_schedule(interval_secs: Option<u64>, expires_in_secs: Option<u64>) -> (Duration, Duration) {
+    let interval = interval_secs.filter(|&secs| secs > 0).map_or(POLL_INTERVAL, Duration::from_secs);
+    let lifetime = expires_in_secs.filter(|&secs| secs > 0).map_or(DEVICE_CODE_LIFETIME, Duration::from_secs);
+    (interval, lifetime)
+}
+
+/// Token endpoint error while polling: keep waiting (slower on `slow_down`) or fail
+fn keep_polling(error: OAuthError, interval: &mut Duration) -> Result<(), LauncherError> {
+    match error.error.as_str() {
+        "authorization_pending" => Ok(()),
+        "slow_down" => {
+            *interval += SLOW_DOWN_STEP;
+            Ok(())
+        }
+        "authorization_declined" => Err(LauncherError::auth_failed("The login was declined")),
+        "expired_token" => Err(LauncherError::auth_failed("The login code expired, start the login again")),
+        "bad_verification_code" => Err(LauncherError::invalid_params("Unknown device code")),
+        _ => Err(LauncherError::auth_failed(format!(
+            "Microsoft login failed: {}",
+            error.error_description.unwrap_or(error.error)
+        ))),
+    }
+}
+
+/// Silently renew a Microsoft login with a stored refresh token
+///
+/// Microsoft rotates refresh tokens, so the returned `refresh_token` replaces the
+/// stored one. A revoked or expired refresh token fails with `ReauthRequired`.
+#[tauri::command]
+pub async fn ms_refresh_token(
+    refresh_token: String,
+    app: tauri::AppHandle,
+) -> Result<MinecraftSession, LauncherError> {
+    if refresh_token.is_empty() {
+        return Err(LauncherError::ReauthRequired { message: "No refresh token stored".to_string() });
+    }
+
+    let client = http_client()?;
+    let response = client.post(TOKEN_URL)
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("client_id", client_id()?),
+            ("refresh_token", refresh_token.as_str()),
+            ("scope", MS_SCOPE),
+        ])
+        .send()
+        .await
+        .map_err(|e| LauncherError::network(format!("Failed to refresh the Microsoft login: {}", e)))?;
+
+    if !response.status().is_success() {
+        let error: OAuthError = response.json()
+            .await
+            .map_err(|e| LauncherError::network(format!("Invalid token error response: {}", e)))?;
+
+        // invalid_grant: токен отозван, истек или пароль был сменен
+        if error.error == "invalid_grant" || error.error == "interaction_required" {
+            eprintln!("[Microsoft Login] ⚠️ Refresh token rejected: {}", error.error);
+            return Err(LauncherError::ReauthRequired {
+                message: "The Microsoft login has expired, please sign in again".to_string(),
+            });
+        }
+        return Err(LauncherError::auth_failed(format!(
+            "Microsoft token refresh failed: {}",
+            error.error_description.unwrap_or(error.error)
+        )));
+    }
+
+    let token: MsTokenResponse = response.json()
+        .await
+        .map_err(|e| LauncherError::network(format!("Invalid token response: {}", e)))?;
+
+    let refresh_token = token.refresh_token.unwrap_or(refresh_token);
+    minecraft_session(&client, &app, &token.access_token, refresh_token).await
+}
+
+/// Exchange a Microsoft access token for a Minecraft session (XBL → XSTS → Minecraft → profile)
+async fn minecraft_session(
+    client: &reqwest::Client,
+    app: &tauri::AppHandle,
+    ms_access_token: &str,
+    refresh_token: String,
+) -> Result<MinecraftSession, LauncherError> {
+    emit_progress(app, LoginProgress { stage: "xbox_live", user_code: None, verification_uri: None });
+    let xbl = xbox_authenticate(client, XBL_AUTH_URL, json!({
+        "Properties": {
+            "AuthMethod": "RPS",
+            "SiteName": "user.auth.xboxlive.com",
+            "RpsTicket": format!("d={}", ms_access_token),
+        },
+        "RelyingParty": "http://auth.xboxlive.com",
+        "TokenType": "JWT",
+    })).await?;
+
+    emit_progress(app, LoginProgress { stage: "xsts", user_code: None, verification_uri: None });
+    let xsts = xbox_authenticate(client, XSTS_AUTH_URL, json!({
+        "Properties": {
+            "SandboxId": "RETAIL",
+            "UserTokens": [xbl.token],
+        },
+        "RelyingParty": "rp://api.minecraftservices.com/",
+        "TokenType": "JWT",
+    })).await?;
+
+    let user_hash = xsts.display_claims.xui.first()
+        .map(|xui| xui.uhs.clone())
+        .ok_or_else(|| LauncherError::auth_failed("Xbox Live returned no user hash"))?;
+
+    emit_progress(app, LoginProgress { stage: "minecraft", user_code: None, verification_uri: None });
+    let response = client.post(MINECRAFT_LOGIN_URL)
+        .json(&json!({ "identityToken": format!("XBL3.0 x={};{}", user_hash, xsts.token) }))
+        .send()
+        .await
+        .map_err(|e| LauncherError::network(format!("Failed to sign in to Minecraft: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(LauncherError::auth_failed(format!(
+            "Minecraft sign-in failed: HTTP {}",
+            response.status()
+        )));
+    }
+    let login: MinecraftLoginResponse = response.json()
+        .await
+        .map_err(|e| LauncherError::network(format!("Invalid Minecraft login response: {}", e)))?;
+
+    emit_progress(app, LoginProgress { stage: "profile", user_code: None, verification_uri: None });
+    let response = client.get(MINECRAFT_PROFILE_URL)
+        .bearer_auth(&login.access_token)
+        .send()
+        .await
+        .map_err(|e| LauncherError::network(format!("Failed to fetch the Minecraft profile: {}", e)))?;
+
+    // 404 — аккаунт Microsoft без купленной игры (или профиль еще не создан)
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(LauncherError::NoMinecraft {
+            message: "This Microsoft account does not own Minecraft".to_string(),
+        });
+    }
+    if !response.status().is_success() {
+        return Err(LauncherError::network(format!(
+            "Failed to fetch the Minecraft profile: HTTP {}",
+            response.status()
+        )));
+    }
+    let profile: MinecraftProfile = response.json()
+        .await
+        .map_err(|e| LauncherError::network(format!("Invalid Minecraft profile response: {}", e)))?;
+
+    eprintln!("[Microsoft Login] ✅ Signed in as {}", profile.name);
+    emit_progress(app, LoginProgress { stage: "done", user_code: None, verification_uri: None });
+
+    Ok(MinecraftSession {
+        access_token: login.access_token,
+        refresh_token,
+        expires_at: chrono::Utc::now().timestamp() + login.expires_in,
+        uuid: profile.id,
+        username: profile.name,
+    })
+}
+
+async fn xbox_authenticate(
+    client: &reqwest::Client,
+    url: &str,
+    body: serde_json::Value,
+) -> Result<XboxAuthResponse, LauncherError> {
+    let response = client.post(url)
+        .header("Accept", "application/json")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| LauncherError::network(format!("Xbox Live request failed: {}", e)))?;
+
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        let xerr = response.json::<XstsError>().await.map(|e| e.xerr).unwrap_or_default();
+        return Err(LauncherError::auth_failed(xbox_error_message(xerr)));
+    }
+    if !response.status().is_success() {
+        return Err(LauncherError::auth_failed(format!(
+            "Xbox Live authentication failed: HTTP {}",
+            response.status()
+        )));
+    }
+
+    response.json()
+        .await
+        .map_err(|e| LauncherError::network(format!("Invalid Xbox Live response: {}", e)))
+}
+
+/// Known XSTS `XErr` codes
+fn xbox_error_message(xerr: u64) -> String {
+    match xerr {
+        2148916233 => "This Microsoft account has no Xbox profile. Sign in on minecraft.net once to create it".to_string(),
+        2148916235 => "Xbox Live is not available in your country".to_string(),
+        2148916236 | 2148916237 => "This account needs adult verification on xbox.com".to_string(),
+        2148916238 => "This is a child account, it must be added to a Family by an adult".to_string(),
+        0 => "Xbox Live authentication was rejected".to_string(),
+        code => format!("Xbox Live authentication was rejected (XErr {})", code),
+    }
+}
+
+fn client_id() -> Result<&'static str, LauncherError> {
+    MS_CLIENT_ID.ok_or_else(|| LauncherError::Other {
+        message: "Microsoft login is not configured in this build".to_string(),
+    })
+}
+
+fn http_client() -> Result<reqwest::Client, LauncherError> {
+    Ok(reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?)
+}
+
+fn emit_progress(app: &tauri::AppHandle, progress: LoginProgress) {
+    let _ = app.emit("ms-login-progress", progress);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oauth_error(error: &str) -> OAuthError {
+        OAuthError { error: error.to_string(), error_description: None }
+    }
+
+    #[test]
+    fn poll_schedule_follows_the_server() {
+        assert_eq!(poll_schedule(Some(10), Some(600)), (Duration::from_secs(10), Duration::from_secs(600)));
+        assert_eq!(poll_schedule(None, None), (POLL_INTERVAL, DEVICE_CODE_LIFETIME));
+        assert_eq!(poll_schedule(Some(0), Some(0)), (POLL_INTERVAL, DEVICE_CODE_LIFETIME));
+    }
+
+    #[test]
+    fn slow_down_increases_the_interval() {
+        let mut interval = Duration::from_secs(5);
+        keep_polling(oauth_error("authorization_pending"), &mut interval).unwrap();
+        assert_eq!(interval, Duration::from_secs(5));
+
+        keep_polling(oauth_error("slow_down"), &mut interval).unwrap();
+        keep_polling(oauth_error("slow_down"), &mut interval).unwrap();
+        assert_eq!(interval, Duration::from_secs(15));
+    }
+
+    #[test]
+    fn terminal_poll_errors_stop_the_login() {
+        let mut interval = POLL_INTERVAL;
+        assert!(matches!(keep_polling(oauth_error("authorization_declined"), &mut interval), Err(LauncherError::AuthFailed { .. })));
+        assert!(matches!(keep_polling(oauth_error("expired_token"), &mut interval), Err(LauncherError::AuthFailed { .. })));
+        assert!(matches!(keep_polling(oauth_error("bad_verification_code"), &mut interval), Err(LauncherError::InvalidParams { .. })));
+        assert!(keep_polling(oauth_error("invalid_client"), &mut interval).is_err());
+    }
+}
@@ -0,0 +1,596 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use serde_json::Value;
+use crate::archive;
+use crate::error::LauncherError;
+
+/// Metadata declared inside a mod jar
+#[derive(Debug, Clone, Serialize)]
+pub struct ModMetadata {
+    pub id: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    /// "fabric", "quilt", "forge", "neoforge"
+    pub loader: String,
+}
+
+/// Read mod id/name/version from `fabric.mod.json`, `quilt.mod.json`,
+/// `META-INF/mods.toml`, `META-INF/neoforge.mods.toml` or `mcmod.info`
+pub fn read_mod_metadata(jar: &Path) -> Result<Option<ModMetadata>, String> {
+    if let Some(content) = archive::read_zip_entry(jar, "fabric.mod.json")? {
+        return Ok(parse_fabric_json(&content, "fabric"));
+    }
+
+    if let Some(content) = archive::read_zip_entry(jar, "quilt.mod.json")? {
+        return Ok(parse_quilt_json(&content));
+    }
+
+    for (entry, loader) in [
+        ("META-INF/neoforge.mods.toml", "neoforge"),
+        ("META-INF/mods.toml", "forge"),
+    ] {
+        if let Some(content) = archive::read_zip_entry(jar, entry)? {
+            let mut metadata = parse_mods_toml(&content, loader);
+            // "${file.jarVersion}" берется из манифеста jar
+            if let Some(meta) = metadata.as_mut() {
+                if meta.version.as_deref().is_some_and(|v| v.contains("${")) {
+                    meta.version = read_manifest_version(jar)?;
+                }
+            }
+            return Ok(metadata);
+        }
+    }
+
+    if let Some(content) = archive::read_zip_entry(jar, "mcmod.info")? {
+        return Ok(parse_mcmod_info(&content));
+    }
+
+    Ok(None)
+}
+
+fn json_str(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn parse_fabric_json(content: &[u8], loader: &str) -> Option<ModMetadata> {
+    // Некоторые моды кладут в JSON управляющие символы, поэтому читаем через lossy
+    let json: Value = serde_json::from_str(&String::from_utf8_lossy(content)).ok()?;
+    Some(ModMetadata {
+        id: json_str(&json, "id")?,
+        name: json_str(&json, "name"),
+        version: json_str(&json, "version"),
+        description: json_str(&json, "description"),
+        loader: loader.to_string(),
+    })
+}
+
+fn parse_quilt_json(content: &[u8]) -> Option<ModMetadata> {
+    let json: Value = serde_json::from_slice(content).ok()?;
+    let loader = json.get("quilt_loader")?;
+    let metadata = loader.get("metadata");
+    Some(ModMetadata {
+        id: json_str(loader, "id")?,
+        name: metadata.and_then(|m| json_str(m, "name")),
+        version: json_str(loader, "version"),
+        description: metadata.and_then(|m| json_str(m, "description")),
+        loader: "quilt".to_string(),
+    })
+}
+
+fn parse_mods_toml(content: &[u8], loader: &str) -> Option<ModMetadata> {
+    let toml: toml::Value = toml::from_str(&String::from_utf8_lossy(content)).ok()?;
+    let first = toml.get("mods")?.as_array()?.first()?;
+    let field = |key: &str| first.get(key).and_then(|v| v.as_str()).map(|s| s.trim().to_string());
+    Some(ModMetadata {
+        id: field("modId")?,
+        name: field("displayName"),
+        version: field("version"),
+        description: field("description"),
+        loader: loader.to_string(),
+    })
+}
+
+fn parse_mcmod_info(content: &[u8]) -> Option<ModMetadata> {
+    let json: Value = serde_json::from_str(&String::from_utf8_lossy(content)).ok()?;
+    // Два формата: массив модов или { "modList": [...] }
+    let first = match &json {
+        Value::Array(mods) => mods.first()?,
+        _ => json.get("modList")?.as_array()?.first()?,
+    };
+    Some(ModMetadata {
+        id: json_str(first, "modid")?,
+        name: json_str(first, "name"),
+        version: json_str(first, "version"),
+        description: json_str(first, "description"),
+        loader: "forge".to_string(),
+    })
+}
+
+fn read_manifest_version(jar: &Path) -> Result<Option<String>, String> {
+    let manifest = match archive::read_zip_entry(jar, "META-INF/MANIFEST.MF")? {
+        Some(manifest) => manifest,
+        None => return Ok(None),
+    };
+
+    Ok(String::from_utf8_lossy(&manifest)
+        .lines()
+        .find_map(|line| line.strip_prefix("Implementation-Version:"))
+        .map(|v| v.trim().to_string()))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateFile {
+    pub path: String,
+    pub version: Option<String>,
+}
+
+/// Mod jars sharing the same mod id
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub mod_id: String,
+    pub files: Vec<DuplicateFile>,
+}
+
+/// Group enabled mod jars by mod id and report ids present more than once
+///
+/// Nothing is deleted; jars with unreadable metadata are skipped.
+#[tauri::command]
+pub async fn find_duplicate_mods(mods_dir: String) -> Result<Vec<DuplicateGroup>, LauncherError> {
+    let groups = tokio::task::spawn_blocking(move || duplicate_groups(&mods_dir))
+        .await
+        .map_err(|e| format!("Duplicate scan task failed: {}", e))??;
+    Ok(groups)
+}
+
+fn duplicate_groups(mods_dir: &str) -> Result<Vec<DuplicateGroup>, String> {
+    let entries = fs::read_dir(mods_dir)
+        .map_err(|e| format!("Failed to read mods directory '{}': {}", mods_dir, e))?;
+
+    let mut by_id: BTreeMap<String, Vec<DuplicateFile>> = BTreeMap::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("jar") {
+            continue;
+        }
+
+        match read_mod_metadata(&path) {
+            Ok(Some(metadata)) => {
+                by_id.entry(metadata.id).or_default().push(DuplicateFile {
+                    path: path.to_string_lossy().to_string(),
+                    version: metadata.version,
+                });
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("[Mods] ⚠️ Skipping {}: {}", path.display(), e),
+        }
+    }
+
+    Ok(by_id.into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|(mod_id, files)| DuplicateGroup { mod_id, files })
+        .collect())
+}
+
+/// Files touched by `merge_mods`, as file names inside the destination folder
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MergeReport {
+    pub added: Vec<String>,
+    pub skipped: Vec<String>,
+    pub replaced: Vec<String>,
+}
+
+/// A jar in a mods folder, enabled (`.jar`) or disabled (`.jar.disabled`)
+struct ModFile {
+    path: PathBuf,
+    file_name: String,
+    /// Mod id, or the file name without `.disabled` for jars without metadata
+    key: String,
+    metadata: Option<ModMetadata>,
+    enabled: bool,
+}
+
+impl ModFile {
+    fn version(&self) -> Option<&str> {
+        self.metadata.as_ref()?.version.as_deref()
+    }
+
+    /// The same mod after being copied to `path`
+    fn copied_to(self, path: PathBuf) -> ModFile {
+        ModFile { path, ..self }
+    }
+}
+
+fn scan_mod_files(dir: &Path) -> Result<Vec<ModFile>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read mods directory '{}': {}", dir.display(), e))?;
+
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let (base_name, enabled) = match file_name.strip_suffix(".disabled") {
+            Some(base) => (base.to_string(), false),
+            None => (file_name.clone(), true),
+        };
+        if !path.is_file() || !base_name.ends_with(".jar") {
+            continue;
+        }
+
+        let metadata = read_mod_metadata(&path).unwrap_or_else(|e| {
+            eprintln!("[Mods] ⚠️ Failed to read metadata of {}: {}", path.display(), e);
+            None
+        });
+
+        files.push(ModFile {
+            key: metadata.as_ref().map(|m| m.id.clone()).unwrap_or(base_name),
+            metadata,
+            path,
+            file_name,
+            enabled,
+        });
+    }
+    Ok(files)
+}
+
+/// A jar in `<game_dir>/mods` with what its metadata declares
+#[derive(Debug, Clone, Serialize)]
+pub struct ModInfo {
+    pub file_name: String,
+    pub path: String,
+    pub enabled: bool,
+    pub size: u64,
+    /// Mod id, or the file name without `.jar` when the jar has no metadata
+    pub id: String,
+    /// Falls back to `id`
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    /// "fabric", "quilt", "forge", "neoforge"; None without metadata
+    pub loader: Option<String>,
+}
+
+/// Installed mods of an instance, enabled and disabled, sorted by name
+///
+/// A missing mods folder means no mods.
+#[tauri::command]
+pub async fn list_mods(game_dir: String) -> Result<Vec<ModInfo>, LauncherError> {
+    let mods_dir = Path::new(&game_dir).join("mods");
+    if !mods_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let files = tokio::task::spawn_blocking(move || scan_mod_files(&mods_dir))
+        .await
+        .map_err(|e| format!("Mod scan task failed: {}", e))??;
+
+    let mut mods: Vec<ModInfo> = files.into_iter()
+        .map(|file| {
+            let stem = file.file_name.trim_end_matches(".disabled").trim_end_matches(".jar").to_string();
+            let size = fs::metadata(&file.path).map(|m| m.len()).unwrap_or(0);
+            let metadata = file.metadata;
+            let id = metadata.as_ref().map(|m| m.id.clone()).unwrap_or(stem);
+            ModInfo {
+                file_name: file.file_name,
+                path: file.path.to_string_lossy().to_string(),
+                enabled: file.enabled,
+                size,
+                name: metadata.as_ref().and_then(|m| m.name.clone()).unwrap_or_else(|| id.clone()),
+                id,
+                version: metadata.as_ref().and_then(|m| m.version.clone()),
+                description: metadata.as_ref().and_then(|m| m.description.clone()),
+                loader: metadata.map(|m| m.loader),
+            }
+        })
+        .collect();
+
+    mods.sort_by_cached_key(|info| (info.name.to_lowercase(), info.file_name.clone()));
+    Ok(mods)
+}
+
+/// Copy mods from `src_dir` into `dst_dir`
+///
+/// `on_conflict` decides what happens when the destination already has the same mod or a
+/// file with the same name: "skip", "overwrite" or "keep-newer" (by mod version, falling
+/// back to mtime). A disabled copy in the destination does not block an enabled one from
+/// being added, and a disabled source never replaces an enabled mod.
+#[tauri::command]
+pub async fn merge_mods(src_dir: String, dst_dir: String, on_conflict: String) -> Result<MergeReport, LauncherError> {
+    if !matches!(on_conflict.as_str(), "skip" | "overwrite" | "keep-newer") {
+        return Err(LauncherError::invalid_params(format!(
+            "Unknown conflict mode: {}. Expected skip, overwrite or keep-newer.",
+            on_conflict
+        )));
+    }
+
+    tokio::task::spawn_blocking(move || merge_mod_dirs(Path::new(&src_dir), Path::new(&dst_dir), &on_conflict))
+        .await
+        .map_err(|e| format!("Mod merge task failed: {}", e))?
+}
+
+fn merge_mod_dirs(src_dir: &Path, dst_dir: &Path, on_conflict: &str) -> Result<MergeReport, LauncherError> {
+    fs::create_dir_all(dst_dir)
+        .map_err(|e| LauncherError::io("Failed to create mods directory", e))?;
+    if fs::canonicalize(src_dir).ok() == fs::canonicalize(dst_dir).ok() {
+        return Err(LauncherError::invalid_params("Source and destination are the same folder"));
+    }
+
+    let sources = scan_mod_files(src_dir)?;
+    let mut existing = scan_mod_files(dst_dir)?;
+    let mut report = MergeReport::default();
+
+    for source in sources {
+        let same_name = existing.iter().position(|d| d.file_name == source.file_name);
+        let same_mod = || existing.iter().enumerate().filter(|(_, d)| d.key == source.key);
+        // Отключенный мод в папке назначения не мешает добавить включенный
+        let same_mod_conflict = if source.enabled {
+            same_mod().find(|(_, d)| d.enabled)
+        } else {
+            same_mod().find(|(_, d)| d.enabled).or_else(|| same_mod().next())
+        };
+        let dest = dst_dir.join(&source.file_name);
+
+        let Some(index) = same_mod_conflict.map(|(index, _)| index).or(same_name) else {
+            copy_mod(&source.path, &dest)?;
+            report.added.push(source.file_name.clone());
+            existing.push(source.copied_to(dest));
+            continue;
+        };
+        let conflict = &existing[index];
+
+        // Под этим именем лежит другой файл, замена его бы затерла
+        let name_taken = same_name.is_some_and(|name_index| name_index != index);
+        // Отключенная копия не должна отключать мод пользователя
+        let disables_mod = conflict.enabled && !source.enabled;
+        let replace = !name_taken && !disables_mod && match on_conflict {
+            "overwrite" => true,
+            "keep-newer" => is_newer(&source, conflict),
+            _ => false,
+        };
+
+        if !replace {
+            report.skipped.push(source.file_name);
+            continue;
+        }
+
+        copy_mod(&source.path, &dest)?;
+        if conflict.file_name != source.file_name {
+            fs::remove_file(&conflict.path)
+                .map_err(|e| format!("Failed to remove {}: {}", conflict.path.display(), e))?;
+        }
+        report.replaced.push(source.file_name.clone());
+        existing[index] = source.copied_to(dest);
+    }
+
+    eprintln!(
+        "[Mods] Merged mods: {} added, {} replaced, {} skipped",
+        report.added.len(),
+        report.replaced.len(),
+        report.skipped.len()
+    );
+
+    Ok(report)
+}
+
+/// Copy through a temp file so an interrupted merge never leaves a truncated jar
+fn copy_mod(from: &Path, to: &Path) -> Result<(), String> {
+    let file_name = to.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let temp = to.with_file_name(format!(".{}.tmp", file_name));
+
+    fs::copy(from, &temp)
+        .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))?;
+    fs::rename(&temp, to).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        format!("Failed to move {} into place: {}", to.display(), e)
+    })
+}
+
+fn is_newer(source: &ModFile, existing: &ModFile) -> bool {
+    if let (Some(a), Some(b)) = (source.version(), existing.version()) {
+        if let Some(ordering) = compare_versions(a, b) {
+            return ordering == Ordering::Greater;
+        }
+    }
+
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(&source.path), modified(&existing.path)) {
+        (Some(a), Some(b)) => a > b,
+        _ => false,
+    }
+}
+
+/// Compare dotted versions part by part ("0.5.10" > "0.5.9"); `None` if either is empty
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    // Метаданные сборки после '+' (например, "+mc1.20.1") в сравнении не участвуют
+    let parts = |v: &str| -> Vec<String> {
+        v.split('+').next().unwrap_or_default()
+            .split(['.', '-', '_'])
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+
+    for (x, y) in a.iter().zip(&b) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return Some(ordering);
+        }
+    }
+    Some(a.len().cmp(&b.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn write_jar(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = ZipWriter::new(fs::File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[tokio::test]
+    async fn list_mods_reads_metadata_of_every_loader() {
+        let dir = tempfile::tempdir().unwrap();
+        let mods_dir = dir.path().join("mods");
+        fs::create_dir(&mods_dir).unwrap();
+
+        write_jar(&mods_dir.join("sodium-0.5.8.jar"), &[(
+            "fabric.mod.json",
+            r#"{"id": "sodium", "name": "Sodium", "version": "0.5.8", "description": "Rendering engine"}"#,
+        )]);
+        write_jar(&mods_dir.join("jei.jar"), &[
+            ("META-INF/mods.toml", "modLoader = \"javafml\"\n[[mods]]\nmodId = \"jei\"\ndisplayName = \"Just Enough Items\"\nversion = \"${file.jarVersion}\"\n"),
+            ("META-INF/MANIFEST.MF", "Manifest-Version: 1.0\nImplementation-Version: 15.3.0.4\n"),
+        ]);
+        write_jar(&mods_dir.join("OldMod.jar.disabled"), &[(
+            "mcmod.info",
+            r#"{"modList": [{"modid": "oldmod", "name": "Old Mod", "version": "1.7.10-2"}]}"#,
+        )]);
+        write_jar(&mods_dir.join("no-metadata.jar"), &[("com/example/Main.class", "")]);
+        fs::write(mods_dir.join("readme.txt"), "not a mod").unwrap();
+
+        let mods = list_mods(dir.path().to_string_lossy().to_string()).await.unwrap();
+        let summary: Vec<_> = mods.iter()
+            .map(|m| (m.id.as_str(), m.name.as_str(), m.version.as_deref(), m.loader.as_deref(), m.enabled))
+            .collect();
+        assert_eq!(summary, [
+            ("jei", "Just Enough Items", Some("15.3.0.4"), Some("forge"), true),
+            ("no-metadata", "no-metadata", None, None, true),
+            ("oldmod", "Old Mod", Some("1.7.10-2"), Some("forge"), false),
+            ("sodium", "Sodium", Some("0.5.8"), Some("fabric"), true),
+        ]);
+        assert_eq!(mods[3].description.as_deref(), Some("Rendering engine"));
+        assert_eq!(mods[2].file_name, "OldMod.jar.disabled");
+        assert!(mods.iter().all(|m| m.size > 0));
+    }
+
+    #[tokio::test]
+    async fn missing_mods_folder_means_no_mods() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(list_mods(dir.path().to_string_lossy().to_string()).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn quilt_and_neoforge_metadata_is_recognised() {
+        let dir = tempfile::tempdir().unwrap();
+        let quilt = dir.path().join("quilt.jar");
+        write_jar(&quilt, &[(
+            "quilt.mod.json",
+            r#"{"quilt_loader": {"id": "qsl", "version": "7.0.0", "metadata": {"name": "Quilt Standard Libraries"}}}"#,
+        )]);
+        let metadata = read_mod_metadata(&quilt).unwrap().unwrap();
+        assert_eq!((metadata.id.as_str(), metadata.loader.as_str()), ("qsl", "quilt"));
+        assert_eq!(metadata.name.as_deref(), Some("Quilt Standard Libraries"));
+
+        let neoforge = dir.path().join("neoforge.jar");
+        write_jar(&neoforge, &[("META-INF/neoforge.mods.toml", "[[mods]]\nmodId = \"create\"\nversion = \"0.5.1\"\n")]);
+        let metadata = read_mod_metadata(&neoforge).unwrap().unwrap();
+        assert_eq!((metadata.id.as_str(), metadata.loader.as_str()), ("create", "neoforge"));
+
+        // Битый JSON не мешает списку, мод просто без метаданных
+        let broken = dir.path().join("broken.jar");
+        write_jar(&broken, &[("fabric.mod.json", "{ not json")]);
+        assert!(read_mod_metadata(&broken).unwrap().is_none());
+    }
+
+    #[test]
+    fn dotted_versions_compare_numerically() {
+        assert_eq!(compare_versions("0.5.10", "0.5.9"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("1.0.0+mc1.20.1", "1.0.0+mc1.19.2"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("1.2", "1.2.1"), Some(Ordering::Less));
+        assert_eq!(compare_versions("", "1.0"), None);
+    }
+
+    fn fabric_jar(path: &Path, id: &str, version: &str) {
+        write_jar(path, &[("fabric.mod.json", &format!(r#"{{"id": "{}", "version": "{}"}}"#, id, version))]);
+    }
+
+    async fn merge(src: &Path, dst: &Path, on_conflict: &str) -> MergeReport {
+        merge_mods(src.to_string_lossy().to_string(), dst.to_string_lossy().to_string(), on_conflict.to_string())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn merge_adds_each_mod_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let (src, dst) = (dir.path().join("src"), dir.path().join("dst"));
+        fs::create_dir_all(&src).unwrap();
+        fabric_jar(&src.join("sodium-0.5.7.jar"), "sodium", "0.5.7");
+        fabric_jar(&src.join("sodium-0.5.8.jar"), "sodium", "0.5.8");
+
+        let report = merge(&src, &dst, "skip").await;
+        assert_eq!((report.added.len(), report.skipped.len()), (1, 1));
+        assert_eq!(fs::read_dir(&dst).unwrap().count(), 1);
+
+        // keep-newer оставляет одну, самую новую копию
+        fs::remove_dir_all(&dst).unwrap();
+        let report = merge(&src, &dst, "keep-newer").await;
+        assert_eq!(report.added.len() + report.replaced.len() + report.skipped.len(), 2);
+        let names: Vec<_> = fs::read_dir(&dst).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(names, ["sodium-0.5.8.jar"]);
+    }
+
+    #[tokio::test]
+    async fn disabled_source_never_replaces_an_enabled_mod() {
+        let dir = tempfile::tempdir().unwrap();
+        let (src, dst) = (dir.path().join("src"), dir.path().join("dst"));
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&dst).unwrap();
+        fabric_jar(&src.join("sodium.jar.disabled"), "sodium", "0.6.0");
+        fabric_jar(&dst.join("sodium.jar"), "sodium", "0.5.8");
+
+        let report = merge(&src, &dst, "overwrite").await;
+        assert_eq!(report.skipped, ["sodium.jar.disabled"]);
+        assert!(dst.join("sodium.jar").exists());
+        assert!(!dst.join("sodium.jar.disabled").exists());
+    }
+
+    #[tokio::test]
+    async fn file_with_the_same_name_is_a_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        let (src, dst) = (dir.path().join("src"), dir.path().join("dst"));
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&dst).unwrap();
+        fabric_jar(&src.join("library.jar"), "lib-a", "1.0");
+        fabric_jar(&dst.join("library.jar"), "lib-b", "1.0");
+
+        let report = merge(&src, &dst, "skip").await;
+        assert_eq!(report.skipped, ["library.jar"]);
+        assert_eq!(read_mod_metadata(&dst.join("library.jar")).unwrap().unwrap().id, "lib-b");
+
+        let report = merge(&src, &dst, "overwrite").await;
+        assert_eq!(report.replaced, ["library.jar"]);
+        assert_eq!(read_mod_metadata(&dst.join("library.jar")).unwrap().unwrap().id, "lib-a");
+    }
+
+    #[tokio::test]
+    async fn duplicates_are_grouped_by_mod_id() {
+        let dir = tempfile::tempdir().unwrap();
+        fabric_jar(&dir.path().join("a.jar"), "sodium", "0.5.7");
+        fabric_jar(&dir.path().join("b.jar"), "sodium", "0.5.8");
+        fabric_jar(&dir.path().join("c.jar"), "lithium", "0.11.2");
+
+        let groups = find_duplicate_mods(dir.path().to_string_lossy().to_string()).await.unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!((groups[0].mod_id.as_str(), groups[0].files.len()), ("sodium", 2));
+    }
+}
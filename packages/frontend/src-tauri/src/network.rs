@@ -0,0 +1,87 @@
+use std::time::Duration;
+use tokio::net::TcpStream;
+use crate::error::LauncherError;
+
+/// Default Minecraft server port; SRV records are only consulted for it
+pub const DEFAULT_MINECRAFT_PORT: u16 = 25565;
+
+/// Resolve `_minecraft._tcp.<host>` the way the vanilla client does
+///
+/// Returns the original host/port when there is no SRV record or the port is not the default.
+pub async fn resolve_minecraft_srv(host: &str, port: u16) -> (String, u16) {
+    if port != DEFAULT_MINECRAFT_PORT || host.parse::<std::net::IpAddr>().is_ok() {
+        return (host.to_string(), port);
+    }
+
+    let resolver = match hickory_resolver::TokioAsyncResolver::tokio_from_system_conf() {
+        Ok(resolver) => resolver,
+        Err(e) => {
+            eprintln!("[Network] ⚠️ Failed to create DNS resolver: {}", e);
+            return (host.to_string(), port);
+        }
+    };
+
+    match resolver.srv_lookup(format!("_minecraft._tcp.{}", host)).await {
+        Ok(lookup) => match lookup.iter().next() {
+            Some(srv) => {
+                let target = srv.target().to_utf8().trim_end_matches('.').to_string();
+                eprintln!("[Network] SRV {} -> {}:{}", host, target, srv.port());
+                (target, srv.port())
+            }
+            None => (host.to_string(), port),
+        },
+        Err(_) => (host.to_string(), port),
+    }
+}
+
+/// Check that a TCP connection to the server can be opened within the timeout
+///
+/// `Ok(false)` means the server did not answer in time; DNS failures and refused
+/// connections are reported as errors with distinct messages.
+#[tauri::command]
+pub async fn check_tcp_reachable(host: String, port: u16, timeout_ms: u64) -> Result<bool, LauncherError> {
+    let host = host.trim();
+    if host.is_empty() {
+        return Err(LauncherError::invalid_params("Host must not be empty"));
+    }
+
+    let timeout = Duration::from_millis(timeout_ms.max(1));
+    let (target_host, target_port) = resolve_minecraft_srv(host, port).await;
+
+    // Резолвим отдельно, чтобы отличать ошибку DNS от отказа в соединении
+    let addrs: Vec<_> = match tokio::time::timeout(
+        timeout,
+        tokio::net::lookup_host((target_host.as_str(), target_port)),
+    ).await {
+        Ok(Ok(addrs)) => addrs.collect(),
+        Ok(Err(e)) => return Err(LauncherError::network(format!("DNS resolution failed for '{}': {}", target_host, e))),
+        Err(_) => return Err(LauncherError::network(format!("DNS resolution timed out for '{}'", target_host))),
+    };
+
+    if addrs.is_empty() {
+        return Err(LauncherError::network(format!("DNS resolution failed for '{}': no addresses", target_host)));
+    }
+
+    let mut refused = None;
+    for addr in addrs {
+        match tokio::time::timeout(timeout, TcpStream::connect(addr)).await {
+            Ok(Ok(_)) => return Ok(true),
+            Ok(Err(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                refused = Some(format!("Connection refused by {}", addr));
+            }
+            Ok(Err(e)) => {
+                eprintln!("[Network] Failed to connect to {}: {}", addr, e);
+            }
+            Err(_) => {
+                eprintln!("[Network] Connection to {} timed out", addr);
+            }
+        }
+    }
+
+    // Хост жив, но порт закрыт - это ошибка конфигурации, а не "недоступен"
+    if let Some(e) = refused {
+        return Err(LauncherError::network(e));
+    }
+
+    Ok(false)
+}
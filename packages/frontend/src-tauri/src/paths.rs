@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+/// Per-user ALauncher data directory
+///
+/// Windows: `%LOCALAPPDATA%\ALauncher`, macOS: `~/Library/Application Support/ALauncher`,
+/// Linux: `~/.local/share/ALauncher`
+pub fn app_data_dir() -> Option<PathBuf> {
+    let base_dir = if cfg!(target_os = "windows") {
+        dirs::data_local_dir()
+    } else if cfg!(target_os = "macos") {
+        dirs::data_dir()
+    } else {
+        dirs::data_local_dir()
+    };
+
+    base_dir.map(|dir| dir.join("ALauncher"))
+}
+
+/// Launcher log directory, the same one `FileLogger` writes to
+pub fn logs_dir() -> Option<PathBuf> {
+    app_data_dir().map(|dir| dir.join("logs"))
+}
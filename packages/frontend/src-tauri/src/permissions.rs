@@ -0,0 +1,128 @@
+use std::path::Path;
+use serde::Serialize;
+use crate::error::LauncherError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PermissionProblem {
+    /// Directory the owner cannot write to (worlds, configs, logs fail to save)
+    DirectoryNotWritable,
+    /// File the owner cannot read
+    FileNotReadable,
+    /// Known executable (bundled Java, shell scripts) without the execute bit
+    MissingExecute,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionIssue {
+    pub path: String,
+    pub problem: PermissionProblem,
+    /// Current mode in octal, e.g. "0644"
+    pub mode: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PermissionReport {
+    pub issues: Vec<PermissionIssue>,
+    /// Paths whose mode was changed (only when `fix` is set)
+    pub fixed: Vec<String>,
+}
+
+/// Find (and with `fix`, repair) wrong file modes inside an instance
+///
+/// Repairs set dirs to 0755, files to 0644 and executables to 0755, never wider.
+/// Windows has no mode bits, the report is always empty there.
+#[tauri::command]
+pub async fn check_instance_permissions(game_dir: String, fix: bool) -> Result<PermissionReport, LauncherError> {
+    let root = Path::new(&game_dir).to_path_buf();
+    if !root.is_dir() {
+        return Err(LauncherError::not_found(format!("Game directory not found: {}", game_dir)));
+    }
+
+    tokio::task::spawn_blocking(move || scan_permissions(&root, fix))
+        .await
+        .map_err(|e| format!("Permission scan task failed: {}", e))?
+        .map_err(LauncherError::from)
+}
+
+#[cfg(unix)]
+fn scan_permissions(root: &Path, fix: bool) -> Result<PermissionReport, String> {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut report = PermissionReport::default();
+
+    // Симлинки не трогаем: chmod пошел бы по ссылке за пределы инстанса
+    for entry in walkdir::WalkDir::new(root).follow_links(false).into_iter().filter_map(|e| e.ok()) {
+        let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            continue;
+        }
+
+        let mode = match entry.metadata() {
+            Ok(metadata) => metadata.permissions().mode() & 0o7777,
+            Err(_) => continue,
+        };
+
+        let (problem, target_mode) = if file_type.is_dir() {
+            if mode & 0o700 == 0o700 {
+                continue;
+            }
+            (PermissionProblem::DirectoryNotWritable, 0o755)
+        } else if is_known_executable(entry.path()) {
+            if mode & 0o500 == 0o500 {
+                continue;
+            }
+            (PermissionProblem::MissingExecute, 0o755)
+        } else {
+            if mode & 0o400 != 0 {
+                continue;
+            }
+            (PermissionProblem::FileNotReadable, 0o644)
+        };
+
+        let path = entry.path().to_string_lossy().to_string();
+
+        if fix {
+            match fs::set_permissions(entry.path(), fs::Permissions::from_mode(target_mode)) {
+                Ok(()) => report.fixed.push(path.clone()),
+                Err(e) => eprintln!("[Permissions] ⚠️ Failed to fix {}: {}", path, e),
+            }
+        }
+
+        report.issues.push(PermissionIssue {
+            path,
+            problem,
+            mode: format!("{:04o}", mode),
+        });
+    }
+
+    eprintln!(
+        "[Permissions] {} issues found, {} fixed in {}",
+        report.issues.len(),
+        report.fixed.len(),
+        root.display()
+    );
+
+    Ok(report)
+}
+
+#[cfg(not(unix))]
+fn scan_permissions(_root: &Path, _fix: bool) -> Result<PermissionReport, String> {
+    Ok(PermissionReport::default())
+}
+
+/// Bundled Java binaries (`.../bin/java`, `lib/jspawnhelper`) and shell scripts
+#[cfg(unix)]
+fn is_known_executable(path: &Path) -> bool {
+    if path.extension().and_then(|s| s.to_str()) == Some("sh") {
+        return true;
+    }
+
+    let in_bin_dir = path.parent()
+        .and_then(|p| p.file_name())
+        .is_some_and(|name| name == "bin");
+    let name = path.file_name().and_then(|s| s.to_str()).unwrap_or_default();
+
+    // jspawnhelper лежит в lib/, без +x Java не может запускать процессы
+    name == "jspawnhelper" || (in_bin_dir && matches!(name, "java" | "keytool"))
+}
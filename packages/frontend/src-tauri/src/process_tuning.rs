@@ -0,0 +1,328 @@
+use std::process::{Child, Command};
+use serde::{Deserialize, Serialize};
+
+/// Scheduling priority of the game relative to other programs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProcessPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// Check requested core indices against the logical CPUs of this machine
+pub fn validate_cpu_affinity(cores: &[usize]) -> Result<(), String> {
+    if cores.is_empty() {
+        return Err("CPU affinity must list at least one core".to_string());
+    }
+
+    let cpu_count = crate::system_info::cpu_count();
+
+    if let Some(&bad) = cores.iter().find(|&&core| core >= cpu_count) {
+        return Err(format!(
+            "CPU core {} does not exist (available cores: 0-{})",
+            bad,
+            cpu_count - 1
+        ));
+    }
+
+    // Маска на Windows - одно слово, ядра за его пределами задать нельзя
+    if cfg!(target_os = "windows") {
+        if let Some(&bad) = cores.iter().find(|&&core| core >= usize::BITS as usize) {
+            return Err(format!("CPU core {} is outside the supported affinity mask", bad));
+        }
+    }
+
+    Ok(())
+}
+
+/// Heaps above this get the large-heap variant of `optimized_gc_args`
+const LARGE_HEAP_MB: u64 = 12 * 1024;
+
+/// Tuned G1GC flags for `-Xmx<heap_mb>m` ("Aikar's flags") plus GC thread counts
+///
+/// Never applied automatically: the UI offers them as a preset for `jvm_args`.
+/// `G1RSetUpdatingPauseTimePercent` from the original set is left out, Java 20+ no
+/// longer knows it.
+pub fn optimized_gc_args(cpu_count: usize, heap_mb: u64) -> Vec<String> {
+    let (new_size, max_new_size, region_size, reserve, occupancy) = if heap_mb > LARGE_HEAP_MB {
+        (40, 50, "16M", 15, 20)
+    } else {
+        (30, 40, "8M", 20, 15)
+    };
+
+    // Та же формула, что у HotSpot по умолчанию: все ядра до 8, дальше 5/8 от остальных
+    let cpu_count = cpu_count.max(1);
+    let parallel_threads = if cpu_count <= 8 { cpu_count } else { 8 + (cpu_count - 8) * 5 / 8 };
+    let concurrent_threads = ((parallel_threads + 2) / 4).max(1);
+
+    vec![
+        "-XX:+UseG1GC".to_string(),
+        "-XX:+ParallelRefProcEnabled".to_string(),
+        "-XX:MaxGCPauseMillis=200".to_string(),
+        "-XX:+UnlockExperimentalVMOptions".to_string(),
+        "-XX:+DisableExplicitGC".to_string(),
+        "-XX:+AlwaysPreTouch".to_string(),
+        format!("-XX:G1NewSizePercent={}", new_size),
+        format!("-XX:G1MaxNewSizePercent={}", max_new_size),
+        format!("-XX:G1HeapRegionSize={}", region_size),
+        format!("-XX:G1ReservePercent={}", reserve),
+        "-XX:G1HeapWastePercent=5".to_string(),
+        "-XX:G1MixedGCCountTarget=4".to_string(),
+        format!("-XX:InitiatingHeapOccupancyPercent={}", occupancy),
+        "-XX:G1MixedGCLiveThresholdPercent=90".to_string(),
+        "-XX:SurvivorRatio=32".to_string(),
+        "-XX:+PerfDisableSharedMem".to_string(),
+        "-XX:MaxTenuringThreshold=1".to_string(),
+        format!("-XX:ParallelGCThreads={}", parallel_threads),
+        format!("-XX:ConcGCThreads={}", concurrent_threads),
+    ]
+}
+
+/// `optimized_gc_args` for this machine and the given `-Xmx` in megabytes
+#[tauri::command]
+pub async fn get_optimized_jvm_args(max_ram_mb: u64) -> Vec<String> {
+    optimized_gc_args(crate::system_info::cpu_count(), max_ram_mb)
+}
+
+/// Pin a freshly spawned process to the given cores
+///
+/// Linux: `sched_setaffinity`, Windows: `SetProcessAffinityMask`.
+/// macOS has no affinity API, the request is ignored with a warning.
+pub fn apply_cpu_affinity(child: &Child, cores: &[usize]) -> Result<(), String> {
+    if set_affinity(child, cores)? {
+        eprintln!("[Launcher Backend] CPU affinity for PID {} set to cores {:?}", child.id(), cores);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(child: &Child, cores: &[usize]) -> Result<bool, String> {
+    // SAFETY: cpu_set_t is a plain bitmask, zeroed is a valid empty set
+    let result = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_ZERO(&mut set);
+        for &core in cores {
+            libc::CPU_SET(core, &mut set);
+        }
+        libc::sched_setaffinity(
+            child.id() as libc::pid_t,
+            std::mem::size_of::<libc::cpu_set_t>(),
+            &set,
+        )
+    };
+
+    if result != 0 {
+        return Err(format!("sched_setaffinity failed: {}", std::io::Error::last_os_error()));
+    }
+    Ok(true)
+}
+
+#[cfg(target_os = "windows")]
+fn set_affinity(child: &Child, cores: &[usize]) -> Result<bool, String> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::System::Threading::SetProcessAffinityMask;
+
+    let mask = cores.iter().fold(0usize, |mask, &core| mask | (1usize << core));
+
+    // SAFETY: the handle belongs to a live Child owned by the caller
+    let ok = unsafe { SetProcessAffinityMask(child.as_raw_handle() as _, mask) };
+    if ok == 0 {
+        return Err(format!("SetProcessAffinityMask failed: {}", std::io::Error::last_os_error()));
+    }
+    Ok(true)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn set_affinity(_child: &Child, _cores: &[usize]) -> Result<bool, String> {
+    eprintln!("[Launcher Backend] ⚠️ CPU affinity is not supported on this platform, ignoring");
+    Ok(false)
+}
+
+/// Change the scheduling priority of a freshly spawned game
+///
+/// Unix: `setpriority` on the game's process group (nice 10 for Low, -5 for High), so
+/// threads and child processes are covered too. Windows: `SetPriorityClass` with the
+/// below/above normal class. Raising the priority usually needs root on Unix and is
+/// reported as an error; `Normal` leaves the process untouched.
+pub fn apply_process_priority(child: &Child, priority: ProcessPriority) -> Result<(), String> {
+    if priority == ProcessPriority::Normal {
+        return Ok(());
+    }
+    set_priority(child, priority)?;
+    eprintln!("[Launcher Backend] Priority of PID {} set to {:?}", child.id(), priority);
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_priority(child: &Child, priority: ProcessPriority) -> Result<(), String> {
+    let nice = match priority {
+        ProcessPriority::Low => 10,
+        ProcessPriority::Normal => 0,
+        ProcessPriority::High => -5,
+    };
+
+    // Группа создана isolate_process_group с pgid == pid ребенка
+    // SAFETY: setpriority has no memory-safety preconditions
+    let result = unsafe { libc::setpriority(libc::PRIO_PGRP, child.id() as libc::id_t, nice) };
+    if result != 0 {
+        return Err(format!("setpriority failed: {}", std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn set_priority(child: &Child, priority: ProcessPriority) -> Result<(), String> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::System::Threading::{
+        SetPriorityClass, ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS,
+    };
+
+    let class = match priority {
+        ProcessPriority::Low => BELOW_NORMAL_PRIORITY_CLASS,
+        ProcessPriority::Normal => NORMAL_PRIORITY_CLASS,
+        ProcessPriority::High => ABOVE_NORMAL_PRIORITY_CLASS,
+    };
+
+    // SAFETY: the handle belongs to a live Child owned by the caller
+    let ok = unsafe { SetPriorityClass(child.as_raw_handle() as _, class) };
+    if ok == 0 {
+        return Err(format!("SetPriorityClass failed: {}", std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn set_priority(_child: &Child, _priority: ProcessPriority) -> Result<(), String> {
+    eprintln!("[Launcher Backend] ⚠️ Process priority is not supported on this platform, ignoring");
+    Ok(())
+}
+
+/// Start the game in its own process group so `kill_process_tree` can reach its children
+///
+/// Unix only. The game no longer shares the launcher's group, so a Ctrl+C or SIGHUP
+/// sent to the launcher's terminal is not delivered to it anymore; it is stopped only
+/// through `kill_game_process` or by closing it.
+pub fn isolate_process_group(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// Kill the game together with everything it spawned (wrapper scripts, Forge bootstrap, ...)
+///
+/// Unix: SIGKILL to the process group created by `isolate_process_group`.
+/// Windows: `taskkill /T /F`. Falls back to killing only the direct child.
+pub fn kill_process_tree(child: &mut Child) -> std::io::Result<()> {
+    if let Err(e) = kill_tree(child.id()) {
+        eprintln!("[Launcher Backend] ⚠️ Failed to kill process tree of PID {}: {}, killing the process only", child.id(), e);
+        return child.kill();
+    }
+    Ok(())
+}
+
+/// `kill_process_tree` for a game known only by PID (restored after a launcher restart)
+pub fn kill_process_tree_by_pid(pid: u32) -> std::io::Result<()> {
+    kill_tree(pid)
+}
+
+#[cfg(unix)]
+fn kill_tree(pid: u32) -> std::io::Result<()> {
+    // Группа создана с pgid == pid ребенка, отрицательный pid - вся группа
+    // SAFETY: kill has no memory-safety preconditions
+    let result = unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGKILL) };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn kill_tree(pid: u32) -> std::io::Result<()> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let status = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .creation_flags(CREATE_NO_WINDOW)
+        .status()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!("taskkill exited with {}", status)));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn kill_tree(pid: u32) -> std::io::Result<()> {
+    Err(std::io::Error::other(format!("process tree kill is not supported for PID {}", pid)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_affinity_is_validated_against_cpu_count() {
+        let cpu_count = crate::system_info::cpu_count();
+
+        assert!(validate_cpu_affinity(&[0]).is_ok());
+        assert!(validate_cpu_affinity(&(0..cpu_count).collect::<Vec<_>>()).is_ok());
+        assert!(validate_cpu_affinity(&[]).is_err());
+        assert!(validate_cpu_affinity(&[0, cpu_count]).unwrap_err().contains(&cpu_count.to_string()));
+    }
+
+    #[test]
+    fn gc_preset_scales_with_heap_and_cpu_count() {
+        let arg = |args: &[String], name: &str| -> Option<String> {
+            args.iter().find_map(|arg| arg.strip_prefix(&format!("-XX:{}=", name)).map(str::to_string))
+        };
+
+        let small = optimized_gc_args(4, 4096);
+        assert_eq!(small[0], "-XX:+UseG1GC");
+        assert_eq!(arg(&small, "G1HeapRegionSize").as_deref(), Some("8M"));
+        assert_eq!(arg(&small, "G1NewSizePercent").as_deref(), Some("30"));
+        assert_eq!(arg(&small, "ParallelGCThreads").as_deref(), Some("4"));
+        assert_eq!(arg(&small, "ConcGCThreads").as_deref(), Some("1"));
+        assert!(!small.iter().any(|arg| arg.contains("G1RSetUpdatingPauseTimePercent")));
+
+        let large = optimized_gc_args(16, 16384);
+        assert_eq!(arg(&large, "G1HeapRegionSize").as_deref(), Some("16M"));
+        assert_eq!(arg(&large, "InitiatingHeapOccupancyPercent").as_deref(), Some("20"));
+        // 8 + (16 - 8) * 5 / 8
+        assert_eq!(arg(&large, "ParallelGCThreads").as_deref(), Some("13"));
+        assert_eq!(arg(&large, "ConcGCThreads").as_deref(), Some("3"));
+
+        let unknown_cpus = optimized_gc_args(0, 2048);
+        assert_eq!(arg(&unknown_cpus, "ParallelGCThreads").as_deref(), Some("1"));
+        assert_eq!(arg(&unknown_cpus, "ConcGCThreads").as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn priority_defaults_to_normal() {
+        assert_eq!(ProcessPriority::default(), ProcessPriority::Normal);
+        assert_eq!(serde_json::from_str::<ProcessPriority>("\"Low\"").unwrap(), ProcessPriority::Low);
+        assert!(serde_json::from_str::<ProcessPriority>("\"Realtime\"").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn low_priority_renices_the_game_process_group() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("30");
+        isolate_process_group(&mut cmd);
+        let mut child = cmd.spawn().unwrap();
+
+        apply_process_priority(&child, ProcessPriority::Normal).unwrap();
+        apply_process_priority(&child, ProcessPriority::Low).unwrap();
+        // SAFETY: getpriority has no memory-safety preconditions
+        let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, child.id() as libc::id_t) };
+
+        let _ = kill_process_tree(&mut child);
+        let _ = child.wait();
+        assert_eq!(nice, 10);
+    }
+}
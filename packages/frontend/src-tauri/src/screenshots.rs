@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+use crate::archive;
+use crate::error::LauncherError;
+
+/// Zip `<game_dir>/screenshots/*.png` (optionally only those modified at or after
+/// `since_secs`, a unix timestamp) into `dest_zip` and return its path
+#[tauri::command]
+pub async fn export_screenshots(
+    game_dir: String,
+    dest_zip: String,
+    since_secs: Option<u64>,
+) -> Result<String, LauncherError> {
+    let screenshots_dir = Path::new(&game_dir).join("screenshots");
+    if !screenshots_dir.is_dir() {
+        return Err(LauncherError::not_found(format!("No screenshots directory in '{}'", game_dir)));
+    }
+
+    let since = since_secs.map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+
+    let entries = fs::read_dir(&screenshots_dir)
+        .map_err(|e| LauncherError::io("Failed to read screenshots directory", e))?;
+
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_png = path.extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+        if !path.is_file() || !is_png {
+            continue;
+        }
+
+        if let Some(since) = since {
+            let modified = entry.metadata().and_then(|m| m.modified());
+            if !matches!(modified, Ok(time) if time >= since) {
+                continue;
+            }
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+        files.push((path, name));
+    }
+
+    if files.is_empty() {
+        return Err(LauncherError::not_found("No screenshots match the selected period"));
+    }
+
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let dest = Path::new(&dest_zip).to_path_buf();
+    let count = files.len();
+    tokio::task::spawn_blocking(move || archive::write_zip(&dest, &files, false))
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))??;
+
+    eprintln!("[Screenshots] Exported {} screenshots to {}", count, dest_zip);
+    Ok(dest_zip)
+}
@@ -0,0 +1,337 @@
+use std::io;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use crate::error::LauncherError;
+use crate::network;
+
+/// Whole ping (connect, status and pong) must finish within this
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Status JSON with a favicon and a long modded server list stays well below this
+const MAX_PACKET_BYTES: usize = 2 * 1024 * 1024;
+
+/// -1: the server answers the status request whatever its own protocol version is
+const STATUS_PROTOCOL_VERSION: i32 = -1;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerStatus {
+    /// Message of the day as plain text, without `§` formatting codes
+    pub motd: String,
+    pub version: String,
+    pub players_online: u32,
+    pub players_max: u32,
+    pub latency_ms: u64,
+    /// `data:image/png;base64,...` as sent by the server
+    pub favicon: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusResponse {
+    #[serde(default)]
+    description: serde_json::Value,
+    #[serde(default)]
+    version: Option<StatusVersion>,
+    #[serde(default)]
+    players: Option<StatusPlayers>,
+    #[serde(default)]
+    favicon: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusVersion {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusPlayers {
+    online: u32,
+    max: u32,
+}
+
+/// Query a server's status with the Server List Ping protocol
+///
+/// The default port goes through the `_minecraft._tcp` SRV record, as in the client.
+/// Latency is the round trip of the ping packet, or of the status request for servers
+/// that close the connection before answering the ping.
+#[tauri::command]
+pub async fn ping_server(address: String, port: u16) -> Result<ServerStatus, LauncherError> {
+    let address = address.trim().to_string();
+    if address.is_empty() {
+        return Err(LauncherError::invalid_params("Server address must not be empty"));
+    }
+
+    let (host, port) = network::resolve_minecraft_srv(&address, port).await;
+    match tokio::time::timeout(PING_TIMEOUT, ping(&host, port)).await {
+        Ok(Ok(status)) => Ok(status),
+        Ok(Err(e)) => Err(LauncherError::network(format!("Failed to ping {}:{}: {}", host, port, e))),
+        Err(_) => Err(LauncherError::network(format!("Ping to {}:{} timed out", host, port))),
+    }
+}
+
+async fn ping(host: &str, port: u16) -> io::Result<ServerStatus> {
+    let mut stream = TcpStream::connect((host, port)).await?;
+    stream.set_nodelay(true)?;
+
+    // Handshake (next state 1 = status), затем пустой Status Request
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, 0x00);
+    write_varint(&mut handshake, STATUS_PROTOCOL_VERSION);
+    write_string(&mut handshake, host);
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, 1);
+
+    let started = Instant::now();
+    stream.write_all(&frame(&handshake)).await?;
+    stream.write_all(&frame(&[0x00])).await?;
+
+    let packet = read_packet(&mut stream).await?;
+    let status_latency = started.elapsed();
+    let mut body = packet.as_slice();
+    if read_varint(&mut body).await? != 0x00 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Unexpected status response packet"));
+    }
+    let json_len = read_varint(&mut body).await?;
+    let json = body.get(..json_len.max(0) as usize)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Truncated status response"))?;
+    let json = std::str::from_utf8(json)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let latency = ping_pong(&mut stream).await.unwrap_or(status_latency);
+    parse_status(json, latency)
+}
+
+/// Ping (0x01) with a payload the server must echo back
+async fn ping_pong(stream: &mut TcpStream) -> io::Result<Duration> {
+    // Как в клиенте: в качестве полезной нагрузки - текущее время
+    let payload = chrono::Utc::now().timestamp_millis();
+    let mut ping = vec![0x01];
+    ping.extend_from_slice(&payload.to_be_bytes());
+
+    let started = Instant::now();
+    stream.write_all(&frame(&ping)).await?;
+    let packet = read_packet(stream).await?;
+    if packet.len() != 9 || packet[0] != 0x01 || packet[1..] != payload.to_be_bytes() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Unexpected pong packet"));
+    }
+    Ok(started.elapsed())
+}
+
+fn parse_status(json: &str, latency: Duration) -> io::Result<ServerStatus> {
+    let response: StatusResponse = serde_json::from_str(json)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid status JSON: {}", e)))?;
+
+    let mut motd = String::new();
+    flatten_chat(&response.description, &mut motd);
+    let players = response.players.unwrap_or(StatusPlayers { online: 0, max: 0 });
+
+    Ok(ServerStatus {
+        motd: strip_formatting(&motd),
+        version: response.version.map(|version| strip_formatting(&version.name)).unwrap_or_default(),
+        players_online: players.online,
+        players_max: players.max,
+        latency_ms: latency.as_millis() as u64,
+        favicon: response.favicon,
+    })
+}
+
+/// Plain text of a chat component: a string, `{text, extra}` or an array of them
+fn flatten_chat(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::String(text) => out.push_str(text),
+        serde_json::Value::Array(parts) => parts.iter().for_each(|part| flatten_chat(part, out)),
+        serde_json::Value::Object(component) => {
+            if let Some(text) = component.get("text") {
+                flatten_chat(text, out);
+            }
+            if let Some(extra) = component.get("extra") {
+                flatten_chat(extra, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Drop legacy `§x` color and style codes
+fn strip_formatting(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Prefix a packet (id + data) with its VarInt length
+fn frame(packet: &[u8]) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(packet.len() + 5);
+    write_varint(&mut framed, packet.len() as i32);
+    framed.extend_from_slice(packet);
+    framed
+}
+
+async fn read_packet<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let len = read_varint(reader).await?;
+    if len <= 0 || len as usize > MAX_PACKET_BYTES {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid packet length {}", len)));
+    }
+    let mut packet = vec![0u8; len as usize];
+    reader.read_exact(&mut packet).await?;
+    Ok(packet)
+}
+
+fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+async fn read_varint<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<i32> {
+    let mut value = 0u32;
+    for i in 0..5 {
+        let byte = reader.read_u8().await?;
+        value |= ((byte & 0x7F) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidData, "VarInt is too long"))
+}
+
+fn write_string(buf: &mut Vec<u8>, value: &str) {
+    write_varint(buf, value.len() as i32);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Примеры из описания протокола
+    const VARINTS: &[(i32, &[u8])] = &[
+        (0, &[0x00]),
+        (1, &[0x01]),
+        (127, &[0x7f]),
+        (128, &[0x80, 0x01]),
+        (255, &[0xff, 0x01]),
+        (25565, &[0xdd, 0xc7, 0x01]),
+        (2097151, &[0xff, 0xff, 0x7f]),
+        (i32::MAX, &[0xff, 0xff, 0xff, 0xff, 0x07]),
+        (-1, &[0xff, 0xff, 0xff, 0xff, 0x0f]),
+        (i32::MIN, &[0x80, 0x80, 0x80, 0x80, 0x08]),
+    ];
+
+    #[tokio::test]
+    async fn varints_match_protocol_examples() {
+        for &(value, bytes) in VARINTS {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, value);
+            assert_eq!(buf, bytes, "{}", value);
+
+            let mut reader = bytes;
+            assert_eq!(read_varint(&mut reader).await.unwrap(), value);
+            assert!(reader.is_empty());
+        }
+
+        let mut too_long: &[u8] = &[0x80, 0x80, 0x80, 0x80, 0x80, 0x01];
+        assert_eq!(read_varint(&mut too_long).await.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let mut truncated: &[u8] = &[0x80];
+        assert!(read_varint(&mut truncated).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn packets_are_length_prefixed() {
+        assert_eq!(frame(&[0x00]), [0x01, 0x00]);
+
+        let mut framed: &[u8] = &[0x03, 0x01, 0x02, 0x03, 0xff];
+        assert_eq!(read_packet(&mut framed).await.unwrap(), [0x01, 0x02, 0x03]);
+        assert_eq!(framed, [0xff]);
+
+        let mut empty: &[u8] = &[0x00];
+        assert!(read_packet(&mut empty).await.is_err());
+        let mut huge = Vec::new();
+        write_varint(&mut huge, MAX_PACKET_BYTES as i32 + 1);
+        assert!(read_packet(&mut huge.as_slice()).await.is_err());
+    }
+
+    #[test]
+    fn status_json_is_flattened_and_stripped() {
+        let json = r#"{
+            "version": {"name": "§aPaper 1.20.4", "protocol": 765},
+            "players": {"max": 100, "online": 7, "sample": []},
+            "description": {"text": "§6Welcome ", "extra": [{"text": "to "}, "§lthe server"]},
+            "favicon": "data:image/png;base64,iVBORw0KGgo="
+        }"#;
+        let status = parse_status(json, Duration::from_millis(42)).unwrap();
+        assert_eq!(status.motd, "Welcome to the server");
+        assert_eq!(status.version, "Paper 1.20.4");
+        assert_eq!((status.players_online, status.players_max), (7, 100));
+        assert_eq!(status.latency_ms, 42);
+        assert_eq!(status.favicon.as_deref(), Some("data:image/png;base64,iVBORw0KGgo="));
+
+        // Старые серверы присылают description строкой и могут не прислать игроков
+        let legacy = parse_status(r#"{"description": "A Minecraft Server"}"#, Duration::ZERO).unwrap();
+        assert_eq!(legacy.motd, "A Minecraft Server");
+        assert_eq!((legacy.players_online, legacy.players_max), (0, 0));
+        assert_eq!(legacy.favicon, None);
+
+        assert!(parse_status("not json", Duration::ZERO).is_err());
+    }
+
+    /// Сервер, отвечающий на один статус-запрос и пинг
+    async fn serve_status(listener: TcpListener, status_json: &'static str) -> io::Result<()> {
+        let (mut stream, _) = listener.accept().await?;
+
+        let handshake = read_packet(&mut stream).await?;
+        let mut body = handshake.as_slice();
+        assert_eq!(read_varint(&mut body).await?, 0x00);
+        assert_eq!(read_varint(&mut body).await?, STATUS_PROTOCOL_VERSION);
+        let host_len = read_varint(&mut body).await? as usize;
+        assert_eq!(&body[..host_len], b"127.0.0.1");
+        // Порт и next state = 1 (status)
+        assert_eq!(body[host_len + 2..], [0x01]);
+
+        assert_eq!(read_packet(&mut stream).await?, [0x00]);
+        let mut response = vec![0x00];
+        write_string(&mut response, status_json);
+        stream.write_all(&frame(&response)).await?;
+
+        let ping = read_packet(&mut stream).await?;
+        assert_eq!((ping.len(), ping[0]), (9, 0x01));
+        stream.write_all(&frame(&ping)).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pings_a_local_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(serve_status(
+            listener,
+            r#"{"version":{"name":"1.20.4","protocol":765},"players":{"max":20,"online":3},"description":{"text":"Test"}}"#,
+        ));
+
+        let status = ping("127.0.0.1", port).await.unwrap();
+        server.await.unwrap().unwrap();
+        assert_eq!(status.motd, "Test");
+        assert_eq!(status.version, "1.20.4");
+        assert_eq!((status.players_online, status.players_max), (3, 20));
+    }
+
+    #[tokio::test]
+    async fn empty_address_is_rejected() {
+        assert!(matches!(ping_server("  ".to_string(), 25565).await, Err(LauncherError::InvalidParams { .. })));
+    }
+}
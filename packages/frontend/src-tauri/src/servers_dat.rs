@@ -0,0 +1,487 @@
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::fs_utils;
+use crate::error::LauncherError;
+
+/// One entry of the in-game multiplayer server list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerEntry {
+    pub name: String,
+    pub ip: String,
+    /// None = "prompt", the game's default
+    #[serde(default)]
+    pub accept_textures: Option<bool>,
+    /// Base64 PNG cached by the game; kept as-is when writing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+/// Read `<game_dir>/servers.dat`; a missing file is an empty list
+#[tauri::command]
+pub async fn read_servers_dat(game_dir: String) -> Result<Vec<ServerEntry>, LauncherError> {
+    let path = Path::new(&game_dir).join("servers.dat");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let root = read_nbt_file(&path)?;
+    Ok(servers_from_nbt(&root))
+}
+
+/// Write the server list to `<game_dir>/servers.dat`
+///
+/// Entries are matched to the existing file by address: the game's own fields the launcher
+/// doesn't know (`hidden`, `preventsChatReports`, ...) and cached icons are preserved.
+#[tauri::command]
+pub async fn write_servers_dat(game_dir: String, servers: Vec<ServerEntry>) -> Result<(), LauncherError> {
+    let path = Path::new(&game_dir).join("servers.dat");
+
+    let existing = if path.exists() {
+        read_nbt_file(&path)?
+    } else {
+        Tag::Compound(Vec::new())
+    };
+
+    let mut bytes = Vec::new();
+    write_named_tag(&mut bytes, "", &merge_servers(existing, servers));
+    Ok(fs_utils::write_atomic(&path, &bytes)?)
+}
+
+/// Replace the `servers` list of `root`, reusing the existing compound of each server
+fn merge_servers(root: Tag, servers: Vec<ServerEntry>) -> Tag {
+    let mut root_fields = match root {
+        Tag::Compound(fields) => fields,
+        _ => Vec::new(),
+    };
+    let existing = match root_fields.iter().position(|(name, _)| name == "servers") {
+        Some(index) => match root_fields.remove(index).1 {
+            Tag::List(_, items) => items,
+            _ => Vec::new(),
+        },
+        None => Vec::new(),
+    };
+
+    let servers: Vec<Tag> = servers.into_iter()
+        .filter(|s| !s.ip.trim().is_empty())
+        .map(|server| {
+            let previous = existing.iter().find(|old| {
+                old.get("ip").and_then(Tag::as_str).is_some_and(|ip| ip.eq_ignore_ascii_case(&server.ip))
+            });
+            server_to_nbt(&server, previous)
+        })
+        .collect();
+
+    root_fields.push(("servers".to_string(), Tag::List(10, servers)));
+    Tag::Compound(root_fields)
+}
+
+fn read_nbt_file(path: &Path) -> Result<Tag, String> {
+    let bytes = fs::read(path)
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let mut reader = NbtReader { data: &bytes, pos: 0 };
+    reader.read_root()
+        .map_err(|e| format!("Failed to parse {:?}: {}", path, e))
+}
+
+fn servers_from_nbt(root: &Tag) -> Vec<ServerEntry> {
+    let servers = match root.get("servers") {
+        Some(Tag::List(_, items)) => items,
+        _ => return Vec::new(),
+    };
+
+    servers.iter()
+        .filter_map(|server| {
+            let ip = server.get("ip")?.as_str()?.to_string();
+            Some(ServerEntry {
+                name: server.get("name").and_then(Tag::as_str).unwrap_or(&ip).to_string(),
+                accept_textures: match server.get("acceptTextures") {
+                    Some(Tag::Byte(b)) => Some(*b != 0),
+                    _ => None,
+                },
+                icon: server.get("icon").and_then(Tag::as_str).map(str::to_string),
+                ip,
+            })
+        })
+        .collect()
+}
+
+/// `previous` is this server's compound from the existing file, if any
+fn server_to_nbt(server: &ServerEntry, previous: Option<&Tag>) -> Tag {
+    let mut fields = match previous {
+        Some(Tag::Compound(fields)) => fields.clone(),
+        _ => Vec::new(),
+    };
+    let mut set = |key: &str, value: Option<Tag>| {
+        let index = fields.iter().position(|(k, _)| k == key);
+        match (index, value) {
+            (Some(index), Some(value)) => fields[index].1 = value,
+            (None, Some(value)) => fields.push((key.to_string(), value)),
+            (Some(index), None) => {
+                fields.remove(index);
+            }
+            (None, None) => {}
+        }
+    };
+
+    set("name", Some(Tag::String(server.name.clone())));
+    set("ip", Some(Tag::String(server.ip.clone())));
+    set("acceptTextures", server.accept_textures.map(|accept| Tag::Byte(accept as i8)));
+    // Без иконки во входных данных оставляем ту, что закэшировала игра
+    if let Some(icon) = &server.icon {
+        set("icon", Some(Tag::String(icon.clone())));
+    }
+    Tag::Compound(fields)
+}
+
+// ===== Minimal uncompressed NBT =====
+
+#[derive(Debug, Clone)]
+enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    /// Element type id + elements
+    List(u8, Vec<Tag>),
+    Compound(Vec<(String, Tag)>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => 1,
+            Tag::Short(_) => 2,
+            Tag::Int(_) => 3,
+            Tag::Long(_) => 4,
+            Tag::Float(_) => 5,
+            Tag::Double(_) => 6,
+            Tag::ByteArray(_) => 7,
+            Tag::String(_) => 8,
+            Tag::List(..) => 9,
+            Tag::Compound(_) => 10,
+            Tag::IntArray(_) => 11,
+            Tag::LongArray(_) => 12,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Tag> {
+        match self {
+            Tag::Compound(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+struct NbtReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl NbtReader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], String> {
+        let end = self.pos.checked_add(n).filter(|&end| end <= self.data.len())
+            .ok_or_else(|| "unexpected end of data".to_string())?;
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i16(&mut self) -> Result<i16, String> {
+        Ok(i16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64, String> {
+        Ok(i64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn len(&mut self) -> Result<usize, String> {
+        let len = self.i32()?;
+        usize::try_from(len).map_err(|_| format!("negative length {}", len))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = self.i16()? as u16 as usize;
+        Ok(decode_modified_utf8(self.take(len)?))
+    }
+
+    fn read_root(&mut self) -> Result<Tag, String> {
+        let id = self.u8()?;
+        if id != 10 {
+            return Err(format!("root tag is not a compound (type {})", id));
+        }
+        self.string()?;
+        self.payload(id, 0)
+    }
+
+    fn payload(&mut self, id: u8, depth: usize) -> Result<Tag, String> {
+        if depth > 512 {
+            return Err("NBT nesting too deep".to_string());
+        }
+
+        Ok(match id {
+            1 => Tag::Byte(self.u8()? as i8),
+            2 => Tag::Short(self.i16()?),
+            3 => Tag::Int(self.i32()?),
+            4 => Tag::Long(self.i64()?),
+            5 => Tag::Float(f32::from_bits(self.i32()? as u32)),
+            6 => Tag::Double(f64::from_bits(self.i64()? as u64)),
+            7 => {
+                let len = self.len()?;
+                Tag::ByteArray(self.take(len)?.iter().map(|&b| b as i8).collect())
+            }
+            8 => Tag::String(self.string()?),
+            9 => {
+                let element_id = self.u8()?;
+                let len = self.len()?;
+                let mut items = Vec::new();
+                for _ in 0..len {
+                    items.push(self.payload(element_id, depth + 1)?);
+                }
+                Tag::List(element_id, items)
+            }
+            10 => {
+                let mut fields = Vec::new();
+                loop {
+                    let field_id = self.u8()?;
+                    if field_id == 0 {
+                        break;
+                    }
+                    let name = self.string()?;
+                    fields.push((name, self.payload(field_id, depth + 1)?));
+                }
+                Tag::Compound(fields)
+            }
+            11 => {
+                let len = self.len()?;
+                Tag::IntArray((0..len).map(|_| self.i32()).collect::<Result<_, _>>()?)
+            }
+            12 => {
+                let len = self.len()?;
+                Tag::LongArray((0..len).map(|_| self.i64()).collect::<Result<_, _>>()?)
+            }
+            _ => return Err(format!("unknown tag type {}", id)),
+        })
+    }
+}
+
+/// NBT strings are Java's modified UTF-8 with a u16 length; longer strings are cut at a
+/// character boundary (the game itself refuses to write them)
+fn write_string(out: &mut Vec<u8>, s: &str) {
+    let mut encoded = Vec::with_capacity(s.len());
+    let mut buf = [0u16; 2];
+    for c in s.chars() {
+        let start = encoded.len();
+        for &unit in c.encode_utf16(&mut buf).iter() {
+            encode_modified_utf8_unit(&mut encoded, unit);
+        }
+        if encoded.len() > u16::MAX as usize {
+            encoded.truncate(start);
+            break;
+        }
+    }
+
+    out.extend_from_slice(&(encoded.len() as u16).to_be_bytes());
+    out.extend_from_slice(&encoded);
+}
+
+/// One UTF-16 code unit: NUL takes two bytes, surrogates are encoded separately (3 bytes each)
+fn encode_modified_utf8_unit(out: &mut Vec<u8>, unit: u16) {
+    match unit {
+        0x0001..=0x007F => out.push(unit as u8),
+        0x0000 | 0x0080..=0x07FF => {
+            out.push(0xC0 | (unit >> 6) as u8);
+            out.push(0x80 | (unit & 0x3F) as u8);
+        }
+        _ => {
+            out.push(0xE0 | (unit >> 12) as u8);
+            out.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+            out.push(0x80 | (unit & 0x3F) as u8);
+        }
+    }
+}
+
+/// Decode modified UTF-8; malformed sequences become U+FFFD
+fn decode_modified_utf8(bytes: &[u8]) -> String {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let continuation = |offset: usize| {
+            bytes.get(i + offset)
+                .filter(|&&c| c & 0xC0 == 0x80)
+                .map(|&c| (c & 0x3F) as u16)
+        };
+        let (unit, width) = if b < 0x80 {
+            (b as u16, 1)
+        } else if b & 0xE0 == 0xC0 {
+            match continuation(1) {
+                Some(c1) => (((b & 0x1F) as u16) << 6 | c1, 2),
+                None => (0xFFFD, 1),
+            }
+        } else if b & 0xF0 == 0xE0 {
+            match (continuation(1), continuation(2)) {
+                (Some(c1), Some(c2)) => (((b & 0x0F) as u16) << 12 | c1 << 6 | c2, 3),
+                _ => (0xFFFD, 1),
+            }
+        } else {
+            (0xFFFD, 1)
+        };
+        units.push(unit);
+        i += width;
+    }
+    String::from_utf16_lossy(&units)
+}
+
+fn write_named_tag(out: &mut Vec<u8>, name: &str, tag: &Tag) {
+    out.push(tag.id());
+    write_string(out, name);
+    write_payload(out, tag);
+}
+
+fn write_payload(out: &mut Vec<u8>, tag: &Tag) {
+    match tag {
+        Tag::Byte(v) => out.push(*v as u8),
+        Tag::Short(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Int(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Long(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Float(v) => out.extend_from_slice(&v.to_bits().to_be_bytes()),
+        Tag::Double(v) => out.extend_from_slice(&v.to_bits().to_be_bytes()),
+        Tag::ByteArray(v) => {
+            out.extend_from_slice(&(v.len() as i32).to_be_bytes());
+            out.extend(v.iter().map(|&b| b as u8));
+        }
+        Tag::String(s) => write_string(out, s),
+        Tag::List(element_id, items) => {
+            // Пустой список пишем с типом End, как это делает игра
+            out.push(if items.is_empty() { 0 } else { *element_id });
+            out.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            for item in items {
+                write_payload(out, item);
+            }
+        }
+        Tag::Compound(fields) => {
+            for (name, value) in fields {
+                write_named_tag(out, name, value);
+            }
+            out.push(0);
+        }
+        Tag::IntArray(v) => {
+            out.extend_from_slice(&(v.len() as i32).to_be_bytes());
+            for i in v {
+                out.extend_from_slice(&i.to_be_bytes());
+            }
+        }
+        Tag::LongArray(v) => {
+            out.extend_from_slice(&(v.len() as i32).to_be_bytes());
+            for i in v {
+                out.extend_from_slice(&i.to_be_bytes());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> Tag {
+        NbtReader { data: bytes, pos: 0 }.read_root().unwrap()
+    }
+
+    fn encode(root: &Tag) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_named_tag(&mut bytes, "", root);
+        bytes
+    }
+
+    fn entry(name: &str, ip: &str, accept_textures: Option<bool>) -> ServerEntry {
+        ServerEntry {
+            name: name.into(),
+            ip: ip.into(),
+            accept_textures,
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn keeps_unknown_server_tags_and_icons() {
+        let existing = Tag::Compound(vec![("servers".into(), Tag::List(10, vec![Tag::Compound(vec![
+            ("name".into(), Tag::String("Old".into())),
+            ("ip".into(), Tag::String("play.example.com".into())),
+            ("icon".into(), Tag::String("iVBOR".into())),
+            ("hidden".into(), Tag::Byte(1)),
+            ("preventsChatReports".into(), Tag::Byte(1)),
+            ("acceptTextures".into(), Tag::Byte(1)),
+        ])]))]);
+
+        let merged = merge_servers(existing, vec![
+            entry("New name", "PLAY.example.com", None),
+            entry("Other", "other.example.com", Some(false)),
+        ]);
+        let root = parse(&encode(&merged));
+        let servers = match root.get("servers") {
+            Some(Tag::List(10, servers)) => servers,
+            other => panic!("unexpected servers tag: {:?}", other),
+        };
+
+        assert_eq!(servers[0].get("name").and_then(Tag::as_str), Some("New name"));
+        assert_eq!(servers[0].get("icon").and_then(Tag::as_str), Some("iVBOR"));
+        assert!(matches!(servers[0].get("hidden"), Some(Tag::Byte(1))));
+        assert!(matches!(servers[0].get("preventsChatReports"), Some(Tag::Byte(1))));
+        // None = спрашивать, как в игре по умолчанию
+        assert!(servers[0].get("acceptTextures").is_none());
+        assert!(matches!(servers[1].get("acceptTextures"), Some(Tag::Byte(0))));
+        assert!(servers[1].get("hidden").is_none());
+
+        let entries = servers_from_nbt(&root);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].accept_textures, Some(false));
+    }
+
+    #[test]
+    fn strings_use_modified_utf8() {
+        let mut out = Vec::new();
+        write_string(&mut out, "a\0é😀");
+        assert_eq!(out, [0, 11, b'a', 0xC0, 0x80, 0xC3, 0xA9, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]);
+        assert_eq!(decode_modified_utf8(&out[2..]), "a\0é😀");
+    }
+
+    #[test]
+    fn long_strings_are_cut_at_a_character_boundary() {
+        let long = "é".repeat(40_000);
+        let mut out = Vec::new();
+        write_string(&mut out, &long);
+
+        let len = u16::from_be_bytes([out[0], out[1]]) as usize;
+        assert_eq!(len, 65_534);
+        assert_eq!(decode_modified_utf8(&out[2..]), "é".repeat(32_767));
+    }
+
+    #[test]
+    fn empty_file_round_trips_to_empty_list() {
+        let merged = merge_servers(Tag::Compound(Vec::new()), Vec::new());
+        assert!(servers_from_nbt(&parse(&encode(&merged))).is_empty());
+    }
+}
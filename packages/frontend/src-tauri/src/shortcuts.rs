@@ -0,0 +1,236 @@
+use std::fs;
+use std::path::Path;
+use crate::deep_link;
+use crate::error::LauncherError;
+
+/// Create a desktop shortcut that starts the launcher with `--launch-profile <profile_id>`
+///
+/// Linux: `<name>.desktop`, Windows: `<name>.lnk`. macOS: `<name>.webloc` opening
+/// `alauncher://launch/<profile_id>`, which Launch Services hands to the installed launcher
+/// (an existing instance gets it through the single-instance plugin).
+/// Returns the path of the created shortcut.
+#[tauri::command]
+pub async fn create_instance_shortcut(
+    profile_id: String,
+    name: String,
+    icon_path: Option<String>,
+) -> Result<String, LauncherError> {
+    if !deep_link::is_valid_profile_id(&profile_id) {
+        return Err(LauncherError::invalid_params(format!("Invalid profile id: {}", profile_id)));
+    }
+
+    let file_stem = sanitize_file_name(&name);
+    if file_stem.is_empty() {
+        return Err(LauncherError::invalid_params("Shortcut name must not be empty"));
+    }
+
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to determine launcher executable: {}", e))?;
+
+    let desktop_dir = dirs::desktop_dir()
+        .ok_or_else(|| "Failed to determine desktop directory".to_string())?;
+    fs::create_dir_all(&desktop_dir)
+        .map_err(|e| format!("Failed to create desktop directory: {}", e))?;
+
+    let icon = icon_path.filter(|p| Path::new(p).is_file());
+
+    let shortcut_path = if cfg!(target_os = "windows") {
+        let path = desktop_dir.join(format!("{}.lnk", file_stem));
+        write_windows_shortcut(&path, &exe, &profile_id, icon.as_deref())?;
+        path
+    } else if cfg!(target_os = "macos") {
+        let path = desktop_dir.join(format!("{}.webloc", file_stem));
+        fs::write(&path, webloc(&profile_id))
+            .map_err(|e| format!("Failed to write shortcut {:?}: {}", path, e))?;
+        path
+    } else {
+        let path = desktop_dir.join(format!("{}.desktop", file_stem));
+        write_linux_shortcut(&path, &exe, &profile_id, &name, icon.as_deref())?;
+        path
+    };
+
+    eprintln!("[Shortcuts] Created shortcut for profile {}: {}", profile_id, shortcut_path.display());
+
+    Ok(shortcut_path.to_string_lossy().to_string())
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>()
+        .trim()
+        .trim_matches('.')
+        .to_string()
+}
+
+fn write_linux_shortcut(
+    path: &Path,
+    exe: &Path,
+    profile_id: &str,
+    name: &str,
+    icon: Option<&str>,
+) -> Result<(), String> {
+    fs::write(path, desktop_entry(exe, profile_id, name, icon))
+        .map_err(|e| format!("Failed to write shortcut {:?}: {}", path, e))?;
+    make_executable(path)
+}
+
+/// Contents of a `.desktop` file; values are escaped per the Desktop Entry spec
+fn desktop_entry(exe: &Path, profile_id: &str, name: &str, icon: Option<&str>) -> String {
+    let display_name: String = name.chars().filter(|c| !c.is_control()).collect();
+    let exec = [exe.to_string_lossy().as_ref(), "--launch-profile", profile_id]
+        .iter()
+        .map(|arg| quote_exec_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut content = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec={}\nTerminal=false\nCategories=Game;\n",
+        escape_desktop_value(&display_name),
+        escape_desktop_value(&exec)
+    );
+    if let Some(icon) = icon {
+        content.push_str(&format!("Icon={}\n", escape_desktop_value(icon)));
+    }
+    content
+}
+
+/// Quote one `Exec=` argument: `"`, `` ` ``, `$` and `\` are backslash-escaped inside the quotes,
+/// `%` is doubled so it isn't taken for a field code
+fn quote_exec_arg(arg: &str) -> String {
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '%' => quoted.push_str("%%"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Escape a string value of a `.desktop` key (applied on top of `Exec=` quoting)
+fn escape_desktop_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// macOS `.webloc` pointing at the launcher's deep link for the profile
+fn webloc(profile_id: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n<dict>\n\t<key>URL</key>\n\t<string>{}</string>\n</dict>\n</plist>\n",
+        deep_link::launch_url(profile_id)
+    )
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("Failed to make shortcut executable: {}", e))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+fn write_windows_shortcut(
+    path: &Path,
+    exe: &Path,
+    profile_id: &str,
+    icon: Option<&str>,
+) -> Result<(), String> {
+    use std::process::Command;
+
+    // .lnk is a binary format, let the shell's COM object write it
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let mut script = format!(
+        "$s = (New-Object -ComObject WScript.Shell).CreateShortcut({}); $s.TargetPath = {}; $s.Arguments = {};",
+        quote(&path.to_string_lossy()),
+        quote(&exe.to_string_lossy()),
+        quote(&format!("--launch-profile {}", profile_id))
+    );
+    if let Some(icon) = icon {
+        script.push_str(&format!(" $s.IconLocation = {};", quote(icon)));
+    }
+    script.push_str(" $s.Save()");
+
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd.output()
+        .map_err(|e| format!("Failed to run powershell: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to create shortcut: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desktop_entry_quotes_exec_and_escapes_values() {
+        let exe = Path::new("/opt/My \"Games\"/$HOME/100%/a\\b/alauncher");
+        let entry = desktop_entry(exe, "pack-1", "My\nPack", Some("/icons/a\\b.png"));
+
+        assert!(entry.contains("Name=MyPack\n"));
+        assert!(entry.contains(
+            r#"Exec="/opt/My \\"Games\\"/\\$HOME/100%%/a\\\\b/alauncher" "--launch-profile" "pack-1""#
+        ));
+        assert!(entry.contains("Icon=/icons/a\\\\b.png\n"));
+    }
+
+    #[test]
+    fn desktop_entry_without_icon() {
+        let entry = desktop_entry(Path::new("/usr/bin/alauncher"), "pack", "Pack", None);
+        assert!(entry.contains("Exec=\"/usr/bin/alauncher\" \"--launch-profile\" \"pack\"\n"));
+        assert!(!entry.contains("Icon="));
+    }
+
+    #[test]
+    fn webloc_points_at_deep_link() {
+        assert!(webloc("pack-1").contains("<string>alauncher://launch/pack-1</string>"));
+    }
+
+    #[test]
+    fn file_names_are_sanitized() {
+        assert_eq!(sanitize_file_name(" a/b:c? "), "a_b_c_");
+        assert_eq!(sanitize_file_name("..."), "");
+    }
+}
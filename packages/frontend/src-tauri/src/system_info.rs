@@ -0,0 +1,146 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MemoryInfo {
+    pub total_mb: u64,
+    /// Memory that can be given to new processes without swapping
+    pub available_mb: u64,
+    /// `-Xmx` that fits right now, see `suggest_ram_for`
+    pub suggested_max_ram_mb: u32,
+}
+
+/// Total and currently available physical memory
+#[tauri::command]
+pub async fn get_memory_info() -> MemoryInfo {
+    tokio::task::spawn_blocking(memory_info)
+        .await
+        .unwrap_or_else(|_| memory_info())
+}
+
+pub fn memory_info() -> MemoryInfo {
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+
+    let total_mb = system.total_memory() / (1024 * 1024);
+    let available_mb = (system.available_memory() / (1024 * 1024)).min(total_mb);
+    MemoryInfo {
+        total_mb,
+        available_mb,
+        suggested_max_ram_mb: suggest_ram_for(total_mb, available_mb),
+    }
+}
+
+/// Number of logical CPUs, at least 1
+#[tauri::command]
+pub async fn get_cpu_count() -> usize {
+    cpu_count()
+}
+
+pub fn cpu_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Smallest heap `suggest_ram_from_total` recommends; modern versions struggle below it
+const MIN_SUGGESTED_RAM_MB: u32 = 2048;
+/// Largest heap `suggest_ram_from_total` recommends; more only lengthens GC pauses
+const MAX_SUGGESTED_RAM_MB: u32 = 8192;
+
+/// Recommended `-Xmx` for this machine, to pre-fill the RAM setting
+#[tauri::command]
+pub async fn suggest_ram_mb() -> u32 {
+    let total_mb = get_memory_info().await.total_mb;
+    suggest_ram_from_total(total_mb.min(u32::MAX as u64) as u32)
+}
+
+/// Half of the total memory, rounded down to 512 MB and clamped to 2-8 GiB
+///
+/// 4 GiB -> 2048, 8 GiB -> 4096, 16 GiB and more -> 8192. Unknown memory (0) gets the minimum.
+pub fn suggest_ram_from_total(total_mb: u32) -> u32 {
+    let half = total_mb / 2 / 512 * 512;
+    half.clamp(MIN_SUGGESTED_RAM_MB, MAX_SUGGESTED_RAM_MB)
+}
+
+/// Smallest heap ever suggested, even when almost nothing is available
+const MIN_FITTING_RAM_MB: u32 = 512;
+
+/// `suggest_ram_from_total`, lowered to what is available right now (rounded down to 512 MB)
+///
+/// This is the figure shown when the heap does not fit, e.g. after the JVM failed to reserve it.
+pub fn suggest_ram_for(total_mb: u64, available_mb: u64) -> u32 {
+    let total_mb = total_mb.min(u32::MAX as u64) as u32;
+    let available_mb = available_mb.min(u32::MAX as u64) as u32;
+    if total_mb == 0 {
+        return MIN_SUGGESTED_RAM_MB;
+    }
+
+    let fitting = (available_mb / 512 * 512).max(MIN_FITTING_RAM_MB);
+    suggest_ram_from_total(total_mb).min(fitting)
+}
+
+/// Start time (seconds since the Unix epoch) of a running process, None if it is gone
+///
+/// Zombies count as gone. Comparing start times tells a still running game from an
+/// unrelated process that reused its PID.
+pub fn process_start_time(pid: u32) -> Option<u64> {
+    let pid = sysinfo::Pid::from_u32(pid);
+    let mut system = sysinfo::System::new();
+    system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]), true);
+
+    let process = system.process(pid)?;
+    if process.status() == sysinfo::ProcessStatus::Zombie {
+        return None;
+    }
+    Some(process.start_time())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_half_of_total_memory_within_bounds() {
+        assert_eq!(suggest_ram_from_total(4 * 1024), 2048);
+        assert_eq!(suggest_ram_from_total(8 * 1024), 4096);
+        assert_eq!(suggest_ram_from_total(16 * 1024), 8192);
+        assert_eq!(suggest_ram_from_total(64 * 1024), 8192);
+        assert_eq!(suggest_ram_from_total(2 * 1024), 2048);
+        assert_eq!(suggest_ram_from_total(0), 2048);
+        // 12 GiB - 300 MB зарезервировано: половина округляется вниз до 512
+        assert_eq!(suggest_ram_from_total(12 * 1024 - 300), 5632);
+    }
+
+    #[test]
+    fn suggestion_is_capped_by_available_memory() {
+        assert_eq!(suggest_ram_for(16 * 1024, 12 * 1024), 8192);
+        assert_eq!(suggest_ram_for(16 * 1024, 3000), 2560);
+        assert_eq!(suggest_ram_for(8 * 1024, 100), 512);
+        assert_eq!(suggest_ram_for(0, 0), 2048);
+    }
+
+    #[test]
+    fn memory_info_is_consistent() {
+        let memory = memory_info();
+        assert!(memory.available_mb <= memory.total_mb);
+        assert_eq!(memory.suggested_max_ram_mb, suggest_ram_for(memory.total_mb, memory.available_mb));
+    }
+
+    #[test]
+    fn own_process_has_a_start_time() {
+        assert!(process_start_time(std::process::id()).is_some());
+    }
+
+    #[tokio::test]
+    async fn suggested_ram_follows_total_memory() {
+        let suggested = suggest_ram_mb().await;
+        assert_eq!(suggested, suggest_ram_from_total(memory_info().total_mb.min(u32::MAX as u64) as u32));
+        assert!((MIN_SUGGESTED_RAM_MB..=MAX_SUGGESTED_RAM_MB).contains(&suggested));
+        assert_eq!(suggested % 512, 0);
+
+        // Границы эвристики
+        assert_eq!(suggest_ram_from_total(5 * 1024 - 1), 2048);
+        assert_eq!(suggest_ram_from_total(10 * 1024), 5120);
+        assert_eq!(suggest_ram_from_total(u32::MAX), MAX_SUGGESTED_RAM_MB);
+    }
+}
@@ -0,0 +1,634 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use crate::error::LauncherError;
+
+/// Typed `<version>.json` of a Minecraft version
+///
+/// Both schemas are accepted: `arguments` (1.13+) and the legacy `minecraftArguments`
+/// string (1.12 and older). Legacy arguments end up in `arguments.game` as plain values,
+/// so callers never have to look at the original schema.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", from = "RawVersionJson")]
+pub struct VersionJson {
+    pub id: String,
+    pub main_class: String,
+    /// Parent version of loader profiles (Fabric, Forge)
+    pub inherits_from: Option<String>,
+    /// Version whose client jar is used; loader profiles run the parent's jar
+    pub jar: Option<String>,
+    #[serde(rename = "type")]
+    pub version_type: Option<String>,
+    pub asset_index: Option<AssetIndex>,
+    /// Asset index id of versions without `assetIndex`
+    pub assets: Option<String>,
+    pub arguments: Arguments,
+    pub libraries: Vec<Library>,
+    pub java_version: Option<JavaVersion>,
+    /// Arguments came from `minecraftArguments`; such a string replaces the parent's on merge
+    #[serde(skip)]
+    pub legacy_arguments: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Arguments {
+    #[serde(default)]
+    pub game: Vec<Argument>,
+    #[serde(default)]
+    pub jvm: Vec<Argument>,
+}
+
+/// Argument template, either unconditional or guarded by rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Argument {
+    Plain(String),
+    Conditional {
+        #[serde(default)]
+        rules: Vec<Rule>,
+        value: ArgumentValue,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArgumentValue {
+    Single(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    /// "allow" or "disallow"
+    pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<OsRule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features: Option<HashMap<String, bool>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetIndex {
+    pub id: String,
+    pub url: String,
+    pub sha1: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub total_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaVersion {
+    pub component: String,
+    pub major_version: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Library {
+    /// Maven coordinate, `group:artifact:version[:classifier]`
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub downloads: Option<LibraryDownloads>,
+    /// Maven repository of loader libraries that have no `downloads`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    /// Natives classifier per OS (pre-1.19), e.g. `"linux": "natives-linux"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub natives: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryDownloads {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact: Option<Artifact>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classifiers: Option<HashMap<String, Artifact>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Artifact {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub url: String,
+    #[serde(default)]
+    pub sha1: Option<String>,
+    #[serde(default)]
+    pub size: u64,
+}
+
+/// `<version>.json` as Mojang writes it, before the two argument schemas are merged
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawVersionJson {
+    id: String,
+    #[serde(default)]
+    main_class: String,
+    #[serde(default)]
+    inherits_from: Option<String>,
+    #[serde(default)]
+    jar: Option<String>,
+    #[serde(default, rename = "type")]
+    version_type: Option<String>,
+    #[serde(default)]
+    asset_index: Option<AssetIndex>,
+    #[serde(default)]
+    assets: Option<String>,
+    #[serde(default)]
+    arguments: Option<Arguments>,
+    #[serde(default)]
+    minecraft_arguments: Option<String>,
+    #[serde(default)]
+    libraries: Vec<Library>,
+    #[serde(default)]
+    java_version: Option<JavaVersion>,
+}
+
+impl From<RawVersionJson> for VersionJson {
+    fn from(raw: RawVersionJson) -> Self {
+        // До 1.13 аргументы игры - одна строка, JVM-аргументов в json нет
+        let legacy_arguments = raw.arguments.is_none() && raw.minecraft_arguments.is_some();
+        let arguments = match (raw.arguments, raw.minecraft_arguments) {
+            (Some(arguments), _) => arguments,
+            (None, Some(legacy)) => Arguments {
+                game: legacy.split_whitespace().map(|arg| Argument::Plain(arg.to_string())).collect(),
+                jvm: Vec::new(),
+            },
+            (None, None) => Arguments::default(),
+        };
+
+        VersionJson {
+            id: raw.id,
+            main_class: raw.main_class,
+            inherits_from: raw.inherits_from,
+            jar: raw.jar,
+            version_type: raw.version_type,
+            asset_index: raw.asset_index,
+            assets: raw.assets,
+            arguments,
+            libraries: raw.libraries,
+            java_version: raw.java_version,
+            legacy_arguments,
+        }
+    }
+}
+
+impl Library {
+    /// `group:artifact[:classifier]` without the version, used to match overrides
+    pub fn coordinate_key(&self) -> String {
+        let parts: Vec<&str> = self.name.split(':').collect();
+        match parts.as_slice() {
+            [group, artifact, _version, classifier, ..] => format!("{}:{}:{}", group, artifact, classifier),
+            [group, artifact, ..] => format!("{}:{}", group, artifact),
+            _ => self.name.clone(),
+        }
+    }
+
+    /// Path inside `libraries/`: `downloads.artifact.path`, or derived from the Maven coordinate
+    pub fn artifact_path(&self) -> Option<String> {
+        if let Some(path) = self.downloads.as_ref().and_then(|d| d.artifact.as_ref()).and_then(|a| a.path.clone()) {
+            return Some(path);
+        }
+        maven_path(&self.name, None)
+    }
+
+    /// Natives classifier for `os` (pre-1.19 `natives` map), `${arch}` resolved to 32/64
+    pub fn native_classifier(&self, os: &OsInfo) -> Option<String> {
+        let classifier = self.natives.as_ref()?.get(&os.name)?;
+        let bits = if os.arch == "x86" { "32" } else { "64" };
+        Some(classifier.replace("${arch}", bits))
+    }
+}
+
+/// `group/path/artifact/version/artifact-version[-classifier].jar` for a Maven coordinate
+///
+/// `net.fabricmc:tiny-mappings-parser:0.3.0` →
+/// `net/fabricmc/tiny-mappings-parser/0.3.0/tiny-mappings-parser-0.3.0.jar`.
+/// A classifier in the coordinate is used unless `classifier` overrides it.
+pub fn maven_path(coordinate: &str, classifier: Option<&str>) -> Option<String> {
+    let parts: Vec<&str> = coordinate.split(':').collect();
+    let (group, artifact, version) = match parts.as_slice() {
+        [group, artifact, version, ..] => (group, artifact, version),
+        _ => return None,
+    };
+    let file = match classifier.or(parts.get(3).copied()) {
+        Some(classifier) => format!("{}-{}-{}.jar", artifact, version, classifier),
+        None => format!("{}-{}.jar", artifact, version),
+    };
+    Some(format!("{}/{}/{}/{}", group.replace('.', "/"), artifact, version, file))
+}
+
+/// Minor and patch of a release id: "1.20.4" → (20, 4), "1.12" → (12, 0)
+///
+/// Loader suffixes are ignored ("1.20.1-forge-47.1.0" → (20, 1)). Snapshots and other
+/// ids that are not "1.x[.y]" give None.
+pub fn release_number(version_id: &str) -> Option<(u32, u32)> {
+    let mut parts = version_id.trim().split('.');
+    if parts.next()? != "1" {
+        return None;
+    }
+    let leading_number = |part: &str| -> Option<u32> {
+        let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok()
+    };
+    let minor = leading_number(parts.next()?)?;
+    let patch = parts.next().and_then(leading_number).unwrap_or(0);
+    Some((minor, patch))
+}
+
+/// The system that `os` rules are matched against
+#[derive(Debug, Clone)]
+pub struct OsInfo {
+    /// "windows", "osx" or "linux"
+    pub name: String,
+    /// Like Java's `os.version`: "10.0" on Windows, "14.4.1" on macOS, kernel release on Linux
+    pub version: String,
+    /// "x86", "x86_64" or "arm64"
+    pub arch: String,
+}
+
+impl OsInfo {
+    pub fn current() -> OsInfo {
+        static CURRENT: OnceLock<OsInfo> = OnceLock::new();
+        CURRENT.get_or_init(|| OsInfo {
+            name: match std::env::consts::OS {
+                "macos" => "osx".to_string(),
+                other => other.to_string(),
+            },
+            version: current_os_version().unwrap_or_default(),
+            arch: match std::env::consts::ARCH {
+                "aarch64" => "arm64".to_string(),
+                other => other.to_string(),
+            },
+        }).clone()
+    }
+}
+
+/// Launcher features that rules can ask for; all are off unless the launch enables them
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Features {
+    pub is_demo_user: bool,
+    pub has_custom_resolution: bool,
+    pub has_quick_plays_support: bool,
+    pub is_quick_play_singleplayer: bool,
+    pub is_quick_play_multiplayer: bool,
+    pub is_quick_play_realms: bool,
+}
+
+impl Features {
+    fn enabled(&self, name: &str) -> bool {
+        match name {
+            "is_demo_user" => self.is_demo_user,
+            "has_custom_resolution" => self.has_custom_resolution,
+            "has_quick_plays_support" => self.has_quick_plays_support,
+            "is_quick_play_singleplayer" => self.is_quick_play_singleplayer,
+            "is_quick_play_multiplayer" => self.is_quick_play_multiplayer,
+            "is_quick_play_realms" => self.is_quick_play_realms,
+            _ => false,
+        }
+    }
+}
+
+impl Rule {
+    /// A rule applies when every condition it has matches
+    fn applies(&self, os: &OsInfo, features: &Features) -> bool {
+        let os_matches = self.os.as_ref().map_or(true, |rule| {
+            rule.name.as_deref().map_or(true, |name| name == os.name)
+                && rule.arch.as_deref().map_or(true, |arch| arch == os.arch)
+                && rule.version.as_deref().map_or(true, |pattern| {
+                    // os.version - регулярное выражение, как в Java (поиск, а не полное совпадение)
+                    regex::Regex::new(pattern).is_ok_and(|re| re.is_match(&os.version))
+                })
+        });
+        let features_match = self.features.as_ref()
+            .map_or(true, |wanted| wanted.iter().all(|(name, value)| features.enabled(name) == *value));
+
+        os_matches && features_match
+    }
+}
+
+/// Evaluate Mojang `rules` the way the vanilla launcher does
+///
+/// No rules means allowed. Otherwise the result starts as disallowed and every
+/// applying rule overwrites it with its action, so the last match wins.
+pub fn rule_allows(rules: &[Rule], os: &OsInfo, features: &Features) -> bool {
+    if rules.is_empty() {
+        return true;
+    }
+
+    rules.iter()
+        .rev()
+        .find(|rule| rule.applies(os, features))
+        .is_some_and(|rule| rule.action == "allow")
+}
+
+#[cfg(target_os = "linux")]
+fn current_os_version() -> Option<String> {
+    fs::read_to_string("/proc/sys/kernel/osrelease").ok().map(|v| v.trim().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn current_os_version() -> Option<String> {
+    let output = std::process::Command::new("sw_vers").arg("-productVersion").output().ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// "Microsoft Windows [Version 10.0.19045.3803]" -> "10.0", как System.getProperty("os.version")
+#[cfg(windows)]
+fn current_os_version() -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let output = std::process::Command::new("cmd")
+        .args(["/C", "ver"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let version = text.split("Version ").nth(1)?.trim_end_matches(|c: char| c == ']' || c.is_whitespace());
+    Some(version.split('.').take(2).collect::<Vec<_>>().join("."))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn current_os_version() -> Option<String> {
+    None
+}
+
+impl Argument {
+    /// Values of the argument when its rules allow it
+    pub fn allowed_values(&self, os: &OsInfo, features: &Features) -> Vec<String> {
+        match self {
+            Argument::Plain(arg) => vec![arg.clone()],
+            Argument::Conditional { rules, value } if rule_allows(rules, os, features) => match value {
+                ArgumentValue::Single(arg) => vec![arg.clone()],
+                ArgumentValue::Many(args) => args.clone(),
+            },
+            Argument::Conditional { .. } => Vec::new(),
+        }
+    }
+}
+
+impl VersionJson {
+    /// Id of the version whose `<id>.jar` goes on the classpath
+    pub fn client_jar_id(&self) -> &str {
+        self.jar.as_deref().unwrap_or(&self.id)
+    }
+
+    /// Put `self` (the child) on top of its parent
+    ///
+    /// Scalars come from the child when set. New-style arguments are appended to the
+    /// parent's, a legacy `minecraftArguments` string replaces them. Child libraries go
+    /// first and drop parent libraries with the same coordinate, whatever their version.
+    fn merge_onto(self, parent: VersionJson) -> VersionJson {
+        let arguments = if self.legacy_arguments {
+            Arguments { game: self.arguments.game, jvm: parent.arguments.jvm }
+        } else {
+            let mut arguments = parent.arguments;
+            arguments.game.extend(self.arguments.game);
+            arguments.jvm.extend(self.arguments.jvm);
+            arguments
+        };
+
+        let overridden: HashSet<String> = self.libraries.iter().map(Library::coordinate_key).collect();
+        let mut libraries = self.libraries;
+        libraries.extend(parent.libraries.into_iter().filter(|lib| !overridden.contains(&lib.coordinate_key())));
+
+        let jar = self.jar.or(parent.jar).unwrap_or(parent.id);
+
+        VersionJson {
+            id: self.id,
+            jar: Some(jar),
+            main_class: if self.main_class.is_empty() { parent.main_class } else { self.main_class },
+            inherits_from: parent.inherits_from,
+            version_type: self.version_type.or(parent.version_type),
+            asset_index: self.asset_index.or(parent.asset_index),
+            assets: self.assets.or(parent.assets),
+            arguments,
+            libraries,
+            java_version: self.java_version.or(parent.java_version),
+            legacy_arguments: self.legacy_arguments,
+        }
+    }
+}
+
+/// Read and parse a version json from disk
+pub fn read_version_json(path: &str) -> Result<VersionJson, LauncherError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| LauncherError::io(&format!("Failed to read version json {}", path), e))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| LauncherError::invalid_params(format!("Failed to parse version json {}: {}", path, e)))
+}
+
+/// Load `<versions_dir>/<id>/<id>.json` and merge its `inheritsFrom` chain into one version
+///
+/// Loader profiles (Fabric, Forge, OptiFine) extend a vanilla version this way.
+/// A cycle in the chain is an error instead of an endless loop.
+pub fn resolve_version_json(version_id: &str, versions_dir: &Path) -> Result<VersionJson, LauncherError> {
+    let mut chain = Vec::new();
+    let mut seen = HashSet::new();
+    let mut next = Some(version_id.to_string());
+
+    while let Some(id) = next {
+        if !seen.insert(id.clone()) {
+            return Err(LauncherError::invalid_params(format!(
+                "Version {} has an inheritance cycle at {}",
+                version_id, id
+            )));
+        }
+
+        let path = versions_dir.join(&id).join(format!("{}.json", id));
+        let version = read_version_json(&path.to_string_lossy())?;
+        next = version.inherits_from.clone();
+        chain.push(version);
+    }
+
+    // Собираем от корневой (ванильной) версии к запрошенной
+    let mut resolved = chain.pop().expect("chain has at least the requested version");
+    while let Some(child) = chain.pop() {
+        resolved = child.merge_onto(resolved);
+    }
+    Ok(resolved)
+}
+
+/// Parse a version and everything it inherits from into a single merged version
+#[tauri::command]
+pub async fn resolve_version(version_id: String, versions_dir: String) -> Result<VersionJson, LauncherError> {
+    resolve_version_json(&version_id, Path::new(&versions_dir))
+}
+
+/// Parse `<version>.json` into typed fields for the frontend
+#[tauri::command]
+pub async fn parse_version_json(path: String) -> Result<VersionJson, LauncherError> {
+    read_version_json(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os(name: &str, version: &str) -> OsInfo {
+        OsInfo { name: name.to_string(), version: version.to_string(), arch: "x86_64".to_string() }
+    }
+
+    fn rules(json: &str) -> Vec<Rule> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        // Старый lwjgl: разрешён везде, кроме macOS
+        let everywhere_but_osx = rules(r#"[{"action":"allow"},{"action":"disallow","os":{"name":"osx"}}]"#);
+        let features = Features::default();
+        assert!(rule_allows(&everywhere_but_osx, &os("linux", "6.1.0"), &features));
+        assert!(rule_allows(&everywhere_but_osx, &os("windows", "10.0"), &features));
+        assert!(!rule_allows(&everywhere_but_osx, &os("osx", "14.4.1"), &features));
+
+        let only_osx = rules(r#"[{"action":"allow","os":{"name":"osx"}}]"#);
+        assert!(rule_allows(&only_osx, &os("osx", "14.4.1"), &features));
+        assert!(!rule_allows(&only_osx, &os("linux", "6.1.0"), &features));
+
+        assert!(rule_allows(&[], &os("linux", "6.1.0"), &features));
+    }
+
+    #[test]
+    fn os_version_is_a_regex_search() {
+        let not_on_old_macos = rules(r#"[{"action":"allow"},{"action":"disallow","os":{"name":"osx","version":"^10\\.5\\.\\d$"}}]"#);
+        let features = Features::default();
+        assert!(!rule_allows(&not_on_old_macos, &os("osx", "10.5.8"), &features));
+        assert!(rule_allows(&not_on_old_macos, &os("osx", "14.4.1"), &features));
+
+        let windows_10 = rules(r#"[{"action":"allow","os":{"name":"windows","version":"10\\."}}]"#);
+        assert!(rule_allows(&windows_10, &os("windows", "10.0"), &features));
+        assert!(!rule_allows(&windows_10, &os("windows", "6.1"), &features));
+
+        let broken_pattern = rules(r#"[{"action":"allow","os":{"version":"("}}]"#);
+        assert!(!rule_allows(&broken_pattern, &os("linux", "6.1.0"), &features));
+    }
+
+    #[test]
+    fn feature_rules_need_every_feature_to_match() {
+        let demo = rules(r#"[{"action":"allow","features":{"is_demo_user":true}}]"#);
+        let resolution = rules(r#"[{"action":"allow","features":{"has_custom_resolution":true,"is_demo_user":false}}]"#);
+        let linux = os("linux", "6.1.0");
+
+        let demo_user = Features { is_demo_user: true, ..Features::default() };
+        assert!(rule_allows(&demo, &linux, &demo_user));
+        assert!(!rule_allows(&demo, &linux, &Features::default()));
+
+        let custom_size = Features { has_custom_resolution: true, ..Features::default() };
+        assert!(rule_allows(&resolution, &linux, &custom_size));
+        assert!(!rule_allows(&resolution, &linux, &Features { is_demo_user: true, ..custom_size }));
+
+        let unknown = rules(r#"[{"action":"allow","features":{"is_brand_new_feature":true}}]"#);
+        assert!(!rule_allows(&unknown, &linux, &demo_user));
+    }
+
+    fn write_version(versions_dir: &Path, id: &str, json: &str) {
+        let dir = versions_dir.join(id);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(format!("{}.json", id)), json).unwrap();
+    }
+
+    fn plain_args(arguments: &[Argument]) -> Vec<&str> {
+        arguments.iter()
+            .filter_map(|arg| match arg {
+                Argument::Plain(value) => Some(value.as_str()),
+                Argument::Conditional { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn loader_profile_is_merged_onto_vanilla() {
+        let dir = tempfile::tempdir().unwrap();
+        write_version(dir.path(), "1.20.1", r#"{
+            "id": "1.20.1",
+            "mainClass": "net.minecraft.client.main.Main",
+            "assetIndex": {"id": "5", "sha1": "", "size": 0, "totalSize": 0, "url": ""},
+            "arguments": {"game": ["--username", "${auth_player_name}"], "jvm": ["-cp", "${classpath}"]},
+            "libraries": [
+                {"name": "org.ow2.asm:asm:9.3"},
+                {"name": "com.mojang:brigadier:1.1.8"}
+            ],
+            "javaVersion": {"component": "java-runtime-gamma", "majorVersion": 17}
+        }"#);
+        write_version(dir.path(), "fabric-loader-0.15.0-1.20.1", r#"{
+            "id": "fabric-loader-0.15.0-1.20.1",
+            "inheritsFrom": "1.20.1",
+            "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            "arguments": {"game": [], "jvm": ["-DFabricMcEmu= net.minecraft.client.main.Main "]},
+            "libraries": [
+                {"name": "org.ow2.asm:asm:9.6"},
+                {"name": "net.fabricmc:fabric-loader:0.15.0"}
+            ]
+        }"#);
+
+        let version = resolve_version_json("fabric-loader-0.15.0-1.20.1", dir.path()).unwrap();
+        assert_eq!(version.id, "fabric-loader-0.15.0-1.20.1");
+        assert_eq!(version.main_class, "net.fabricmc.loader.impl.launch.knot.KnotClient");
+        assert_eq!(version.client_jar_id(), "1.20.1");
+        assert_eq!(version.inherits_from, None);
+        assert_eq!(version.asset_index.map(|index| index.id), Some("5".to_string()));
+        assert_eq!(version.java_version.map(|java| java.major_version), Some(17));
+
+        // Библиотеки потомка первыми, родительская asm той же координаты отброшена
+        let libraries: Vec<&str> = version.libraries.iter().map(|lib| lib.name.as_str()).collect();
+        assert_eq!(libraries, ["org.ow2.asm:asm:9.6", "net.fabricmc:fabric-loader:0.15.0", "com.mojang:brigadier:1.1.8"]);
+
+        assert_eq!(plain_args(&version.arguments.game), ["--username", "${auth_player_name}"]);
+        assert_eq!(plain_args(&version.arguments.jvm), ["-cp", "${classpath}", "-DFabricMcEmu= net.minecraft.client.main.Main "]);
+    }
+
+    #[test]
+    fn legacy_arguments_replace_the_parents() {
+        let dir = tempfile::tempdir().unwrap();
+        write_version(dir.path(), "1.12.2", r#"{
+            "id": "1.12.2",
+            "mainClass": "net.minecraft.client.main.Main",
+            "minecraftArguments": "--username ${auth_player_name} --version ${version_name}"
+        }"#);
+        write_version(dir.path(), "1.12.2-forge", r#"{
+            "id": "1.12.2-forge",
+            "inheritsFrom": "1.12.2",
+            "mainClass": "net.minecraft.launchwrapper.Launch",
+            "minecraftArguments": "--username ${auth_player_name} --tweakClass net.minecraftforge.fml.common.launcher.FMLTweaker"
+        }"#);
+
+        let version = resolve_version_json("1.12.2-forge", dir.path()).unwrap();
+        assert!(version.legacy_arguments);
+        assert_eq!(
+            plain_args(&version.arguments.game),
+            ["--username", "${auth_player_name}", "--tweakClass", "net.minecraftforge.fml.common.launcher.FMLTweaker"]
+        );
+    }
+
+    #[test]
+    fn inheritance_cycles_and_missing_parents_are_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        write_version(dir.path(), "a", r#"{"id": "a", "inheritsFrom": "b"}"#);
+        write_version(dir.path(), "b", r#"{"id": "b", "inheritsFrom": "a"}"#);
+        let cycle = resolve_version_json("a", dir.path()).unwrap_err();
+        assert!(cycle.to_string().contains("inheritance cycle"), "{}", cycle);
+
+        write_version(dir.path(), "orphan", r#"{"id": "orphan", "inheritsFrom": "1.20.4"}"#);
+        assert!(matches!(resolve_version_json("orphan", dir.path()), Err(LauncherError::NotFound { .. })));
+    }
+}
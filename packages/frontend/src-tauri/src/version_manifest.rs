@@ -0,0 +1,279 @@
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use crate::{fs_utils, paths};
+use crate::version_json::VersionJson;
+use crate::error::LauncherError;
+
+pub const VERSION_MANIFEST_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+
+/// How long a fetched manifest is reused before asking Mojang again
+const MANIFEST_TTL: Duration = Duration::from_secs(5 * 60);
+
+type CachedManifest = (Instant, Arc<VersionManifest>);
+
+static MANIFEST_CACHE: OnceLock<Mutex<Option<CachedManifest>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatestVersions {
+    pub release: String,
+    pub snapshot: String,
+}
+
+/// One entry of `version_manifest_v2.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionEntry {
+    pub id: String,
+    /// "release", "snapshot", "old_beta", "old_alpha"
+    #[serde(rename = "type")]
+    pub version_type: String,
+    pub url: String,
+    pub time: DateTime<Utc>,
+    pub release_time: DateTime<Utc>,
+    #[serde(default)]
+    pub sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionManifest {
+    pub latest: LatestVersions,
+    pub versions: Vec<VersionEntry>,
+}
+
+impl VersionManifest {
+    pub fn find(&self, id: &str) -> Option<&VersionEntry> {
+        self.versions.iter().find(|v| v.id == id)
+    }
+}
+
+/// Fetch Mojang's version manifest, reusing a recent copy when available
+pub async fn fetch_version_manifest() -> Result<Arc<VersionManifest>, String> {
+    let cache = MANIFEST_CACHE.get_or_init(|| Mutex::new(None));
+    if let Ok(cache) = cache.lock() {
+        if let Some((fetched_at, manifest)) = cache.as_ref() {
+            if fetched_at.elapsed() < MANIFEST_TTL {
+                return Ok(manifest.clone());
+            }
+        }
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client.get(VERSION_MANIFEST_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Version manifest is unavailable (offline?): {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Version manifest request failed: HTTP {}", response.status()));
+    }
+
+    let bytes = response.bytes()
+        .await
+        .map_err(|e| format!("Failed to read version manifest: {}", e))?;
+    let manifest: VersionManifest = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("Failed to parse version manifest: {}", e))?;
+    let manifest = Arc::new(manifest);
+
+    if let Ok(mut cache) = cache.lock() {
+        *cache = Some((Instant::now(), manifest.clone()));
+    }
+
+    // Копия на диске для работы без сети
+    if let Some(path) = disk_cache_path() {
+        if let Err(e) = fs_utils::write_atomic(&path, &bytes) {
+            eprintln!("[Versions] ⚠️ Failed to cache version manifest: {}", e);
+        }
+    }
+
+    Ok(manifest)
+}
+
+fn disk_cache_path() -> Option<PathBuf> {
+    paths::app_data_dir().map(|dir| dir.join("version_manifest_v2.json"))
+}
+
+/// Like `fetch_version_manifest`, but falls back to the last copy saved on disk when offline
+///
+/// The flag is `true` when the returned manifest is that stale copy.
+pub async fn fetch_version_manifest_or_cached() -> Result<(Arc<VersionManifest>, bool), String> {
+    let error = match fetch_version_manifest().await {
+        Ok(manifest) => return Ok((manifest, false)),
+        Err(e) => e,
+    };
+
+    let cached = disk_cache_path()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|bytes| serde_json::from_slice::<VersionManifest>(&bytes).ok());
+
+    match cached {
+        Some(manifest) => {
+            eprintln!("[Versions] ⚠️ {}, using cached version manifest", error);
+            Ok((Arc::new(manifest), true))
+        }
+        None => Err(error),
+    }
+}
+
+/// The disk copy if it was saved less than `max_age` ago
+fn fresh_disk_manifest(max_age: Duration) -> Option<VersionManifest> {
+    let path = disk_cache_path()?;
+    let age = fs::metadata(&path).ok()?.modified().ok()?.elapsed().ok()?;
+    if age > max_age {
+        return None;
+    }
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionList {
+    pub versions: Vec<VersionEntry>,
+    /// Mojang was unreachable and this is an outdated cached copy
+    pub stale: bool,
+}
+
+/// All Minecraft versions from Mojang's manifest
+///
+/// A disk copy younger than `max_age_secs` (default 5 minutes) is used without asking
+/// Mojang; when the network is down an older copy is returned with `stale` set.
+#[tauri::command]
+pub async fn fetch_version_list(max_age_secs: Option<u64>) -> Result<VersionList, LauncherError> {
+    let max_age = max_age_secs.map(Duration::from_secs).unwrap_or(MANIFEST_TTL);
+    if let Some(manifest) = fresh_disk_manifest(max_age) {
+        return Ok(VersionList { versions: manifest.versions, stale: false });
+    }
+
+    let (manifest, stale) = fetch_version_manifest_or_cached()
+        .await
+        .map_err(LauncherError::network)?;
+    Ok(VersionList { versions: manifest.versions.clone(), stale })
+}
+
+/// Download a version json from the manifest into `dest`
+///
+/// The file is checked against the SHA-1 listed for `url` in the manifest (when the
+/// URL is listed) and must parse as a version json before it replaces `dest`.
+#[tauri::command]
+pub async fn fetch_version_json(url: String, dest: String) -> Result<String, LauncherError> {
+    let expected_sha1 = match fetch_version_manifest_or_cached().await {
+        Ok((manifest, _)) => manifest.versions.iter().find(|v| v.url == url).and_then(|v| v.sha1.clone()),
+        Err(e) => {
+            eprintln!("[Versions] ⚠️ {}, downloading {} without a checksum", e, url);
+            None
+        }
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client.get(&url)
+        .send()
+        .await
+        .map_err(|e| LauncherError::network(format!("Failed to download version json: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(LauncherError::network(format!("Version json request failed: HTTP {}", response.status())));
+    }
+    let bytes = response.bytes()
+        .await
+        .map_err(|e| LauncherError::network(format!("Failed to read version json: {}", e)))?;
+
+    if let Some(expected) = expected_sha1 {
+        let actual = hex::encode(Sha1::digest(&bytes));
+        if !actual.eq_ignore_ascii_case(&expected) {
+            return Err(LauncherError::network(format!(
+                "Version json checksum mismatch: expected {}, got {}",
+                expected, actual
+            )));
+        }
+    }
+
+    serde_json::from_slice::<VersionJson>(&bytes)
+        .map_err(|e| LauncherError::invalid_params(format!("Downloaded file is not a version json: {}", e)))?;
+
+    fs_utils::write_atomic(Path::new(&dest), &bytes)?;
+    Ok(dest)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionUpdate {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+}
+
+/// Compare `current_version` with the latest Mojang `release` or `snapshot`
+#[tauri::command]
+pub async fn check_version_update(current_version: String, channel: String) -> Result<VersionUpdate, LauncherError> {
+    let manifest = fetch_version_manifest().await?;
+
+    let latest_id = match channel.as_str() {
+        "release" => &manifest.latest.release,
+        "snapshot" => &manifest.latest.snapshot,
+        _ => return Err(LauncherError::invalid_params(format!(
+            "Unknown channel: {} (expected \"release\" or \"snapshot\")",
+            channel
+        ))),
+    };
+
+    let latest = manifest.find(latest_id)
+        .ok_or_else(|| format!("Latest version {} is missing from the manifest", latest_id))?;
+    let current = manifest.find(&current_version)
+        .ok_or_else(|| format!("Unknown Minecraft version: {}", current_version))?;
+
+    // Сравниваем по дате релиза: id вроде "24w14a" и "1.20.5" напрямую не сравнить
+    Ok(VersionUpdate {
+        update_available: latest.release_time > current.release_time,
+        latest_version: latest.id.clone(),
+        current_version,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionSummary {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub version_type: String,
+    pub release_time: DateTime<Utc>,
+    /// Latest release, the version the UI should preselect
+    pub recommended: bool,
+    pub latest_snapshot: bool,
+    /// The list comes from the cached manifest because Mojang was unreachable
+    pub stale: bool,
+}
+
+/// Minecraft versions from Mojang's manifest, newest first
+///
+/// Releases are always included; snapshots and `old_beta`/`old_alpha` only on request.
+#[tauri::command]
+pub async fn list_minecraft_versions(include_snapshots: bool, include_old: bool) -> Result<Vec<VersionSummary>, LauncherError> {
+    let (manifest, stale) = fetch_version_manifest_or_cached().await?;
+
+    let mut versions: Vec<VersionSummary> = manifest.versions.iter()
+        .filter(|v| match v.version_type.as_str() {
+            "release" => true,
+            "snapshot" => include_snapshots,
+            "old_beta" | "old_alpha" => include_old,
+            _ => false,
+        })
+        .map(|v| VersionSummary {
+            id: v.id.clone(),
+            version_type: v.version_type.clone(),
+            release_time: v.release_time,
+            recommended: v.id == manifest.latest.release,
+            latest_snapshot: v.id == manifest.latest.snapshot,
+            stale,
+        })
+        .collect();
+
+    versions.sort_by_key(|v| std::cmp::Reverse(v.release_time));
+    Ok(versions)
+}
@@ -71,14 +71,28 @@ interface GameProcess {
     stderrOutput?: string;
     stdoutOutput?: string;
   };
-  // Track intervals for cleanup
-  monitorInterval?: NodeJS.Timeout;
-  cleanupTimeout?: NodeJS.Timeout;
+}
+
+/**
+ * Payload события game-exited
+ */
+interface GameExitedEvent {
+  process_id: string;
+  exit_code?: number | null;
+  crashed: boolean;
+  duration_ms: number;
+  hs_err_log?: string;
+  crash_report?: string;
+  crash_report_excerpt?: string;
+  error?: string;
+  errorCode?: string;
 }
 
 class GameLauncherService {
   private static activeProcesses: Map<string, GameProcess> = new Map();
   private static crashMonitoringInterval: NodeJS.Timeout | null = null;
+  private static exitListener: Promise<void> | null = null;
+  private static earlyExits: Map<string, GameExitedEvent> = new Map();
 
   /**
    * Инициализация сервиса
@@ -87,6 +101,11 @@ class GameLauncherService {
     // Начать мониторинг активных процессов
     this.startCrashMonitoring();
 
+    // Подписка до первого запуска, чтобы не пропустить быстрое завершение игры
+    this.listenForExits().catch((error) => {
+      console.error('[GameLauncher] Failed to listen for game-exited:', error);
+    });
+
     // Добавить обработчик для отслеживания крашей при закрытии окна
     if (typeof window !== 'undefined') {
       window.addEventListener('beforeunload', () => {
@@ -139,12 +158,18 @@ class GameLauncherService {
       const gameDir = await path.join(updatesDir, profile.clientDirectory || profile.version);
       const assetsDir = await path.join(updatesDir, 'assets');
 
+      const offline = !(accessToken || session);
+
       // Подготовка параметров запуска (все поля в snake_case как в Rust)
       const launchParams = {
         profile_id: profile.id,
+        version: profile.version,
+        asset_index: profile.assetIndex || '',
         username: username || 'Player',
-        uuid: this.generateUUID(),
-        access_token: accessToken || session || 'demo',
+        // Без токена - офлайн-запуск: UUID бэкенд выводит из имени, как ванильный сервер
+        uuid: offline ? '' : this.generateUUID(),
+        access_token: accessToken || session || '',
+        offline,
         game_dir: gameDir,
         assets_dir: assetsDir,
         resolution: {
@@ -154,7 +179,7 @@ class GameLauncherService {
         full_screen: fullScreen || false,
         java_path: javaPath || 'java',
         java_version: profile.jvmVersion || '17',
-        ram: String(ram || '2048'),
+        max_ram: String(ram || '2048'),
         jvm_args: profile.jvmArgs || [],
         client_args: profile.clientArgs || [],
         main_class: profile.mainClass,
@@ -259,95 +284,86 @@ class GameLauncherService {
 
   /**
    * Мониторинг конкретного процесса
+   * Завершение приходит событием game-exited: бэкенд сам убирает процесс из реестра,
+   * после этого check_game_process отвечает ProcessNotFound
    */
   private static async monitorProcess(processId: string) {
     const process = this.activeProcesses.get(processId);
     if (!process) return;
 
     try {
-      // Проверяем статус процесса каждую секунду
-      const checkInterval = setInterval(async () => {
-        try {
-          const invokeFn = await getInvoke();
-          const status = await invokeFn<{
-            running: boolean;
-            exitCode?: number;
-            stdout?: string;
-            stderr?: string;
-          }>('check_game_process', { processId });
-
-          if (!status.running) {
-            // Clear intervals
-            clearInterval(checkInterval);
-            if (process.cleanupTimeout) {
-              clearTimeout(process.cleanupTimeout);
-            }
-
-            // Процесс завершился
-            process.status = 'stopped';
-            process.exitCode = status.exitCode;
-
-            // Если exitCode не 0, это краш
-            if (status.exitCode && status.exitCode !== 0) {
-              process.status = 'crashed';
-              process.crashReport = {
-                exitCode: status.exitCode,
-                errorMessage: `Process exited with code ${status.exitCode}`,
-                stderrOutput: status.stderr,
-                stdoutOutput: status.stdout,
-              };
-
-              // Отправляем отчет о краше
-              await this.reportCrash(process);
-            }
+      await this.listenForExits();
+    } catch (error) {
+      console.error(`Failed to monitor process ${processId}:`, error);
+      this.activeProcesses.delete(processId);
+      return;
+    }
 
-            // Remove from active processes after completion
-            this.activeProcesses.delete(processId);
+    // Игра могла завершиться раньше, чем процесс был зарегистрирован
+    const exited = this.earlyExits.get(processId);
+    if (exited) {
+      this.earlyExits.delete(processId);
+      await this.handleProcessExit(exited);
+    }
+  }
 
-            await ErrorLoggerService.logInfo('GAME_PROCESS_ENDED', {
-              component: 'GameLauncher',
-              action: 'process_ended',
-              processId: processId,
-              exitCode: status.exitCode,
-              crashed: process.status === 'crashed',
-            });
+  /**
+   * Один обработчик game-exited на все процессы
+   */
+  private static listenForExits(): Promise<void> {
+    if (!this.exitListener) {
+      this.exitListener = import('@tauri-apps/api/event')
+        .then(({ listen }) => listen<GameExitedEvent>('game-exited', (event) => {
+          this.handleProcessExit(event.payload).catch((error) => {
+            console.error(`Error handling exit of process ${event.payload.process_id}:`, error);
+          });
+        }))
+        .then(() => undefined)
+        .catch((error) => {
+          this.exitListener = null;
+          throw error;
+        });
+    }
+    return this.exitListener;
+  }
 
-            console.log(`[GameLauncher] Process ${processId} ended with code ${status.exitCode}.`);
-            console.log(`[GameLauncher] stdout:`, status.stdout);
-            console.error(`[GameLauncher] stderr:`, status.stderr);
-          }
-        } catch (error) {
-          console.error(`Error monitoring process ${processId}:`, error);
-          // Clear intervals on error
-          clearInterval(checkInterval);
-          if (process.cleanupTimeout) {
-            clearTimeout(process.cleanupTimeout);
-          }
-          // Remove from active processes
-          this.activeProcesses.delete(processId);
-        }
-      }, 1000);
+  /**
+   * Обработка события game-exited
+   */
+  private static async handleProcessExit(exited: GameExitedEvent) {
+    const processId = exited.process_id;
+    const process = this.activeProcesses.get(processId);
+    if (!process) {
+      this.earlyExits.set(processId, exited);
+      return;
+    }
 
-      // Store interval reference for cleanup
-      process.monitorInterval = checkInterval;
+    process.status = exited.crashed ? 'crashed' : 'stopped';
+    process.exitCode = exited.exit_code ?? undefined;
 
-      // Очистка через 10 минут (если процесс все еще работает)
-      const cleanupTimeout = setTimeout(() => {
-        clearInterval(checkInterval);
-        // Remove from active processes if still present
-        if (this.activeProcesses.has(processId)) {
-          console.warn(`[GameLauncher] Process ${processId} timeout, cleaning up`);
-          this.activeProcesses.delete(processId);
-        }
-      }, 10 * 60 * 1000);
+    if (exited.crashed) {
+      process.crashReport = {
+        exitCode: exited.exit_code ?? -1,
+        errorMessage: exited.error
+          ?? (exited.exit_code != null ? `Process exited with code ${exited.exit_code}` : 'Process terminated by a signal'),
+        stackTrace: exited.crash_report_excerpt,
+      };
 
-      // Store timeout reference
-      process.cleanupTimeout = cleanupTimeout;
-    } catch (error) {
-      console.error(`Failed to monitor process ${processId}:`, error);
-      // Clean up on failure
-      this.activeProcesses.delete(processId);
+      // Отправляем отчет о краше
+      await this.reportCrash(process);
     }
+
+    this.activeProcesses.delete(processId);
+
+    await ErrorLoggerService.logInfo('GAME_PROCESS_ENDED', {
+      component: 'GameLauncher',
+      action: 'process_ended',
+      processId: processId,
+      exitCode: exited.exit_code,
+      crashed: exited.crashed,
+    });
+
+    console.log(`[GameLauncher] Process ${processId} ended with code ${exited.exit_code} after ${exited.duration_ms} ms.`);
   }
 
   /**
@@ -392,13 +408,6 @@ class GameLauncherService {
       for (const [processId, process] of this.activeProcesses.entries()) {
         if (now - process.startTime > ZOMBIE_THRESHOLD) {
           console.warn(`[GameLauncher] Cleaning up zombie process: ${processId}`);
-          // Clear intervals
-          if (process.monitorInterval) {
-            clearInterval(process.monitorInterval);
-          }
-          if (process.cleanupTimeout) {
-            clearTimeout(process.cleanupTimeout);
-          }
           this.activeProcesses.delete(processId);
         }
       }
@@ -410,16 +419,8 @@ class GameLauncherService {
             const invokeFn = await getInvoke();
             const status = await invokeFn<{ running: boolean }>('check_game_process', { processId });
 
-            if (!status.running && process.status === 'running') {
-              // Процесс неожиданно завершился
-              process.status = 'crashed';
-              process.crashReport = {
-                exitCode: -1,
-                errorMessage: 'Process terminated unexpectedly',
-              };
-
-              await this.reportCrash(process);
-            } else if (status.running && process.status === 'starting') {
+            // Завершение обрабатывает handleProcessExit по событию game-exited
+            if (status.running && process.status === 'starting') {
               // Процесс успешно запустился
               process.status = 'running';
             }
@@ -478,20 +479,10 @@ class GameLauncherService {
       this.crashMonitoringInterval = null;
     }
 
-    // Clear all process intervals and timeouts
-    for (const [processId, process] of this.activeProcesses.entries()) {
-      // Clear monitor interval
-      if (process.monitorInterval) {
-        clearInterval(process.monitorInterval);
-      }
-
-      // Clear cleanup timeout
-      if (process.cleanupTimeout) {
-        clearTimeout(process.cleanupTimeout);
-      }
-
+    for (const processId of this.activeProcesses.keys()) {
       console.log(`[GameLauncher] Cleaned up process ${processId}`);
     }
+    this.earlyExits.clear();
 
     // Clear all processes
     this.activeProcesses.clear();
@@ -508,14 +499,6 @@ class GameLauncherService {
 
       const process = this.activeProcesses.get(processId);
       if (process) {
-        // Clear intervals
-        if (process.monitorInterval) {
-          clearInterval(process.monitorInterval);
-        }
-        if (process.cleanupTimeout) {
-          clearTimeout(process.cleanupTimeout);
-        }
-
         process.status = 'stopped';
 
         // Remove from active processes
@@ -527,16 +510,7 @@ class GameLauncherService {
       console.error(`Failed to kill process ${processId}:`, error);
 
       // Clean up from active processes even if kill failed
-      const process = this.activeProcesses.get(processId);
-      if (process) {
-        if (process.monitorInterval) {
-          clearInterval(process.monitorInterval);
-        }
-        if (process.cleanupTimeout) {
-          clearTimeout(process.cleanupTimeout);
-        }
-        this.activeProcesses.delete(processId);
-      }
+      this.activeProcesses.delete(processId);
 
       return false;
     }